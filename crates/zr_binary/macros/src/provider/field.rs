@@ -150,7 +150,7 @@ impl ToTokens for FieldProviderBinaryLen {
                 quote! {
                     ({
                         let __len = <#ty as zr_binary::binary::Binary>::binary_len(&self.#ident);
-                        let __len_as_ty: #length_ty = __len.into();
+                        let __len_as_ty: #length_ty = self.#ident.len().into();
                         <#length_ty as zr_binary::binary::Binary>::binary_len(&__len_as_ty) + __len
                     })
                 }
@@ -177,7 +177,8 @@ impl ToTokens for FieldProviderToBinary {
             FieldProviderToBinary::PrefixedLength(PrefixedLength { length_ty, ident, ty , generic: _}) => {
                 quote! {
                     {
-                        let __len: #length_ty = <#ty as zr_binary::binary::Binary>::binary_len(&self.#ident).into();
+                        // the length is a count of elements, not of bytes
+                        let __len: #length_ty = self.#ident.len().into();
                         __binary.append(&mut <#length_ty as zr_binary::binary::Binary>::to_binary(__len));
                         __binary.append(&mut <#ty as zr_binary::binary::Binary>::to_binary(self.#ident));
                     }
@@ -15,6 +15,13 @@ pub trait Binary {
         Self: Sized;
 }
 
+/// Read a `T` at `cursor` and move the cursor after it
+pub fn read<T: Binary>(bin: &[u8], cursor: &mut usize) -> Result<T> {
+    let value = T::from_binary(bin.get(*cursor..).unwrap_or_default().to_vec())?;
+    *cursor += value.binary_len();
+    Ok(value)
+}
+
 impl Binary for bool {
     fn binary_len(&self) -> usize {
         1
@@ -331,6 +338,26 @@ impl<T: Binary> Binary for Option<T> {
     }
 }
 
+/// Fixed length byte array, without length prefix
+impl<const N: usize> Binary for [u8; N] {
+    fn binary_len(&self) -> usize {
+        N
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        self.to_vec()
+    }
+
+    fn from_binary(bin: Vec<u8>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        bin.get(..N)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(BinaryError::LengthError)
+    }
+}
+
 impl Binary for String {
     fn binary_len(&self) -> usize {
         let length = VarInt::new(self.len() as i32);
@@ -370,7 +397,8 @@ impl<T: Binary + Clone> Binary for Vec<T> {
     {
         let mut cursor = 0;
         let mut vec = Vec::new();
-        while !bin.is_empty() {
+        // the vector takes the rest of the data
+        while cursor < bin.len() {
             let current = T::from_binary(bin[cursor..].to_vec())?;
             cursor += current.binary_len();
             vec.push(current);
@@ -385,14 +413,14 @@ impl Binary for Uuid {
     }
 
     fn to_binary(self) -> Vec<u8> {
-        self.to_u128_le().to_binary()
+        self.as_u128().to_binary()
     }
 
     fn from_binary(bin: Vec<u8>) -> Result<Self>
     where
         Self: Sized,
     {
-        Ok(Self::from_u128_le(u128::from_binary(bin)?))
+        Ok(Self::from_u128(u128::from_binary(bin)?))
     }
 }
 
@@ -433,3 +461,26 @@ where
         Ok(map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::Binary;
+
+    #[test]
+    fn test_uuid() {
+        let uuid = Uuid::parse_str("069a79f4-44e9-4726-a5be-fca90e38aaf5").unwrap();
+        // most significant bits first, as the client writes them
+        let bin = uuid.to_binary();
+        assert_eq!(bin, uuid.as_bytes());
+        assert_eq!(Uuid::from_binary(bin).unwrap(), uuid);
+    }
+
+    #[test]
+    fn test_vec() {
+        // the vector stops at the end of the data
+        assert_eq!(Vec::<u16>::from_binary(vec![0, 1, 0, 2]).unwrap(), [1, 2]);
+        assert!(Vec::<u16>::from_binary(Vec::new()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,459 @@
+use std::{
+    fmt::{self, Display},
+    io,
+    net::IpAddr,
+    path::PathBuf,
+};
+
+use chrono::{DateTime, FixedOffset, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    permission::{read_json, write_json},
+    settings::{ServerSettings, SETTINGS_FILE},
+};
+
+pub const BANNED_PLAYERS_FILE: &str = "banned-players.json";
+pub const BANNED_IPS_FILE: &str = "banned-ips.json";
+pub const WHITELIST_FILE: &str = "whitelist.json";
+/// Source of the bans which do not come from a player
+pub const SERVER_SOURCE: &str = "Server";
+/// Format of the dates in the ban files, as vanilla
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+/// Expiry of the permanent bans
+const FOREVER: &str = "forever";
+
+pub type Date = DateTime<FixedOffset>;
+
+pub fn now() -> Date {
+    Local::now().fixed_offset()
+}
+
+mod date {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::{Date, DATE_FORMAT};
+
+    pub fn serialize<S: Serializer>(date: &Date, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&date.format(DATE_FORMAT))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Date, D::Error> {
+        let date = String::deserialize(deserializer)?;
+        Date::parse_from_str(&date, DATE_FORMAT).map_err(D::Error::custom)
+    }
+}
+
+/// `"forever"` for the bans without expiry
+mod expiry {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use super::{Date, DATE_FORMAT, FOREVER};
+
+    pub fn serialize<S: Serializer>(date: &Option<Date>, serializer: S) -> Result<S::Ok, S::Error> {
+        match date {
+            Some(date) => serializer.collect_str(&date.format(DATE_FORMAT)),
+            None => serializer.serialize_str(FOREVER),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Date>, D::Error> {
+        match String::deserialize(deserializer)? {
+            date if date == FOREVER => Ok(None),
+            date => Date::parse_from_str(&date, DATE_FORMAT)
+                .map(Some)
+                .map_err(D::Error::custom),
+        }
+    }
+}
+
+/// Entry of `banned-players.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerBan {
+    pub uuid: Uuid,
+    pub name: String,
+    #[serde(with = "date")]
+    pub created: Date,
+    pub source: String,
+    /// `None` for a permanent ban
+    #[serde(with = "expiry")]
+    pub expires: Option<Date>,
+    pub reason: String,
+}
+
+/// Entry of `banned-ips.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IpBan {
+    pub ip: IpAddr,
+    #[serde(with = "date")]
+    pub created: Date,
+    pub source: String,
+    #[serde(with = "expiry")]
+    pub expires: Option<Date>,
+    pub reason: String,
+}
+
+/// Entry of `whitelist.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WhitelistEntry {
+    pub uuid: Uuid,
+    pub name: String,
+}
+
+/// Whether the entry is about the player, by uuid when both are known and by name otherwise
+fn is_player(entry_uuid: Uuid, entry_name: &str, uuid: Option<Uuid>, name: &str) -> bool {
+    match uuid {
+        Some(uuid) if !entry_uuid.is_nil() && !uuid.is_nil() => entry_uuid == uuid,
+        _ => entry_name.eq_ignore_ascii_case(name),
+    }
+}
+
+fn is_active(expires: Option<Date>, now: Date) -> bool {
+    expires.is_none_or(|expires| expires > now)
+}
+
+/// Why a player cannot join
+#[derive(Debug, Clone, PartialEq)]
+pub enum Denial {
+    Banned {
+        reason: String,
+        expires: Option<Date>,
+    },
+    IpBanned {
+        reason: String,
+        expires: Option<Date>,
+    },
+    NotWhitelisted,
+}
+
+impl Display for Denial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (message, reason, expires) = match self {
+            Self::Banned { reason, expires } => {
+                ("You are banned from this server.", reason, expires)
+            }
+            Self::IpBanned { reason, expires } => (
+                "Your IP address is banned from this server.",
+                reason,
+                expires,
+            ),
+            Self::NotWhitelisted => return write!(f, "You are not white-listed on this server!"),
+        };
+        write!(f, "{message}\nReason: {reason}")?;
+        if let Some(expires) = expires {
+            write!(
+                f,
+                "\nYour ban will be removed on {}",
+                expires.format(DATE_FORMAT)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Banned players, banned addresses and whitelist, checked when a player logs in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessLists {
+    /// where the lists are saved, `None` to keep them in memory
+    directory: Option<PathBuf>,
+    banned_players: Vec<PlayerBan>,
+    banned_ips: Vec<IpBan>,
+    whitelist: Vec<WhitelistEntry>,
+    /// `white-list` of `server.json`
+    whitelist_enabled: bool,
+}
+
+impl AccessLists {
+    /// Lists kept in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the lists from `directory`, missing files are empty
+    pub fn load(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let mut lists = Self {
+            directory: Some(directory.into()),
+            ..Self::default()
+        };
+        lists.reload()?;
+        Ok(lists)
+    }
+
+    /// Read the files again, the changes which were not saved are lost
+    pub fn reload(&mut self) -> io::Result<()> {
+        let Some(directory) = &self.directory else {
+            return Ok(());
+        };
+        let banned_players = read_json(&directory.join(BANNED_PLAYERS_FILE))?;
+        let banned_ips = read_json(&directory.join(BANNED_IPS_FILE))?;
+        let settings: ServerSettings = read_json(&directory.join(SETTINGS_FILE))?;
+        self.reload_whitelist()?;
+        self.banned_players = banned_players;
+        self.banned_ips = banned_ips;
+        self.whitelist_enabled = settings.white_list;
+        Ok(())
+    }
+
+    pub fn reload_whitelist(&mut self) -> io::Result<()> {
+        if let Some(directory) = &self.directory {
+            self.whitelist = read_json(&directory.join(WHITELIST_FILE))?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(directory) = &self.directory else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(directory)?;
+        write_json(&directory.join(BANNED_PLAYERS_FILE), &self.banned_players)?;
+        write_json(&directory.join(BANNED_IPS_FILE), &self.banned_ips)?;
+        write_json(&directory.join(WHITELIST_FILE), &self.whitelist)?;
+        // the other settings are kept as they are
+        let settings_file = directory.join(SETTINGS_FILE);
+        let mut settings: ServerSettings = read_json(&settings_file)?;
+        settings.white_list = self.whitelist_enabled;
+        write_json(&settings_file, &settings)
+    }
+
+    pub fn banned_players(&self) -> &[PlayerBan] {
+        &self.banned_players
+    }
+
+    pub fn banned_ips(&self) -> &[IpBan] {
+        &self.banned_ips
+    }
+
+    /// Active ban of the player
+    pub fn player_ban(&self, name: &str, uuid: Option<Uuid>, now: Date) -> Option<&PlayerBan> {
+        self.banned_players
+            .iter()
+            .find(|ban| is_player(ban.uuid, &ban.name, uuid, name) && is_active(ban.expires, now))
+    }
+
+    /// Active ban of the address
+    pub fn ip_ban(&self, ip: IpAddr, now: Date) -> Option<&IpBan> {
+        self.banned_ips
+            .iter()
+            .find(|ban| ban.ip == ip && is_active(ban.expires, now))
+    }
+
+    /// `false` if the player was already banned, an expired ban is replaced
+    pub fn ban(&mut self, ban: PlayerBan) -> bool {
+        if self
+            .player_ban(&ban.name, Some(ban.uuid), ban.created)
+            .is_some()
+        {
+            return false;
+        }
+        self.banned_players
+            .retain(|old| !is_player(old.uuid, &old.name, Some(ban.uuid), &ban.name));
+        self.banned_players.push(ban);
+        true
+    }
+
+    /// `false` if the player was not banned
+    pub fn pardon(&mut self, name: &str) -> bool {
+        let count = self.banned_players.len();
+        self.banned_players
+            .retain(|ban| !ban.name.eq_ignore_ascii_case(name));
+        self.banned_players.len() != count
+    }
+
+    /// `false` if the address was already banned, an expired ban is replaced
+    pub fn ban_ip(&mut self, ban: IpBan) -> bool {
+        if self.ip_ban(ban.ip, ban.created).is_some() {
+            return false;
+        }
+        self.banned_ips.retain(|old| old.ip != ban.ip);
+        self.banned_ips.push(ban);
+        true
+    }
+
+    /// `false` if the address was not banned
+    pub fn pardon_ip(&mut self, ip: IpAddr) -> bool {
+        let count = self.banned_ips.len();
+        self.banned_ips.retain(|ban| ban.ip != ip);
+        self.banned_ips.len() != count
+    }
+
+    pub fn whitelist(&self) -> &[WhitelistEntry] {
+        &self.whitelist
+    }
+
+    pub fn is_whitelist_enabled(&self) -> bool {
+        self.whitelist_enabled
+    }
+
+    pub fn set_whitelist_enabled(&mut self, enabled: bool) {
+        self.whitelist_enabled = enabled;
+    }
+
+    pub fn is_whitelisted(&self, name: &str, uuid: Option<Uuid>) -> bool {
+        self.whitelist
+            .iter()
+            .any(|entry| is_player(entry.uuid, &entry.name, uuid, name))
+    }
+
+    /// `false` if the player was already whitelisted
+    pub fn whitelist_add(&mut self, name: &str, uuid: Option<Uuid>) -> bool {
+        if self.is_whitelisted(name, uuid) {
+            return false;
+        }
+        self.whitelist.push(WhitelistEntry {
+            uuid: uuid.unwrap_or_default(),
+            name: name.to_string(),
+        });
+        true
+    }
+
+    /// `false` if the player was not whitelisted
+    pub fn whitelist_remove(&mut self, name: &str) -> bool {
+        let count = self.whitelist.len();
+        self.whitelist
+            .retain(|entry| !entry.name.eq_ignore_ascii_case(name));
+        self.whitelist.len() != count
+    }
+
+    /// Whether the player may join from `ip`
+    pub fn check(
+        &self,
+        name: &str,
+        uuid: Option<Uuid>,
+        ip: Option<IpAddr>,
+        now: Date,
+    ) -> Result<(), Denial> {
+        if let Some(ban) = self.player_ban(name, uuid, now) {
+            return Err(Denial::Banned {
+                reason: ban.reason.clone(),
+                expires: ban.expires,
+            });
+        }
+        if let Some(ban) = ip.and_then(|ip| self.ip_ban(ip, now)) {
+            return Err(Denial::IpBanned {
+                reason: ban.reason.clone(),
+                expires: ban.expires,
+            });
+        }
+        if self.whitelist_enabled && !self.is_whitelisted(name, uuid) {
+            return Err(Denial::NotWhitelisted);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, net::IpAddr};
+
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    use super::{now, AccessLists, Denial, IpBan, PlayerBan, BANNED_PLAYERS_FILE, SERVER_SOURCE};
+
+    fn player_ban(name: &str, uuid: Uuid, expires: Option<Duration>) -> PlayerBan {
+        let created = now();
+        PlayerBan {
+            uuid,
+            name: name.to_string(),
+            created,
+            source: SERVER_SOURCE.to_string(),
+            expires: expires.map(|duration| created + duration),
+            reason: "Griefing".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_bans() {
+        let mut lists = AccessLists::new();
+        let uuid = Uuid::new_v4();
+        assert!(lists.ban(player_ban("Alice", uuid, None)));
+        assert!(!lists.ban(player_ban("alice", uuid, None)));
+        // the uuid wins over the name once known
+        assert!(lists
+            .check("Alice", Some(Uuid::new_v4()), None, now())
+            .is_ok());
+        let denial = lists.check("Renamed", Some(uuid), None, now()).unwrap_err();
+        assert_eq!(
+            denial.to_string(),
+            "You are banned from this server.\nReason: Griefing"
+        );
+        assert!(lists.pardon("ALICE"));
+        assert!(lists.check("Alice", Some(uuid), None, now()).is_ok());
+
+        lists.ban(player_ban("Bob", Uuid::nil(), Some(Duration::hours(1))));
+        assert!(matches!(
+            lists.check("Bob", Some(Uuid::new_v4()), None, now()),
+            Err(Denial::Banned {
+                expires: Some(_),
+                ..
+            })
+        ));
+        assert!(lists
+            .check("Bob", None, None, now() + Duration::hours(2))
+            .is_ok());
+
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        lists.ban_ip(IpBan {
+            ip,
+            created: now(),
+            source: SERVER_SOURCE.to_string(),
+            expires: None,
+            reason: "Spam".to_string(),
+        });
+        assert!(matches!(
+            lists.check("Carol", None, Some(ip), now()),
+            Err(Denial::IpBanned { .. })
+        ));
+        assert!(lists.pardon_ip(ip));
+        assert!(lists.check("Carol", None, Some(ip), now()).is_ok());
+    }
+
+    #[test]
+    fn test_whitelist() {
+        let mut lists = AccessLists::new();
+        assert!(lists.check("Alice", None, None, now()).is_ok());
+        lists.set_whitelist_enabled(true);
+        assert_eq!(
+            lists.check("Alice", None, None, now()),
+            Err(Denial::NotWhitelisted)
+        );
+        assert!(lists.whitelist_add("Alice", None));
+        assert!(!lists.whitelist_add("alice", None));
+        assert!(lists
+            .check("Alice", Some(Uuid::new_v4()), None, now())
+            .is_ok());
+        assert!(lists.whitelist_remove("Alice"));
+        assert!(!lists.whitelist_remove("Alice"));
+    }
+
+    #[test]
+    fn test_persistence() {
+        let directory = std::env::temp_dir().join(format!("zr_access_{}", Uuid::new_v4()));
+        let mut lists = AccessLists::load(&directory).unwrap();
+        lists.ban(player_ban("Alice", Uuid::new_v4(), None));
+        lists.ban(player_ban("Bob", Uuid::new_v4(), Some(Duration::days(1))));
+        lists.whitelist_add("Carol", Some(Uuid::new_v4()));
+        lists.set_whitelist_enabled(true);
+        lists.save().unwrap();
+
+        let banned = fs::read_to_string(directory.join(BANNED_PLAYERS_FILE)).unwrap();
+        assert!(banned.contains("\"expires\": \"forever\""));
+        let mut loaded = AccessLists::load(&directory).unwrap();
+        // the dates are saved to the second
+        assert_eq!(loaded.banned_players().len(), 2);
+        assert_eq!(loaded.banned_players()[0].name, "Alice");
+        assert_eq!(loaded.banned_players()[0].expires, None);
+        assert!(loaded.banned_players()[1].expires.is_some());
+        assert!(loaded.is_whitelisted("Carol", None));
+        assert!(loaded.is_whitelist_enabled());
+
+        fs::write(directory.join(BANNED_PLAYERS_FILE), "[]").unwrap();
+        loaded.reload().unwrap();
+        assert!(loaded.banned_players().is_empty());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,734 @@
+use std::fmt::Display;
+
+use uuid::Uuid;
+use zr_binary::{binary::Binary, varint::VarInt};
+use zr_data_type::{string::Identifier, vector::Vector3};
+use zr_registry::item::Item;
+
+use super::{reader::StringReader, CommandError};
+use crate::{entity::player::GameMode, item::ItemStack};
+
+/// How much of the input a string argument takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringKind {
+    /// A single word
+    Word,
+    /// A word or a quoted string
+    Quotable,
+    /// The rest of the input
+    Greedy,
+}
+
+/// Parser of an argument node, the client uses the same parsers to highlight the input
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgumentType {
+    Bool,
+    Float {
+        min: Option<f32>,
+        max: Option<f32>,
+    },
+    Double {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    Integer {
+        min: Option<i32>,
+        max: Option<i32>,
+    },
+    Long {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    String(StringKind),
+    /// A player name, a UUID or a target selector
+    Entity {
+        single: bool,
+        players_only: bool,
+    },
+    /// A player name, a UUID or a player selector, the player may be offline
+    GameProfile,
+    BlockPos,
+    Vec3,
+    /// An item id, e.g. `minecraft:stone`, gives a stack of one
+    ItemStack,
+    ResourceLocation,
+    /// The rest of the input, selectors included
+    Message,
+    GameMode,
+    /// A duration in ticks, with an optional `d`, `s` or `t` unit
+    Time,
+}
+
+/// Parsed value of an argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum Argument {
+    Bool(bool),
+    Float(f32),
+    Double(f64),
+    Integer(i32),
+    Long(i64),
+    String(String),
+    Entity(EntitySelector),
+    BlockPos(Coordinates),
+    Vec3(Coordinates),
+    ItemStack(ItemStack),
+    ResourceLocation(Identifier),
+    GameMode(GameMode),
+    /// in ticks
+    Time(i32),
+}
+
+/// Target of a selector, the letter after `@`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorTarget {
+    NearestPlayer,
+    AllPlayers,
+    RandomPlayer,
+    Executor,
+    AllEntities,
+    NearestEntity,
+}
+
+impl SelectorTarget {
+    pub const ALL: [SelectorTarget; 6] = [
+        Self::AllPlayers,
+        Self::AllEntities,
+        Self::NearestEntity,
+        Self::NearestPlayer,
+        Self::RandomPlayer,
+        Self::Executor,
+    ];
+
+    pub fn letter(&self) -> char {
+        match self {
+            Self::NearestPlayer => 'p',
+            Self::AllPlayers => 'a',
+            Self::RandomPlayer => 'r',
+            Self::Executor => 's',
+            Self::AllEntities => 'e',
+            Self::NearestEntity => 'n',
+        }
+    }
+
+    fn from_letter(letter: char) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|target| target.letter() == letter)
+    }
+
+    /// May match more than one entity
+    pub fn is_multiple(&self) -> bool {
+        matches!(self, Self::AllPlayers | Self::AllEntities)
+    }
+
+    /// May match entities which are not players,
+    /// `@s` is left to the command as it depends on its source
+    pub fn allows_entities(&self) -> bool {
+        matches!(self, Self::AllEntities | Self::NearestEntity)
+    }
+}
+
+/// Entities targeted by a command, resolved by the command against the server state
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntitySelector {
+    Player(String),
+    Uuid(Uuid),
+    Selector {
+        target: SelectorTarget,
+        /// `key=value` pairs between brackets, values are kept as typed
+        arguments: Vec<(String, String)>,
+    },
+}
+
+impl EntitySelector {
+    /// Value of the selector argument `key`
+    pub fn argument(&self, key: &str) -> Option<&str> {
+        match self {
+            Self::Selector { arguments, .. } => arguments
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
+
+    fn is_multiple(&self) -> bool {
+        match self {
+            Self::Selector { target, .. } => {
+                target.is_multiple() && self.argument("limit") != Some("1")
+            }
+            _ => false,
+        }
+    }
+
+    fn allows_entities(&self) -> bool {
+        match self {
+            Self::Selector { target, .. } => {
+                target.allows_entities()
+                    && !matches!(self.argument("type"), Some("player" | "minecraft:player"))
+            }
+            Self::Uuid(_) => true,
+            Self::Player(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coordinate {
+    Absolute(f64),
+    /// `~`, from the source position
+    Relative(f64),
+    /// `^`, along the source rotation
+    Local(f64),
+}
+
+impl Coordinate {
+    fn value(&self) -> f64 {
+        match self {
+            Self::Absolute(value) | Self::Relative(value) | Self::Local(value) => *value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates(pub [Coordinate; 3]);
+
+impl Coordinates {
+    /// Position from the source `origin` and `rotation` (yaw, pitch in degrees)
+    pub fn resolve(&self, origin: Vector3<f64>, rotation: (f32, f32)) -> Vector3<f64> {
+        let [x, y, z] = self.0;
+        if let Coordinate::Local(_) = x {
+            let (yaw, pitch) = (
+                (rotation.0 as f64 + 90.0).to_radians(),
+                (-rotation.1 as f64).to_radians(),
+            );
+            let (up_yaw, up_pitch) = (yaw, (-rotation.1 as f64 + 90.0).to_radians());
+            let forward = Vector3::new(
+                yaw.cos() * pitch.cos(),
+                pitch.sin(),
+                yaw.sin() * pitch.cos(),
+            );
+            let up = Vector3::new(
+                up_yaw.cos() * up_pitch.cos(),
+                up_pitch.sin(),
+                up_yaw.sin() * up_pitch.cos(),
+            );
+            // left = up x forward
+            let left = Vector3::new(
+                up.y * forward.z - up.z * forward.y,
+                up.z * forward.x - up.x * forward.z,
+                up.x * forward.y - up.y * forward.x,
+            );
+            let (l, u, f) = (x.value(), y.value(), z.value());
+            return Vector3::new(
+                origin.x + left.x * l + up.x * u + forward.x * f,
+                origin.y + left.y * l + up.y * u + forward.y * f,
+                origin.z + left.z * l + up.z * u + forward.z * f,
+            );
+        }
+        let resolve = |coordinate: Coordinate, origin: f64| match coordinate {
+            Coordinate::Absolute(value) => value,
+            Coordinate::Relative(value) | Coordinate::Local(value) => origin + value,
+        };
+        Vector3::new(
+            resolve(x, origin.x),
+            resolve(y, origin.y),
+            resolve(z, origin.z),
+        )
+    }
+
+    /// Block containing the resolved position
+    pub fn resolve_block(&self, origin: Vector3<f64>, rotation: (f32, f32)) -> Vector3<i32> {
+        self.resolve(origin, rotation).map(|c| c.floor() as i32)
+    }
+}
+
+fn check_range<T: PartialOrd + Display + Copy>(
+    reader: &StringReader,
+    start: usize,
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<T, CommandError> {
+    let error = |message: String| CommandError::syntax(message, reader.input(), start);
+    match (min, max) {
+        (Some(min), _) if value < min => Err(error(format!(
+            "Value must not be less than {min}, found {value}"
+        ))),
+        (_, Some(max)) if value > max => Err(error(format!(
+            "Value must not be more than {max}, found {value}"
+        ))),
+        _ => Ok(value),
+    }
+}
+
+fn read_coordinate(
+    reader: &mut StringReader,
+    integer: bool,
+    center: bool,
+) -> Result<Coordinate, CommandError> {
+    let start = reader.cursor();
+    let prefix = reader.peek();
+    if let Some('~' | '^') = prefix {
+        reader.skip();
+    }
+    let word = reader.read_word();
+    let value = if word.is_empty() && prefix.is_some_and(|c| c == '~' || c == '^') {
+        0.0
+    } else if integer && prefix.is_some_and(|c| c != '~' && c != '^') {
+        word.parse::<i32>()
+            .map(|value| value as f64)
+            .map_err(|_| CommandError::syntax("Expected integer", reader.input(), start))?
+    } else {
+        let value = word
+            .parse::<f64>()
+            .map_err(|_| CommandError::syntax("Expected double", reader.input(), start))?;
+        // `1` is the center of the block, `1.0` is its corner
+        if center && !word.contains('.') && prefix.is_some_and(|c| c != '~' && c != '^') {
+            value + 0.5
+        } else {
+            value
+        }
+    };
+    Ok(match prefix {
+        Some('~') => Coordinate::Relative(value),
+        Some('^') => Coordinate::Local(value),
+        _ => Coordinate::Absolute(value),
+    })
+}
+
+fn read_coordinates(reader: &mut StringReader, integer: bool) -> Result<Coordinates, CommandError> {
+    let start = reader.cursor();
+    let mut coordinates = [Coordinate::Absolute(0.0); 3];
+    for (i, coordinate) in coordinates.iter_mut().enumerate() {
+        if i > 0 {
+            if reader.peek() != Some(' ') {
+                return Err(CommandError::syntax(
+                    "Incomplete (expected 3 coordinates)",
+                    reader.input(),
+                    start,
+                ));
+            }
+            reader.skip();
+        }
+        // y is never centered
+        *coordinate = read_coordinate(reader, integer, !integer && i != 1)?;
+    }
+    let local = coordinates
+        .iter()
+        .filter(|c| matches!(c, Coordinate::Local(_)))
+        .count();
+    if local != 0 && local != 3 {
+        return Err(CommandError::syntax(
+            "Cannot mix world & local coordinates (everything must either use ^ or not)",
+            reader.input(),
+            start,
+        ));
+    }
+    Ok(Coordinates(coordinates))
+}
+
+fn read_selector(reader: &mut StringReader) -> Result<EntitySelector, CommandError> {
+    let start = reader.cursor();
+    if reader.peek() != Some('@') {
+        let word = reader.read_word();
+        return Ok(match Uuid::parse_str(word) {
+            Ok(uuid) if word.contains('-') => EntitySelector::Uuid(uuid),
+            _ if word.is_empty() || word.len() > 16 => {
+                return Err(CommandError::syntax(
+                    "Invalid name or UUID",
+                    reader.input(),
+                    start,
+                ))
+            }
+            _ => EntitySelector::Player(word.to_string()),
+        });
+    }
+    reader.skip();
+    let target = reader
+        .peek()
+        .and_then(SelectorTarget::from_letter)
+        .ok_or_else(|| reader.error("Unknown selector type"))?;
+    reader.skip();
+    let mut arguments = Vec::new();
+    if reader.peek() == Some('[') {
+        reader.skip();
+        loop {
+            reader.skip_whitespace();
+            if reader.peek() == Some(']') {
+                reader.skip();
+                break;
+            }
+            let key_start = reader.cursor();
+            while reader.peek().is_some_and(|c| !"=,] ".contains(c)) {
+                reader.skip();
+            }
+            let key = reader.input()[key_start..reader.cursor()].to_string();
+            reader.skip_whitespace();
+            if reader.peek() != Some('=') {
+                return Err(reader.error(format!("Expected value for option '{key}'")));
+            }
+            reader.skip();
+            reader.skip_whitespace();
+            let value = if let Some('"' | '\'') = reader.peek() {
+                reader.read_string()?
+            } else {
+                let value_start = reader.cursor();
+                while reader.peek().is_some_and(|c| !",]".contains(c)) {
+                    reader.skip();
+                }
+                reader.input()[value_start..reader.cursor()]
+                    .trim()
+                    .to_string()
+            };
+            arguments.push((key, value));
+            reader.skip_whitespace();
+            match reader.peek() {
+                Some(',') => reader.skip(),
+                Some(']') => {}
+                _ => return Err(reader.error("Expected end of options")),
+            }
+        }
+    }
+    Ok(EntitySelector::Selector { target, arguments })
+}
+
+impl ArgumentType {
+    pub fn parse(&self, reader: &mut StringReader) -> Result<Argument, CommandError> {
+        let start = reader.cursor();
+        Ok(match self {
+            Self::Bool => Argument::Bool(reader.read_parsed("bool")?),
+            Self::Float { min, max } => {
+                let value = reader.read_parsed("float")?;
+                Argument::Float(check_range(reader, start, value, *min, *max)?)
+            }
+            Self::Double { min, max } => {
+                let value = reader.read_parsed("double")?;
+                Argument::Double(check_range(reader, start, value, *min, *max)?)
+            }
+            Self::Integer { min, max } => {
+                let value = reader.read_parsed("integer")?;
+                Argument::Integer(check_range(reader, start, value, *min, *max)?)
+            }
+            Self::Long { min, max } => {
+                let value = reader.read_parsed("long")?;
+                Argument::Long(check_range(reader, start, value, *min, *max)?)
+            }
+            Self::String(StringKind::Word) => Argument::String(reader.read_word().to_string()),
+            Self::String(StringKind::Quotable) => Argument::String(reader.read_string()?),
+            Self::String(StringKind::Greedy) | Self::Message => {
+                Argument::String(reader.read_remaining().to_string())
+            }
+            Self::Entity {
+                single,
+                players_only,
+            } => {
+                let selector = read_selector(reader)?;
+                let error = |message| Err(CommandError::syntax(message, reader.input(), start));
+                if *single && selector.is_multiple() {
+                    return error(
+                        "Only one entity is allowed, but the provided selector allows more than one",
+                    );
+                }
+                if *players_only && selector.allows_entities() {
+                    return error(
+                        "Only players may be affected by this command, but the provided selector includes entities",
+                    );
+                }
+                Argument::Entity(selector)
+            }
+            Self::GameProfile => Argument::Entity(read_selector(reader)?),
+            Self::BlockPos => Argument::BlockPos(read_coordinates(reader, true)?),
+            Self::Vec3 => Argument::Vec3(read_coordinates(reader, false)?),
+            Self::ItemStack => {
+                let word = reader.read_word();
+                // the components between brackets are not parsed yet
+                let (id, components) = match word.split_once('[') {
+                    Some((id, _)) => (id, true),
+                    None => (word, false),
+                };
+                let id: Identifier = id
+                    .parse()
+                    .map_err(|_| CommandError::syntax("Invalid ID", reader.input(), start))?;
+                let item = Item::from_identifier(&id).ok_or_else(|| {
+                    CommandError::syntax(
+                        format!("Unknown item '{}'", id.to_string()),
+                        reader.input(),
+                        start,
+                    )
+                })?;
+                if components {
+                    return Err(CommandError::syntax(
+                        "Item components are not supported",
+                        reader.input(),
+                        start + word.find('[').unwrap_or_default(),
+                    ));
+                }
+                Argument::ItemStack(ItemStack::new(item, 1))
+            }
+            Self::ResourceLocation => {
+                let word = reader.read_word();
+                Argument::ResourceLocation(
+                    word.parse()
+                        .map_err(|_| CommandError::syntax("Invalid ID", reader.input(), start))?,
+                )
+            }
+            Self::GameMode => {
+                let word = reader.read_word();
+                Argument::GameMode(word.parse().map_err(|_| {
+                    CommandError::syntax(
+                        format!("Unknown game mode: {word}"),
+                        reader.input(),
+                        start,
+                    )
+                })?)
+            }
+            Self::Time => {
+                let word = reader.read_word();
+                let (number, unit) = match word.char_indices().last() {
+                    Some((i, unit @ ('d' | 's' | 't'))) => (&word[..i], unit),
+                    _ => (word, 't'),
+                };
+                let value = number
+                    .parse::<f32>()
+                    .map_err(|_| CommandError::syntax("Expected float", reader.input(), start))?;
+                let scale = match unit {
+                    'd' => 24000.0,
+                    's' => 20.0,
+                    _ => 1.0,
+                };
+                let ticks = (value * scale).round() as i32;
+                Argument::Time(check_range(reader, start, ticks, Some(0), None)?)
+            }
+        })
+    }
+
+    /// Suggestions of the type itself, for the partial argument `input`
+    pub fn suggestions(&self, input: &str) -> Vec<String> {
+        let candidates: Vec<String> = match self {
+            Self::Bool => vec!["true".to_string(), "false".to_string()],
+            Self::GameMode => GameMode::ALL
+                .iter()
+                .map(|mode| mode.name().to_string())
+                .collect(),
+            Self::Entity { .. } | Self::GameProfile => SelectorTarget::ALL
+                .iter()
+                .map(|target| format!("@{}", target.letter()))
+                .collect(),
+            Self::BlockPos | Self::Vec3 => {
+                vec!["~".to_string(), "~ ~".to_string(), "~ ~ ~".to_string()]
+            }
+            Self::ItemStack => Item::ALL
+                .iter()
+                .filter(|item| !item.is_air())
+                .map(|item| item.name().to_string())
+                .collect(),
+            _ => Vec::new(),
+        };
+        candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(input))
+            .collect()
+    }
+
+    /// Id of the parser in the `minecraft:command_argument_type` registry
+    pub fn parser_id(&self) -> i32 {
+        match self {
+            Self::Bool => 0,
+            Self::Float { .. } => 1,
+            Self::Double { .. } => 2,
+            Self::Integer { .. } => 3,
+            Self::Long { .. } => 4,
+            Self::String(_) => 5,
+            Self::Entity { .. } => 6,
+            Self::GameProfile => 7,
+            Self::BlockPos => 8,
+            Self::Vec3 => 10,
+            Self::ItemStack => 14,
+            Self::Message => 19,
+            Self::ResourceLocation => 35,
+            Self::GameMode => 41,
+            Self::Time => 42,
+        }
+    }
+
+    /// Properties of the parser, sent after its id
+    pub fn properties(&self) -> Vec<u8> {
+        fn range<T: Binary>(min: Option<T>, max: Option<T>) -> Vec<u8> {
+            let flags = min.is_some() as u8 | (max.is_some() as u8) << 1;
+            let mut bin = vec![flags];
+            bin.extend(min.to_binary());
+            bin.extend(max.to_binary());
+            bin
+        }
+        match self {
+            Self::Float { min, max } => range(*min, *max),
+            Self::Double { min, max } => range(*min, *max),
+            Self::Integer { min, max } => range(*min, *max),
+            Self::Long { min, max } => range(*min, *max),
+            Self::String(kind) => VarInt::new(*kind as i32).to_binary(),
+            Self::Entity {
+                single,
+                players_only,
+            } => vec![*single as u8 | (*players_only as u8) << 1],
+            Self::Time => 0_i32.to_binary(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_data_type::vector::Vector3;
+    use zr_registry::item::Item;
+
+    use super::{
+        Argument, ArgumentType, Coordinate, Coordinates, EntitySelector, SelectorTarget, StringKind,
+    };
+    use crate::{command::reader::StringReader, item::ItemStack};
+
+    fn parse(argument_type: ArgumentType, input: &str) -> Result<Argument, String> {
+        argument_type
+            .parse(&mut StringReader::new(input))
+            .map_err(|err| err.message)
+    }
+
+    #[test]
+    fn test_numbers() {
+        let percent = ArgumentType::Integer {
+            min: Some(0),
+            max: Some(100),
+        };
+        assert_eq!(parse(percent.clone(), "42"), Ok(Argument::Integer(42)));
+        assert_eq!(
+            parse(percent, "101"),
+            Err("Value must not be more than 100, found 101".to_string())
+        );
+        assert_eq!(parse(ArgumentType::Time, "1.5s"), Ok(Argument::Time(30)));
+        assert_eq!(parse(ArgumentType::Time, "2d"), Ok(Argument::Time(48000)));
+        assert!(parse(ArgumentType::Bool, "yes").is_err());
+    }
+
+    #[test]
+    fn test_strings() {
+        let mut reader = StringReader::new("hello world");
+        assert_eq!(
+            ArgumentType::String(StringKind::Word).parse(&mut reader),
+            Ok(Argument::String("hello".to_string()))
+        );
+        reader.skip();
+        assert_eq!(
+            ArgumentType::String(StringKind::Greedy).parse(&mut reader),
+            Ok(Argument::String("world".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_selectors() {
+        let single_player = ArgumentType::Entity {
+            single: true,
+            players_only: true,
+        };
+        assert_eq!(
+            parse(single_player.clone(), "Notch"),
+            Ok(Argument::Entity(EntitySelector::Player(
+                "Notch".to_string()
+            )))
+        );
+        assert!(parse(single_player.clone(), "@a").is_err());
+        assert!(parse(single_player.clone(), "@e[limit=1]").is_err());
+        assert!(parse(single_player.clone(), "@e[type=player, limit=1]").is_ok());
+
+        let Ok(Argument::Entity(selector)) = parse(
+            ArgumentType::Entity {
+                single: false,
+                players_only: false,
+            },
+            "@e[type=pig,name=\"a, b\"]",
+        ) else {
+            panic!("selector not parsed");
+        };
+        assert!(matches!(
+            selector,
+            EntitySelector::Selector {
+                target: SelectorTarget::AllEntities,
+                ..
+            }
+        ));
+        assert_eq!(selector.argument("name"), Some("a, b"));
+        assert_eq!(selector.argument("type"), Some("pig"));
+    }
+
+    #[test]
+    fn test_coordinates() {
+        let Ok(Argument::BlockPos(position)) = parse(ArgumentType::BlockPos, "~1 64 ~") else {
+            panic!("block position not parsed");
+        };
+        assert_eq!(
+            position,
+            Coordinates([
+                Coordinate::Relative(1.0),
+                Coordinate::Absolute(64.0),
+                Coordinate::Relative(0.0),
+            ])
+        );
+        assert_eq!(
+            position.resolve_block(Vector3::new(10.5, 70.0, -3.2), (0.0, 0.0)),
+            Vector3::new(11, 64, -4)
+        );
+        assert!(parse(ArgumentType::BlockPos, "1.5 2 3").is_err());
+        assert!(parse(ArgumentType::BlockPos, "^ ~ ^").is_err());
+        assert!(parse(ArgumentType::BlockPos, "1 2").is_err());
+
+        let Ok(Argument::Vec3(position)) = parse(ArgumentType::Vec3, "^ ^ ^2") else {
+            panic!("position not parsed");
+        };
+        // facing south
+        let resolved = position.resolve(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0));
+        assert!(resolved.x.abs() < 1e-9 && resolved.y.abs() < 1e-9);
+        assert!((resolved.z - 2.0).abs() < 1e-9);
+        let Ok(Argument::Vec3(position)) = parse(ArgumentType::Vec3, "1 2 3.0") else {
+            panic!("position not parsed");
+        };
+        assert_eq!(
+            position.resolve(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0)),
+            Vector3::new(1.5, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn test_item_stack() {
+        let stone = Ok(Argument::ItemStack(ItemStack::new(Item::Stone, 1)));
+        assert_eq!(parse(ArgumentType::ItemStack, "stone"), stone);
+        assert_eq!(parse(ArgumentType::ItemStack, "minecraft:stone"), stone);
+        assert_eq!(
+            parse(ArgumentType::ItemStack, "minecraft:nope"),
+            Err("Unknown item 'minecraft:nope'".to_string())
+        );
+        assert!(parse(ArgumentType::ItemStack, "stone[custom_name='a']").is_err());
+        assert!(ArgumentType::ItemStack
+            .suggestions("minecraft:sto")
+            .contains(&"minecraft:stone".to_string()));
+    }
+
+    #[test]
+    fn test_properties() {
+        let range = ArgumentType::Integer {
+            min: Some(1),
+            max: None,
+        };
+        assert_eq!(range.properties(), vec![0x01, 0, 0, 0, 1]);
+        assert_eq!(
+            ArgumentType::String(StringKind::Greedy).properties(),
+            vec![2]
+        );
+        assert_eq!(
+            ArgumentType::Entity {
+                single: true,
+                players_only: true
+            }
+            .properties(),
+            vec![0x03]
+        );
+    }
+}
@@ -0,0 +1,578 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use zr_data_type::string::Identifier;
+
+use super::{
+    node::{flags, CommandBuilder, Executor, NodeData, NodeKind, Requirement},
+    reader::StringReader,
+    Argument, ArgumentType, CommandContext, CommandError, CommandHandler, CommandSource,
+};
+
+const ROOT: usize = 0;
+const UNKNOWN_COMMAND: &str = "Unknown or incomplete command, see below for error";
+const TRAILING_DATA: &str = "Expected whitespace to end one argument, but found trailing data";
+
+/// Replacements for the input from `start`, as sent in Command Suggestions Response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Suggestions {
+    pub start: usize,
+    pub matches: Vec<String>,
+}
+
+struct Node {
+    kind: NodeKind,
+    executor: Option<Executor>,
+    requirement: Option<Requirement>,
+    redirect: Option<Vec<String>>,
+    children: Vec<usize>,
+}
+
+impl Node {
+    fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            executor: None,
+            requirement: None,
+            redirect: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn is_literal(&self) -> bool {
+        matches!(self.kind, NodeKind::Literal(_))
+    }
+}
+
+/// Tree of the registered commands, parse and run the command lines against it
+pub struct Dispatcher {
+    nodes: Vec<Node>,
+}
+
+impl Default for Dispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dispatcher {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::new(NodeKind::Root)],
+        }
+    }
+
+    /// Add a command, merged with the nodes of the same name already registered
+    pub fn register(&mut self, command: CommandBuilder) -> &mut Self {
+        self.insert(ROOT, command);
+        self
+    }
+
+    fn insert(&mut self, parent: usize, builder: CommandBuilder) {
+        let existing = self.nodes[parent].children.iter().copied().find(|&child| {
+            let kind = &self.nodes[child].kind;
+            kind.name() == builder.kind.name()
+                && matches!(kind, NodeKind::Literal(_))
+                    == matches!(builder.kind, NodeKind::Literal(_))
+        });
+        let index = match existing {
+            Some(index) => {
+                self.nodes[index].kind = builder.kind;
+                index
+            }
+            None => {
+                self.nodes.push(Node::new(builder.kind));
+                let index = self.nodes.len() - 1;
+                self.nodes[parent].children.push(index);
+                index
+            }
+        };
+        let node = &mut self.nodes[index];
+        if builder.executor.is_some() {
+            node.executor = builder.executor;
+        }
+        if builder.requirement.is_some() {
+            node.requirement = builder.requirement;
+        }
+        if builder.redirect.is_some() {
+            node.redirect = builder.redirect;
+        }
+        for child in builder.children {
+            self.insert(index, child);
+        }
+    }
+
+    /// Node at the end of `path`, from the root
+    fn find(&self, path: &[String]) -> Option<usize> {
+        path.iter().try_fold(ROOT, |node, name| {
+            self.nodes[node]
+                .children
+                .iter()
+                .copied()
+                .find(|&child| self.nodes[child].kind.name() == name)
+        })
+    }
+
+    fn redirect(&self, node: usize) -> Option<usize> {
+        self.nodes[node]
+            .redirect
+            .as_ref()
+            .and_then(|path| self.find(path))
+    }
+
+    fn can_use(&self, node: usize, source: &dyn CommandSource) -> bool {
+        self.nodes[node]
+            .requirement
+            .as_ref()
+            .is_none_or(|requirement| requirement(source))
+    }
+
+    /// Children to parse after `node`, literals first
+    fn children(&self, node: usize, source: &dyn CommandSource) -> Vec<usize> {
+        let node = self.redirect(node).unwrap_or(node);
+        let mut children: Vec<usize> = self.nodes[node]
+            .children
+            .iter()
+            .copied()
+            .filter(|&child| self.can_use(child, source))
+            .collect();
+        children.sort_by_key(|&child| !self.nodes[child].is_literal());
+        children
+    }
+
+    /// Read the token of `node`, `None` for a literal
+    fn parse_node(
+        &self,
+        node: usize,
+        reader: &mut StringReader,
+    ) -> Result<Option<Argument>, CommandError> {
+        let start = reader.cursor();
+        match &self.nodes[node].kind {
+            NodeKind::Root => Ok(None),
+            NodeKind::Literal(name) => {
+                if reader.read_word() == name {
+                    Ok(None)
+                } else {
+                    reader.set_cursor(start);
+                    Err(reader.error(UNKNOWN_COMMAND))
+                }
+            }
+            NodeKind::Argument { argument_type, .. } => {
+                let argument = argument_type.parse(reader)?;
+                if reader.can_read() && reader.peek() != Some(' ') {
+                    return Err(reader.error(TRAILING_DATA));
+                }
+                Ok(Some(argument))
+            }
+        }
+    }
+
+    /// Parse the rest of the input from the children of `node`,
+    /// returns the last node and the arguments
+    fn parse_children(
+        &self,
+        node: usize,
+        reader: &StringReader,
+        source: &dyn CommandSource,
+        arguments: &HashMap<String, Argument>,
+    ) -> Result<(usize, HashMap<String, Argument>), CommandError> {
+        let mut error: Option<CommandError> = None;
+        for child in self.children(node, source) {
+            let mut reader = reader.clone();
+            let parsed = self.parse_node(child, &mut reader).and_then(|argument| {
+                let mut arguments = arguments.clone();
+                if let Some(argument) = argument {
+                    arguments.insert(self.nodes[child].kind.name().to_string(), argument);
+                }
+                if !reader.can_read() {
+                    return Ok((child, arguments));
+                }
+                reader.skip();
+                if !reader.can_read() {
+                    return Err(reader.error(UNKNOWN_COMMAND));
+                }
+                self.parse_children(child, &reader, source, &arguments)
+            });
+            match parsed {
+                Ok(parsed) => return Ok(parsed),
+                // keep the error which went the furthest, an argument error
+                // is more telling than a mismatched literal at the same place
+                Err(err)
+                    if error.as_ref().is_none_or(|error| {
+                        err.cursor > error.cursor
+                            || (err.cursor == error.cursor && error.message == UNKNOWN_COMMAND)
+                    }) =>
+                {
+                    error = Some(err)
+                }
+                Err(_) => {}
+            }
+        }
+        Err(error.unwrap_or_else(|| reader.error(UNKNOWN_COMMAND)))
+    }
+
+    /// Parse and run `input`, without its leading `/`
+    pub fn execute(&self, source: &dyn CommandSource, input: &str) -> Result<i32, CommandError> {
+        let reader = StringReader::new(input);
+        let (node, arguments) = self.parse_children(ROOT, &reader, source, &HashMap::new())?;
+        let executor = self.nodes[node].executor.as_ref().or_else(|| {
+            self.redirect(node)
+                .and_then(|target| self.nodes[target].executor.as_ref())
+        });
+        match executor {
+            Some(executor) => executor(&CommandContext {
+                source,
+                input,
+                arguments,
+            }),
+            None => Err(CommandError::syntax(UNKNOWN_COMMAND, input, input.len())),
+        }
+    }
+
+    fn suggest_node(&self, node: usize, partial: &str, context: &CommandContext) -> Vec<String> {
+        match &self.nodes[node].kind {
+            NodeKind::Root => Vec::new(),
+            NodeKind::Literal(name) if name.starts_with(partial) => vec![name.clone()],
+            NodeKind::Literal(_) => Vec::new(),
+            NodeKind::Argument {
+                suggester: Some(suggester),
+                ..
+            } => suggester(context, partial),
+            NodeKind::Argument { argument_type, .. } => argument_type.suggestions(partial),
+        }
+    }
+
+    fn collect_suggestions(
+        &self,
+        node: usize,
+        reader: &StringReader,
+        context: &mut CommandContext,
+        suggestions: &mut Vec<(usize, String)>,
+    ) {
+        for child in self.children(node, context.source) {
+            let mut child_reader = reader.clone();
+            match self.parse_node(child, &mut child_reader) {
+                Ok(argument) if child_reader.peek() == Some(' ') => {
+                    child_reader.skip();
+                    let name = self.nodes[child].kind.name().to_string();
+                    if let Some(argument) = argument {
+                        context.arguments.insert(name.clone(), argument);
+                    }
+                    self.collect_suggestions(child, &child_reader, context, suggestions);
+                    context.arguments.remove(&name);
+                }
+                _ => {
+                    let partial = reader.remaining();
+                    let multi_word = matches!(
+                        self.nodes[child].kind,
+                        NodeKind::Argument {
+                            argument_type: ArgumentType::BlockPos
+                                | ArgumentType::Vec3
+                                | ArgumentType::Message
+                                | ArgumentType::String(_),
+                            ..
+                        }
+                    );
+                    if multi_word || !partial.contains(' ') {
+                        let start = reader.cursor();
+                        suggestions.extend(
+                            self.suggest_node(child, partial, context)
+                                .into_iter()
+                                .map(|suggestion| (start, suggestion)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Completions of the end of `input`, without its leading `/`
+    pub fn suggest(&self, source: &dyn CommandSource, input: &str) -> Suggestions {
+        let mut context = CommandContext::new(source, input);
+        let mut suggestions = Vec::new();
+        self.collect_suggestions(
+            ROOT,
+            &StringReader::new(input),
+            &mut context,
+            &mut suggestions,
+        );
+        let start = suggestions
+            .iter()
+            .map(|(start, _)| *start)
+            .max()
+            .unwrap_or(input.len());
+        let matches: BTreeSet<String> = suggestions
+            .into_iter()
+            .filter(|(suggestion_start, _)| *suggestion_start == start)
+            .map(|(_, suggestion)| suggestion)
+            .collect();
+        Suggestions {
+            start,
+            matches: matches.into_iter().collect(),
+        }
+    }
+
+    /// Nodes of the Commands packet for `source` and the index of the root,
+    /// the commands it can't use are left out
+    pub fn serialize(&self, source: &dyn CommandSource) -> (Vec<NodeData>, i32) {
+        let mut order = vec![ROOT];
+        let mut indices = HashMap::from([(ROOT, 0)]);
+        let mut queue = VecDeque::from([ROOT]);
+        while let Some(node) = queue.pop_front() {
+            let mut next = self.nodes[node].children.clone();
+            next.extend(self.redirect(node));
+            for child in next {
+                if !indices.contains_key(&child) && self.can_use(child, source) {
+                    indices.insert(child, order.len() as i32);
+                    order.push(child);
+                    queue.push_back(child);
+                }
+            }
+        }
+
+        let ask_server = Identifier::minecraft("ask_server").ok();
+        let nodes = order
+            .iter()
+            .map(|&index| {
+                let node = &self.nodes[index];
+                let children = node
+                    .children
+                    .iter()
+                    .filter_map(|child| indices.get(child).copied())
+                    .collect();
+                let redirect = self
+                    .redirect(index)
+                    .and_then(|target| indices.get(&target).copied());
+                let mut node_flags = match node.kind {
+                    NodeKind::Root => flags::ROOT,
+                    NodeKind::Literal(_) => flags::LITERAL,
+                    NodeKind::Argument { .. } => flags::ARGUMENT,
+                };
+                if node.executor.is_some() {
+                    node_flags |= flags::EXECUTABLE;
+                }
+                if redirect.is_some() {
+                    node_flags |= flags::REDIRECT;
+                }
+                let (name, parser, suggestions) = match &node.kind {
+                    NodeKind::Root => (None, None, None),
+                    NodeKind::Literal(name) => (Some(name.clone()), None, None),
+                    NodeKind::Argument {
+                        name,
+                        argument_type,
+                        suggester,
+                    } => {
+                        let suggestions = suggester.as_ref().and(ask_server.clone());
+                        if suggestions.is_some() {
+                            node_flags |= flags::SUGGESTIONS;
+                        }
+                        (
+                            Some(name.clone()),
+                            Some((argument_type.parser_id(), argument_type.properties())),
+                            suggestions,
+                        )
+                    }
+                };
+                NodeData {
+                    flags: node_flags,
+                    children,
+                    redirect,
+                    name,
+                    parser,
+                    suggestions,
+                }
+            })
+            .collect();
+        (nodes, 0)
+    }
+}
+
+impl CommandHandler for Dispatcher {
+    fn execute(&mut self, source: &dyn CommandSource, command: &str) -> Result<i32, CommandError> {
+        Dispatcher::execute(self, source, command)
+    }
+
+    fn complete(&self, source: &dyn CommandSource, command: &str) -> Suggestions {
+        self.suggest(source, command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use zr_binary::binary::Binary;
+
+    use super::{Dispatcher, Suggestions};
+    use crate::command::{
+        argument,
+        argument::StringKind,
+        literal,
+        node::{flags, NodeData},
+        ArgumentType, CommandSource,
+    };
+
+    struct TestSource {
+        op_level: u8,
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl TestSource {
+        fn new(op_level: u8) -> Self {
+            Self {
+                op_level,
+                messages: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl CommandSource for TestSource {
+        fn name(&self) -> String {
+            "test".to_string()
+        }
+
+        fn send_message(&self, message: &str) {
+            self.messages.lock().unwrap().push(message.to_string());
+        }
+
+        fn op_level(&self) -> u8 {
+            self.op_level
+        }
+
+        fn permission(&self, _node: &str) -> Option<bool> {
+            None
+        }
+    }
+
+    fn dispatcher() -> Dispatcher {
+        let mut dispatcher = Dispatcher::new();
+        dispatcher
+            .register(
+                literal("time").requires_op(2).then(
+                    literal("set")
+                        .then(literal("day").executes(|_| Ok(1000)))
+                        .then(
+                            argument("time", ArgumentType::Time)
+                                .executes(|context| context.time("time")),
+                        ),
+                ),
+            )
+            .register(
+                literal("say").then(argument("message", ArgumentType::Message).executes(
+                    |context| {
+                        let message = context.string("message")?;
+                        context.send_message(&message);
+                        Ok(message.len() as i32)
+                    },
+                )),
+            )
+            .register(
+                literal("teleport")
+                    .then(argument("location", ArgumentType::Vec3).executes(|_| Ok(3))),
+            )
+            .register(literal("tp").redirect(&["teleport"]))
+            .register(
+                literal("kick").then(
+                    argument("player", ArgumentType::String(StringKind::Word))
+                        .suggests(|_, partial| {
+                            ["Alice", "Bob"]
+                                .into_iter()
+                                .filter(|name| name.starts_with(partial))
+                                .map(str::to_string)
+                                .collect()
+                        })
+                        .executes(|_| Ok(1)),
+                ),
+            );
+        dispatcher
+    }
+
+    #[test]
+    fn test_execute() {
+        let dispatcher = dispatcher();
+        let op = TestSource::new(4);
+        // literals before arguments
+        assert_eq!(dispatcher.execute(&op, "time set day"), Ok(1000));
+        assert_eq!(dispatcher.execute(&op, "time set 2s"), Ok(40));
+        assert_eq!(dispatcher.execute(&op, "say hello world"), Ok(11));
+        assert_eq!(
+            op.messages.lock().unwrap().as_slice(),
+            ["hello world".to_string()]
+        );
+        assert_eq!(dispatcher.execute(&op, "tp 1 2 3"), Ok(3));
+
+        let err = dispatcher.execute(&op, "time set").unwrap_err();
+        assert_eq!(err.cursor, Some(8));
+        let err = dispatcher.execute(&op, "time set -1").unwrap_err();
+        assert_eq!(err.cursor, Some(9));
+        assert_eq!(
+            err.to_string(),
+            "Value must not be less than 0, found -1\ntime set <--[HERE]"
+        );
+        assert!(dispatcher.execute(&op, "time sett day").is_err());
+
+        // hidden from players
+        let player = TestSource::new(0);
+        let err = dispatcher.execute(&player, "time set day").unwrap_err();
+        assert_eq!(err.cursor, Some(0));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let dispatcher = dispatcher();
+        let op = TestSource::new(4);
+        let player = TestSource::new(0);
+        assert_eq!(
+            dispatcher.suggest(&op, "t"),
+            Suggestions {
+                start: 0,
+                matches: vec!["teleport".into(), "time".into(), "tp".into()],
+            }
+        );
+        assert_eq!(
+            dispatcher.suggest(&player, "t").matches,
+            vec!["teleport", "tp"]
+        );
+        assert_eq!(
+            dispatcher.suggest(&op, "time set d"),
+            Suggestions {
+                start: 9,
+                matches: vec!["day".into()],
+            }
+        );
+        assert_eq!(dispatcher.suggest(&op, "kick A").matches, vec!["Alice"]);
+        assert_eq!(
+            dispatcher.suggest(&op, "tp ~ ").matches,
+            vec!["~ ~", "~ ~ ~"]
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let dispatcher = dispatcher();
+        let (nodes, root) = dispatcher.serialize(&TestSource::new(0));
+        assert_eq!(root, 0);
+        // root, say, teleport, tp, kick, message, location, player
+        assert_eq!(nodes.len(), 8);
+        assert_eq!(nodes[0].children, vec![1, 2, 3, 4]);
+        let tp = &nodes[3];
+        assert_eq!(tp.flags, flags::LITERAL | flags::REDIRECT);
+        assert_eq!(tp.redirect, Some(2));
+        let player = &nodes[7];
+        assert_eq!(
+            player.flags,
+            flags::ARGUMENT | flags::EXECUTABLE | flags::SUGGESTIONS
+        );
+        assert_eq!(player.parser, Some((5, vec![0])));
+
+        let (nodes, _) = dispatcher.serialize(&TestSource::new(4));
+        assert_eq!(nodes.len(), 12);
+
+        let node: NodeData = nodes[3].clone();
+        assert_eq!(
+            NodeData::from_binary(node.clone().to_binary())
+                .unwrap()
+                .name,
+            node.name
+        );
+    }
+}
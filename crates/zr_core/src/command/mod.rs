@@ -0,0 +1,81 @@
+use std::fmt::Display;
+
+pub mod argument;
+pub mod dispatcher;
+pub mod node;
+pub mod reader;
+pub mod source;
+pub mod vanilla;
+
+pub use argument::{Argument, ArgumentType};
+pub use dispatcher::{Dispatcher, Suggestions};
+pub use node::{argument, literal, CommandContext};
+pub use source::{CommandSource, ConsoleSource};
+
+/// Characters of the input shown before the error position
+const ERROR_CONTEXT: usize = 10;
+
+/// Why a command did not run, shown to its source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    pub message: String,
+    /// position of a syntax error in the input
+    pub cursor: Option<usize>,
+    /// input before the error position
+    pub context: Option<String>,
+}
+
+impl CommandError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            cursor: None,
+            context: None,
+        }
+    }
+
+    /// Error in the command line itself, at `cursor`
+    pub fn syntax(message: impl Into<String>, input: &str, cursor: usize) -> Self {
+        let cursor = cursor.min(input.len());
+        let start = input[..cursor]
+            .char_indices()
+            .rev()
+            .nth(ERROR_CONTEXT - 1)
+            .map_or(0, |(index, _)| index);
+        let context = if start > 0 {
+            format!("...{}", &input[start..cursor])
+        } else {
+            input[..cursor].to_string()
+        };
+        Self {
+            message: message.into(),
+            cursor: Some(cursor),
+            context: Some(context),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.context {
+            Some(context) => write!(f, "{}\n{context}<--[HERE]", self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Run the commands sent by the players and typed in the console
+pub trait CommandHandler: Send {
+    /// `command` without its leading `/`, returns the command result
+    fn execute(&mut self, source: &dyn CommandSource, command: &str) -> Result<i32, CommandError>;
+
+    /// Replacements of the end of `command`
+    fn complete(&self, _source: &dyn CommandSource, command: &str) -> Suggestions {
+        Suggestions {
+            start: command.len(),
+            matches: Vec::new(),
+        }
+    }
+}
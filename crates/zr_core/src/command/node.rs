@@ -0,0 +1,278 @@
+use std::{collections::HashMap, sync::Arc};
+
+use zr_binary::{
+    binary::{read, Binary},
+    error::{self, BinaryError},
+    varint::VarInt,
+};
+use zr_data_type::string::Identifier;
+
+use super::{
+    argument::{Coordinates, EntitySelector},
+    Argument, ArgumentType, CommandError, CommandSource,
+};
+use crate::{entity::player::GameMode, item::ItemStack};
+
+pub type Executor = Arc<dyn Fn(&CommandContext) -> Result<i32, CommandError> + Send + Sync>;
+pub type Requirement = Arc<dyn Fn(&dyn CommandSource) -> bool + Send + Sync>;
+/// Suggestions for the partial argument, computed on the server
+pub type Suggester = Arc<dyn Fn(&CommandContext, &str) -> Vec<String> + Send + Sync>;
+
+/// Source and parsed arguments of a command being run
+pub struct CommandContext<'a> {
+    pub source: &'a dyn CommandSource,
+    pub input: &'a str,
+    pub(crate) arguments: HashMap<String, Argument>,
+}
+
+macro_rules! argument_getters {
+    ($($getter: ident: $variant: ident -> $ty: ty),* $(,)?) => {
+        $(
+            pub fn $getter(&self, name: &str) -> Result<$ty, CommandError> {
+                match self.arguments.get(name) {
+                    Some(Argument::$variant(value)) => Ok(value.clone()),
+                    _ => Err(self.missing(name)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'a> CommandContext<'a> {
+    pub(crate) fn new(source: &'a dyn CommandSource, input: &'a str) -> Self {
+        Self {
+            source,
+            input,
+            arguments: HashMap::new(),
+        }
+    }
+
+    pub fn argument(&self, name: &str) -> Option<&Argument> {
+        self.arguments.get(name)
+    }
+
+    pub fn has_argument(&self, name: &str) -> bool {
+        self.arguments.contains_key(name)
+    }
+
+    fn missing(&self, name: &str) -> CommandError {
+        CommandError::new(format!("No argument '{name}' of this type"))
+    }
+
+    argument_getters!(
+        bool: Bool -> bool,
+        float: Float -> f32,
+        double: Double -> f64,
+        integer: Integer -> i32,
+        long: Long -> i64,
+        string: String -> String,
+        entity: Entity -> EntitySelector,
+        block_pos: BlockPos -> Coordinates,
+        vec3: Vec3 -> Coordinates,
+        item_stack: ItemStack -> ItemStack,
+        resource_location: ResourceLocation -> Identifier,
+        game_mode: GameMode -> GameMode,
+        time: Time -> i32,
+    );
+
+    /// Feedback to the source of the command
+    pub fn send_message(&self, message: &str) {
+        self.source.send_message(message);
+    }
+}
+
+#[derive(Clone)]
+pub enum NodeKind {
+    Root,
+    Literal(String),
+    Argument {
+        name: String,
+        argument_type: ArgumentType,
+        suggester: Option<Suggester>,
+    },
+}
+
+impl NodeKind {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Root => "",
+            Self::Literal(name) | Self::Argument { name, .. } => name,
+        }
+    }
+}
+
+/// Node of a command tree before its registration in a [`super::Dispatcher`]
+pub struct CommandBuilder {
+    pub(crate) kind: NodeKind,
+    pub(crate) executor: Option<Executor>,
+    pub(crate) requirement: Option<Requirement>,
+    /// path of the node this one redirects to, from the root
+    pub(crate) redirect: Option<Vec<String>>,
+    pub(crate) children: Vec<CommandBuilder>,
+}
+
+/// A literal word, like the name of the command
+pub fn literal(name: &str) -> CommandBuilder {
+    CommandBuilder::new(NodeKind::Literal(name.to_string()))
+}
+
+/// A value parsed by `argument_type`, read by the executor with its `name`
+pub fn argument(name: &str, argument_type: ArgumentType) -> CommandBuilder {
+    CommandBuilder::new(NodeKind::Argument {
+        name: name.to_string(),
+        argument_type,
+        suggester: None,
+    })
+}
+
+impl CommandBuilder {
+    fn new(kind: NodeKind) -> Self {
+        Self {
+            kind,
+            executor: None,
+            requirement: None,
+            redirect: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn then(mut self, child: CommandBuilder) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// The command can end on this node
+    pub fn executes(
+        mut self,
+        executor: impl Fn(&CommandContext) -> Result<i32, CommandError> + Send + Sync + 'static,
+    ) -> Self {
+        self.executor = Some(Arc::new(executor));
+        self
+    }
+
+    /// The node and its children are hidden from the sources failing `requirement`
+    pub fn requires(
+        mut self,
+        requirement: impl Fn(&dyn CommandSource) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.requirement = Some(Arc::new(requirement));
+        self
+    }
+
+    /// Shorthand for a minimum operator level
+    pub fn requires_op(self, level: u8) -> Self {
+        self.requires(move |source| source.op_level() >= level)
+    }
+
+    /// The permission `node`, or the operator `level` when the node is not set for the source
+    pub fn requires_permission(self, node: &str, level: u8) -> Self {
+        let node = node.to_string();
+        self.requires(move |source| source.has_permission(&node, level))
+    }
+
+    /// Continue the parsing on the children of the node at `path`, e.g. `/tp` to `/teleport`
+    pub fn redirect(mut self, path: &[&str]) -> Self {
+        self.redirect = Some(path.iter().map(|name| name.to_string()).collect());
+        self
+    }
+
+    /// Suggestions computed by the server, the client asks for them while typing
+    pub fn suggests(
+        mut self,
+        suggester: impl Fn(&CommandContext, &str) -> Vec<String> + Send + Sync + 'static,
+    ) -> Self {
+        if let NodeKind::Argument {
+            suggester: current, ..
+        } = &mut self.kind
+        {
+            *current = Some(Arc::new(suggester));
+        }
+        self
+    }
+}
+
+/// Node of the Commands packet
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeData {
+    pub flags: u8,
+    pub children: Vec<i32>,
+    pub redirect: Option<i32>,
+    pub name: Option<String>,
+    /// parser id and properties
+    pub parser: Option<(i32, Vec<u8>)>,
+    pub suggestions: Option<Identifier>,
+}
+
+pub mod flags {
+    pub const ROOT: u8 = 0x00;
+    pub const LITERAL: u8 = 0x01;
+    pub const ARGUMENT: u8 = 0x02;
+    pub const TYPE_MASK: u8 = 0x03;
+    pub const EXECUTABLE: u8 = 0x04;
+    pub const REDIRECT: u8 = 0x08;
+    pub const SUGGESTIONS: u8 = 0x10;
+}
+
+impl Binary for NodeData {
+    fn binary_len(&self) -> usize {
+        self.clone().to_binary().len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        let mut bin = vec![self.flags];
+        bin.extend(VarInt::new(self.children.len() as i32).to_binary());
+        for child in self.children {
+            bin.extend(VarInt::new(child).to_binary());
+        }
+        if let Some(redirect) = self.redirect {
+            bin.extend(VarInt::new(redirect).to_binary());
+        }
+        if let Some(name) = self.name {
+            bin.extend(name.to_binary());
+        }
+        if let Some((id, properties)) = self.parser {
+            bin.extend(VarInt::new(id).to_binary());
+            bin.extend(properties);
+        }
+        if let Some(suggestions) = self.suggestions {
+            bin.extend(suggestions.to_binary());
+        }
+        bin
+    }
+
+    /// Parser properties are not decoded, the node is kept without them
+    fn from_binary(bin: Vec<u8>) -> error::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = 0;
+        let flags = read::<u8>(&bin, &mut cursor)?;
+        let count = read::<VarInt<i32>>(&bin, &mut cursor)?.0;
+        let children = (0..count)
+            .map(|_| read::<VarInt<i32>>(&bin, &mut cursor).map(|child| child.0))
+            .collect::<error::Result<_>>()?;
+        let redirect = if flags & flags::REDIRECT != 0 {
+            Some(read::<VarInt<i32>>(&bin, &mut cursor)?.0)
+        } else {
+            None
+        };
+        let name = match flags & flags::TYPE_MASK {
+            flags::ROOT => None,
+            flags::LITERAL | flags::ARGUMENT => Some(read::<String>(&bin, &mut cursor)?),
+            _ => return Err(BinaryError::FormatError),
+        };
+        let parser = if flags & flags::TYPE_MASK == flags::ARGUMENT {
+            Some((read::<VarInt<i32>>(&bin, &mut cursor)?.0, Vec::new()))
+        } else {
+            None
+        };
+        Ok(Self {
+            flags,
+            children,
+            redirect,
+            name,
+            parser,
+            suggestions: None,
+        })
+    }
+}
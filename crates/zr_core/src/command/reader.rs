@@ -0,0 +1,149 @@
+use std::str::FromStr;
+
+use super::CommandError;
+
+/// Cursor over a command line, arguments are read from it one after the other
+#[derive(Debug, Clone)]
+pub struct StringReader<'a> {
+    input: &'a str,
+    cursor: usize,
+}
+
+impl<'a> StringReader<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input, cursor: 0 }
+    }
+
+    pub fn input(&self) -> &'a str {
+        self.input
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn set_cursor(&mut self, cursor: usize) {
+        self.cursor = cursor;
+    }
+
+    pub fn remaining(&self) -> &'a str {
+        &self.input[self.cursor..]
+    }
+
+    pub fn can_read(&self) -> bool {
+        self.cursor < self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.remaining().chars().next()
+    }
+
+    pub fn skip(&mut self) {
+        if let Some(c) = self.peek() {
+            self.cursor += c.len_utf8();
+        }
+    }
+
+    pub fn skip_whitespace(&mut self) {
+        while self.peek() == Some(' ') {
+            self.skip();
+        }
+    }
+
+    /// Syntax error at the cursor
+    pub fn error(&self, message: impl Into<String>) -> CommandError {
+        CommandError::syntax(message, self.input, self.cursor)
+    }
+
+    /// Read until the next space
+    pub fn read_word(&mut self) -> &'a str {
+        let start = self.cursor;
+        while self.peek().is_some_and(|c| c != ' ') {
+            self.skip();
+        }
+        &self.input[start..self.cursor]
+    }
+
+    /// Read until the end of the input
+    pub fn read_remaining(&mut self) -> &'a str {
+        let remaining = self.remaining();
+        self.cursor = self.input.len();
+        remaining
+    }
+
+    /// A word or a quoted string with `\` escapes
+    pub fn read_string(&mut self) -> Result<String, CommandError> {
+        let quote = match self.peek() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Ok(self.read_word().to_string()),
+        };
+        let start = self.cursor;
+        self.skip();
+        let mut value = String::new();
+        let mut escaped = false;
+        while let Some(c) = self.peek() {
+            self.skip();
+            match c {
+                c if escaped => {
+                    if c != quote && c != '\\' {
+                        self.cursor -= c.len_utf8();
+                        return Err(
+                            self.error(format!("Invalid escape sequence '\\{c}' in quoted string"))
+                        );
+                    }
+                    value.push(c);
+                    escaped = false;
+                }
+                '\\' => escaped = true,
+                c if c == quote => return Ok(value),
+                c => value.push(c),
+            }
+        }
+        self.cursor = start;
+        Err(self.error("Unclosed quoted string"))
+    }
+
+    /// Parse the next word as `T`, the cursor does not move on error
+    pub fn read_parsed<T: FromStr>(&mut self, expected: &str) -> Result<T, CommandError> {
+        let start = self.cursor;
+        let word = self.read_word();
+        if word.is_empty() {
+            return Err(self.error(format!("Expected {expected}")));
+        }
+        word.parse().map_err(|_| {
+            self.cursor = start;
+            self.error(format!("Invalid {expected} '{word}'"))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StringReader;
+
+    #[test]
+    fn test_read() {
+        let mut reader = StringReader::new("give @p 'a \\'b\\'' 12 rest of it");
+        assert_eq!(reader.read_word(), "give");
+        reader.skip_whitespace();
+        assert_eq!(reader.read_string().unwrap(), "@p");
+        reader.skip_whitespace();
+        assert_eq!(reader.read_string().unwrap(), "a 'b'");
+        reader.skip_whitespace();
+        assert_eq!(reader.read_parsed::<i32>("integer").unwrap(), 12);
+        reader.skip_whitespace();
+        assert_eq!(reader.read_remaining(), "rest of it");
+        assert!(!reader.can_read());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut reader = StringReader::new("abc \"unclosed");
+        assert!(reader.read_parsed::<i32>("integer").is_err());
+        assert_eq!(reader.cursor(), 0);
+        reader.read_word();
+        reader.skip_whitespace();
+        let err = reader.read_string().unwrap_err();
+        assert_eq!(err.cursor, Some(4));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::console;
+
+/// Who runs a command: a player, the console, a command block...
+pub trait CommandSource {
+    fn name(&self) -> String;
+    /// Feedback of the command
+    fn send_message(&self, message: &str);
+    /// Operator level, from 0 to 4
+    fn op_level(&self) -> u8;
+    /// Value of the permission node, `None` when it is not set for the source
+    fn permission(&self, node: &str) -> Option<bool>;
+
+    /// The node when it is set, the operator level otherwise
+    fn has_permission(&self, node: &str, level: u8) -> bool {
+        self.permission(node)
+            .unwrap_or_else(|| self.op_level() >= level)
+    }
+}
+
+/// The server console, allowed to do everything
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsoleSource;
+
+impl CommandSource for ConsoleSource {
+    fn name(&self) -> String {
+        "Server".to_string()
+    }
+
+    fn send_message(&self, message: &str) {
+        console::log(message);
+    }
+
+    fn op_level(&self) -> u8 {
+        4
+    }
+
+    fn permission(&self, _node: &str) -> Option<bool> {
+        Some(true)
+    }
+}
@@ -0,0 +1,1397 @@
+use std::sync::Arc;
+
+use rand::seq::SliceRandom;
+use uuid::Uuid;
+use zr_data_type::vector::Vector3;
+
+use super::{
+    argument,
+    argument::{EntitySelector, SelectorTarget},
+    literal,
+    node::CommandBuilder,
+    ArgumentType, CommandContext, CommandError, Dispatcher,
+};
+use crate::{
+    entity::player::GameMode,
+    item::ItemStack,
+    tick::TickReport,
+    world::{Difficulty, Weather, DAY_LENGTH},
+};
+
+const NO_PLAYER: &str = "No player was found";
+const UNKNOWN_PLAYER: &str = "That player does not exist";
+const PLAYER_REQUIRED: &str = "A player is required to run this command here";
+const KICK_REASON: &str = "Kicked by an operator";
+const BAN_REASON: &str = "Banned by an operator";
+/// Weather duration when the command does not give one, in ticks
+const WEATHER_DURATION: i32 = 6000;
+/// Most stacks given at once by `/give`
+const MAX_GIVEN_STACKS: i32 = 100;
+const NO_TICK_REPORT: &str = "The tick statistics are not available";
+
+/// What the built-in commands read and change on the server
+pub trait Server: Send + Sync {
+    fn stop(&self);
+    /// Names of the connected players
+    fn players(&self) -> Vec<String>;
+    fn max_players(&self) -> usize;
+    /// Position and rotation (yaw, pitch) of a connected player
+    fn position(&self, player: &str) -> Option<(Vector3<f64>, (f32, f32))>;
+    fn send_message(&self, player: &str, message: &str);
+    /// Message to all the connected players
+    fn broadcast(&self, message: &str);
+    fn kick(&self, player: &str, reason: &str);
+    /// Uuid of an online player or of one who joined before, `None` for an unknown player
+    fn uuid(&self, player: &str) -> Option<Uuid>;
+    /// `false` if the player was already banned, `source` is who banned them
+    fn ban(&self, player: &str, uuid: Uuid, source: &str, reason: &str) -> bool;
+    /// `false` if the player was not banned
+    fn pardon(&self, player: &str) -> bool;
+    /// `false` if the player already was an operator
+    fn op(&self, player: &str, uuid: Uuid) -> bool;
+    /// `false` if the player was not an operator
+    fn deop(&self, player: &str) -> bool;
+    fn whitelist(&self) -> Vec<String>;
+    fn is_whitelist_enabled(&self) -> bool;
+    fn set_whitelist_enabled(&self, enabled: bool);
+    /// `false` if the player already was whitelisted
+    fn whitelist_add(&self, player: &str) -> bool;
+    /// `false` if the player was not whitelisted
+    fn whitelist_remove(&self, player: &str) -> bool;
+    fn reload_whitelist(&self);
+    fn teleport(&self, player: &str, position: Vector3<f64>, rotation: (f32, f32));
+    fn set_game_mode(&self, player: &str, game_mode: GameMode);
+    fn give(&self, player: &str, stack: ItemStack);
+    /// Ticks since the creation of the world
+    fn game_time(&self) -> i64;
+    /// Time of the day, can go beyond one day
+    fn day_time(&self) -> i64;
+    fn set_day_time(&self, time: i64);
+    fn set_weather(&self, weather: Weather, duration: i32);
+    fn seed(&self) -> i64;
+    fn difficulty(&self) -> Difficulty;
+    fn set_difficulty(&self, difficulty: Difficulty);
+    /// Statistics of the tick loop, `None` when it does not run
+    fn tick_report(&self) -> Option<TickReport>;
+}
+
+/// Register the vanilla administration commands
+pub fn register(dispatcher: &mut Dispatcher, server: Arc<dyn Server>) {
+    dispatcher
+        .register(stop(server.clone()))
+        .register(list(server.clone()))
+        .register(kick(server.clone()))
+        .register(ban(server.clone()))
+        .register(pardon(server.clone()))
+        .register(op(server.clone()))
+        .register(deop(server.clone()))
+        .register(whitelist(server.clone()))
+        .register(teleport(server.clone()))
+        .register(
+            literal("tp")
+                .requires_permission("minecraft.command.teleport", 2)
+                .redirect(&["teleport"]),
+        )
+        .register(gamemode(server.clone()))
+        .register(give(server.clone()))
+        .register(time(server.clone()))
+        .register(weather(server.clone()))
+        .register(say(server.clone()))
+        .register(msg(server.clone()))
+        .register(
+            literal("tell")
+                .requires_permission("minecraft.command.msg", 0)
+                .redirect(&["msg"]),
+        )
+        .register(
+            literal("w")
+                .requires_permission("minecraft.command.msg", 0)
+                .redirect(&["msg"]),
+        )
+        .register(seed(server.clone()))
+        .register(tps(server.clone()))
+        .register(difficulty(server));
+}
+
+fn capitalized(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn distance_squared(a: Vector3<f64>, b: Vector3<f64>) -> f64 {
+    (a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)
+}
+
+/// Position and rotation the relative coordinates start from
+fn origin(context: &CommandContext, server: &dyn Server) -> (Vector3<f64>, (f32, f32)) {
+    server.position(&context.source.name()).unwrap_or_default()
+}
+
+/// The source itself, when it is a connected player
+fn source_player(context: &CommandContext, server: &dyn Server) -> Result<String, CommandError> {
+    let name = context.source.name();
+    match server.players().contains(&name) {
+        true => Ok(name),
+        false => Err(CommandError::new(PLAYER_REQUIRED)),
+    }
+}
+
+/// Connected players matched by the selector argument `name`
+fn players(
+    context: &CommandContext,
+    server: &dyn Server,
+    name: &str,
+) -> Result<Vec<String>, CommandError> {
+    let selector = context.entity(name)?;
+    let mut players = server.players();
+    let limit = match &selector {
+        EntitySelector::Player(name) => {
+            players.retain(|player| player.eq_ignore_ascii_case(name));
+            None
+        }
+        EntitySelector::Uuid(_) => {
+            players.clear();
+            None
+        }
+        EntitySelector::Selector { target, .. } => {
+            if let Some(name) = selector.argument("name") {
+                match name.strip_prefix('!') {
+                    Some(name) => players.retain(|player| player != name),
+                    None => players.retain(|player| player == name),
+                }
+            }
+            let source = context.source.name();
+            match target {
+                SelectorTarget::Executor => players.retain(|player| *player == source),
+                SelectorTarget::NearestPlayer | SelectorTarget::NearestEntity => {
+                    let (origin, _) = self::origin(context, server);
+                    let distance = |player: &String| {
+                        server
+                            .position(player)
+                            .map_or(f64::MAX, |(position, _)| distance_squared(origin, position))
+                    };
+                    players.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+                }
+                SelectorTarget::RandomPlayer => players.shuffle(&mut rand::thread_rng()),
+                SelectorTarget::AllPlayers | SelectorTarget::AllEntities => {}
+            }
+            let default = (!target.is_multiple()).then_some(1);
+            selector
+                .argument("limit")
+                .and_then(|limit| limit.parse().ok())
+                .or(default)
+        }
+    };
+    if let Some(limit) = limit {
+        players.truncate(limit);
+    }
+    match players.is_empty() {
+        true => Err(CommandError::new(NO_PLAYER)),
+        false => Ok(players),
+    }
+}
+
+/// Names matched by the game profile argument `name`, a plain name may be offline
+fn profiles(
+    context: &CommandContext,
+    server: &dyn Server,
+    name: &str,
+) -> Result<Vec<String>, CommandError> {
+    match context.entity(name)? {
+        EntitySelector::Player(name) => Ok(vec![name]),
+        _ => players(context, server, name),
+    }
+}
+
+/// Names and uuids of the players matched by `name`, the offline ones must have joined before
+fn known_profiles(
+    context: &CommandContext,
+    server: &dyn Server,
+    name: &str,
+) -> Result<Vec<(String, Uuid)>, CommandError> {
+    profiles(context, server, name)?
+        .into_iter()
+        .map(|player| match server.uuid(&player) {
+            Some(uuid) => Ok((player, uuid)),
+            None => Err(CommandError::new(UNKNOWN_PLAYER)),
+        })
+        .collect()
+}
+
+/// Players and selectors starting with the partial argument
+fn suggest_players(server: &Arc<dyn Server>, argument_type: &ArgumentType) -> CommandBuilder {
+    let server = server.clone();
+    let selectors = argument_type.clone();
+    argument("targets", argument_type.clone()).suggests(move |_, partial| {
+        let mut suggestions = selectors.suggestions(partial);
+        suggestions.extend(
+            server
+                .players()
+                .into_iter()
+                .filter(|player| player.starts_with(partial)),
+        );
+        suggestions
+    })
+}
+
+fn targets(server: &Arc<dyn Server>, single: bool) -> CommandBuilder {
+    suggest_players(
+        server,
+        &ArgumentType::Entity {
+            single,
+            players_only: true,
+        },
+    )
+}
+
+fn game_profiles(server: &Arc<dyn Server>) -> CommandBuilder {
+    suggest_players(server, &ArgumentType::GameProfile)
+}
+
+/// `targets` in a feedback message
+fn describe(players: &[String]) -> String {
+    match players {
+        [player] => player.clone(),
+        players => format!("{} players", players.len()),
+    }
+}
+
+fn stop(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("stop")
+        .requires_permission("minecraft.command.stop", 4)
+        .executes(move |context| {
+            context.send_message("Stopping the server");
+            server.stop();
+            Ok(1)
+        })
+}
+
+fn list(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("list")
+        .requires_permission("minecraft.command.list", 0)
+        .executes(move |context| {
+            let players = server.players();
+            context.send_message(&format!(
+                "There are {} of a max of {} players online: {}",
+                players.len(),
+                server.max_players(),
+                players.join(", ")
+            ));
+            Ok(players.len() as i32)
+        })
+}
+
+fn kick(server: Arc<dyn Server>) -> CommandBuilder {
+    let targets = targets(&server, false);
+    let run = move |context: &CommandContext, reason: &str| {
+        let players = players(context, server.as_ref(), "targets")?;
+        for player in &players {
+            server.kick(player, reason);
+            context.send_message(&format!("Kicked {player}: {reason}"));
+        }
+        Ok(players.len() as i32)
+    };
+    let with_reason = run.clone();
+    literal("kick")
+        .requires_permission("minecraft.command.kick", 3)
+        .then(
+            targets
+                .executes(move |context| run(context, KICK_REASON))
+                .then(
+                    argument("reason", ArgumentType::Message)
+                        .executes(move |context| with_reason(context, &context.string("reason")?)),
+                ),
+        )
+}
+
+fn ban(server: Arc<dyn Server>) -> CommandBuilder {
+    let targets = game_profiles(&server);
+    let run = move |context: &CommandContext, reason: &str| {
+        let mut banned = 0;
+        for (player, uuid) in known_profiles(context, server.as_ref(), "targets")? {
+            if !server.ban(&player, uuid, &context.source.name(), reason) {
+                continue;
+            }
+            if server.players().contains(&player) {
+                server.kick(
+                    &player,
+                    &format!("You are banned from this server.\nReason: {reason}"),
+                );
+            }
+            context.send_message(&format!("Banned {player}: {reason}"));
+            banned += 1;
+        }
+        match banned {
+            0 => Err(CommandError::new(
+                "Nothing changed. The player is already banned",
+            )),
+            banned => Ok(banned),
+        }
+    };
+    let with_reason = run.clone();
+    literal("ban")
+        .requires_permission("minecraft.command.ban", 3)
+        .then(
+            targets
+                .executes(move |context| run(context, BAN_REASON))
+                .then(
+                    argument("reason", ArgumentType::Message)
+                        .executes(move |context| with_reason(context, &context.string("reason")?)),
+                ),
+        )
+}
+
+fn pardon(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("pardon")
+        .requires_permission("minecraft.command.pardon", 3)
+        .then(
+            argument("targets", ArgumentType::GameProfile).executes(move |context| {
+                let mut pardoned = 0;
+                for player in profiles(context, server.as_ref(), "targets")? {
+                    if server.pardon(&player) {
+                        context.send_message(&format!("Unbanned {player}"));
+                        pardoned += 1;
+                    }
+                }
+                match pardoned {
+                    0 => Err(CommandError::new(
+                        "Nothing changed. The player isn't banned",
+                    )),
+                    pardoned => Ok(pardoned),
+                }
+            }),
+        )
+}
+
+fn op(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("op")
+        .requires_permission("minecraft.command.op", 3)
+        .then(game_profiles(&server).executes(move |context| {
+            let mut opped = 0;
+            for (player, uuid) in known_profiles(context, server.as_ref(), "targets")? {
+                if server.op(&player, uuid) {
+                    context.send_message(&format!("Made {player} a server operator"));
+                    opped += 1;
+                }
+            }
+            match opped {
+                0 => Err(CommandError::new(
+                    "Nothing changed. The player already is an operator",
+                )),
+                opped => Ok(opped),
+            }
+        }))
+}
+
+fn deop(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("deop")
+        .requires_permission("minecraft.command.deop", 3)
+        .then(game_profiles(&server).executes(move |context| {
+            let mut deopped = 0;
+            for player in profiles(context, server.as_ref(), "targets")? {
+                if server.deop(&player) {
+                    context.send_message(&format!("Made {player} no longer a server operator"));
+                    deopped += 1;
+                }
+            }
+            match deopped {
+                0 => Err(CommandError::new(
+                    "Nothing changed. The player is not an operator",
+                )),
+                deopped => Ok(deopped),
+            }
+        }))
+}
+
+fn whitelist(server: Arc<dyn Server>) -> CommandBuilder {
+    let toggle = |enabled: bool| {
+        let server = server.clone();
+        let state = if enabled { "on" } else { "off" };
+        literal(state).executes(move |context| {
+            if server.is_whitelist_enabled() == enabled {
+                return Err(CommandError::new(format!(
+                    "Whitelist is already turned {state}"
+                )));
+            }
+            server.set_whitelist_enabled(enabled);
+            context.send_message(&format!("Whitelist is now turned {state}"));
+            Ok(1)
+        })
+    };
+    let (on, off) = (toggle(true), toggle(false));
+    let add = server.clone();
+    let remove = server.clone();
+    let list = server.clone();
+    let reload = server.clone();
+    literal("whitelist")
+        .requires_permission("minecraft.command.whitelist", 3)
+        .then(on)
+        .then(off)
+        .then(
+            literal("add").then(game_profiles(&server).executes(move |context| {
+                let mut added = 0;
+                for player in profiles(context, add.as_ref(), "targets")? {
+                    if add.whitelist_add(&player) {
+                        context.send_message(&format!("Added {player} to the whitelist"));
+                        added += 1;
+                    }
+                }
+                match added {
+                    0 => Err(CommandError::new("Player is already whitelisted")),
+                    added => Ok(added),
+                }
+            })),
+        )
+        .then(
+            literal("remove").then(game_profiles(&server).executes(move |context| {
+                let mut removed = 0;
+                for player in profiles(context, remove.as_ref(), "targets")? {
+                    if remove.whitelist_remove(&player) {
+                        context.send_message(&format!("Removed {player} from the whitelist"));
+                        removed += 1;
+                    }
+                }
+                match removed {
+                    0 => Err(CommandError::new("Player is not whitelisted")),
+                    removed => Ok(removed),
+                }
+            })),
+        )
+        .then(
+            literal("list")
+                .requires_permission("minecraft.command.list", 0)
+                .executes(move |context| {
+                    let players = list.whitelist();
+                    match players.len() {
+                        0 => context.send_message("There are no whitelisted players"),
+                        count => context.send_message(&format!(
+                            "There are {count} whitelisted player(s): {}",
+                            players.join(", ")
+                        )),
+                    }
+                    Ok(players.len() as i32)
+                }),
+        )
+        .then(literal("reload").executes(move |context| {
+            reload.reload_whitelist();
+            context.send_message("Reloaded the whitelist");
+            Ok(1)
+        }))
+}
+
+fn teleport(server: Arc<dyn Server>) -> CommandBuilder {
+    // players, position and rotation, or the player to go to
+    type Destination = (Vector3<f64>, (f32, f32), String);
+    let to_location = |context: &CommandContext, server: &dyn Server| {
+        let (origin, rotation) = origin(context, server);
+        let position = context.vec3("location")?.resolve(origin, rotation);
+        let description = format!("{:.6}, {:.6}, {:.6}", position.x, position.y, position.z);
+        Ok::<Destination, CommandError>((position, rotation, description))
+    };
+    let to_player = |context: &CommandContext, server: &dyn Server| {
+        let destination = players(context, server, "destination")?.remove(0);
+        let (position, rotation) = server
+            .position(&destination)
+            .ok_or_else(|| CommandError::new(NO_PLAYER))?;
+        Ok::<Destination, CommandError>((position, rotation, destination))
+    };
+    let run = move |server: &dyn Server,
+                    context: &CommandContext,
+                    players: Vec<String>,
+                    (position, rotation, description): Destination| {
+        for player in &players {
+            server.teleport(player, position, rotation);
+        }
+        context.send_message(&format!(
+            "Teleported {} to {description}",
+            describe(&players)
+        ));
+        Ok(players.len() as i32)
+    };
+    let location = |server: &Arc<dyn Server>, targets: bool| {
+        let server = server.clone();
+        argument("location", ArgumentType::Vec3).executes(move |context| {
+            let players = match targets {
+                true => players(context, server.as_ref(), "targets")?,
+                false => vec![source_player(context, server.as_ref())?],
+            };
+            run(
+                server.as_ref(),
+                context,
+                players,
+                to_location(context, server.as_ref())?,
+            )
+        })
+    };
+    let destination = |server: &Arc<dyn Server>, targets: bool| {
+        let server = server.clone();
+        argument(
+            "destination",
+            ArgumentType::Entity {
+                single: true,
+                players_only: true,
+            },
+        )
+        .executes(move |context| {
+            let players = match targets {
+                true => players(context, server.as_ref(), "targets")?,
+                false => vec![source_player(context, server.as_ref())?],
+            };
+            run(
+                server.as_ref(),
+                context,
+                players,
+                to_player(context, server.as_ref())?,
+            )
+        })
+    };
+    literal("teleport")
+        .requires_permission("minecraft.command.teleport", 2)
+        .then(location(&server, false))
+        .then(destination(&server, false))
+        .then(
+            targets(&server, false)
+                .then(location(&server, true))
+                .then(destination(&server, true)),
+        )
+}
+
+fn gamemode(server: Arc<dyn Server>) -> CommandBuilder {
+    let own_server = server.clone();
+    let targets_server = server.clone();
+    let targets = targets(&server, false);
+    let run = move |context: &CommandContext, players: Vec<String>| {
+        let game_mode = context.game_mode("gamemode")?;
+        let mode = format!("{} Mode", capitalized(game_mode.name()));
+        let source = context.source.name();
+        for player in &players {
+            server.set_game_mode(player, game_mode);
+            if *player == source {
+                context.send_message(&format!("Set own game mode to {mode}"));
+            } else {
+                server.send_message(
+                    player,
+                    &format!("Your game mode has been updated to {mode}"),
+                );
+                context.send_message(&format!("Set {player}'s game mode to {mode}"));
+            }
+        }
+        Ok(players.len() as i32)
+    };
+    let own = run.clone();
+    literal("gamemode")
+        .requires_permission("minecraft.command.gamemode", 2)
+        .then(
+            argument("gamemode", ArgumentType::GameMode)
+                .executes(move |context| {
+                    own(context, vec![source_player(context, own_server.as_ref())?])
+                })
+                .then(targets.executes(move |context| {
+                    run(
+                        context,
+                        players(context, targets_server.as_ref(), "targets")?,
+                    )
+                })),
+        )
+}
+
+fn give(server: Arc<dyn Server>) -> CommandBuilder {
+    let targets = targets(&server, false);
+    let run = move |context: &CommandContext, count: i32| {
+        let stack = context.item_stack("item")?;
+        let name = stack.item().name();
+        let max_stack = stack.max_stack_size() as i32;
+        let max = max_stack * MAX_GIVEN_STACKS;
+        if count > max {
+            return Err(CommandError::new(format!(
+                "Can't give more than {max} of [{name}]"
+            )));
+        }
+        let players = players(context, server.as_ref(), "targets")?;
+        for player in &players {
+            let mut remaining = count;
+            while remaining > 0 {
+                let given = remaining.min(max_stack);
+                let mut given_stack = stack.clone();
+                given_stack.set_count(given as u8);
+                server.give(player, given_stack);
+                remaining -= given;
+            }
+        }
+        context.send_message(&format!("Gave {count} [{name}] to {}", describe(&players)));
+        Ok(players.len() as i32)
+    };
+    let with_count = run.clone();
+    literal("give")
+        .requires_permission("minecraft.command.give", 2)
+        .then(
+            targets.then(
+                argument("item", ArgumentType::ItemStack)
+                    .executes(move |context| run(context, 1))
+                    .then(
+                        argument(
+                            "count",
+                            ArgumentType::Integer {
+                                min: Some(1),
+                                max: None,
+                            },
+                        )
+                        .executes(move |context| with_count(context, context.integer("count")?)),
+                    ),
+            ),
+        )
+}
+
+fn time(server: Arc<dyn Server>) -> CommandBuilder {
+    let set = |name: &str, time: Option<i64>| {
+        let server = server.clone();
+        let executor = move |context: &CommandContext| {
+            let time = match time {
+                Some(time) => time,
+                None => context.time("time")? as i64,
+            };
+            server.set_day_time(time);
+            context.send_message(&format!("Set the time to {time}"));
+            Ok((time % DAY_LENGTH) as i32)
+        };
+        match time {
+            Some(_) => literal(name).executes(executor),
+            None => argument(name, ArgumentType::Time).executes(executor),
+        }
+    };
+    let add_server = server.clone();
+    let add = argument("time", ArgumentType::Time).executes(move |context| {
+        let day_time = add_server.day_time() + context.time("time")? as i64;
+        add_server.set_day_time(day_time);
+        context.send_message(&format!("Set the time to {}", day_time % DAY_LENGTH));
+        Ok((day_time % DAY_LENGTH) as i32)
+    });
+    let query = |name: &str, value: fn(&dyn Server) -> i64| {
+        let server = server.clone();
+        literal(name).executes(move |context| {
+            let value = (value(server.as_ref()) % i32::MAX as i64) as i32;
+            context.send_message(&format!("The time is {value}"));
+            Ok(value)
+        })
+    };
+    literal("time")
+        .requires_permission("minecraft.command.time", 2)
+        .then(
+            literal("set")
+                .then(set("day", Some(1000)))
+                .then(set("noon", Some(6000)))
+                .then(set("night", Some(13000)))
+                .then(set("midnight", Some(18000)))
+                .then(set("time", None)),
+        )
+        .then(literal("add").then(add))
+        .then(
+            literal("query")
+                .then(query("daytime", |server| server.day_time() % DAY_LENGTH))
+                .then(query("gametime", |server| server.game_time()))
+                .then(query("day", |server| server.day_time() / DAY_LENGTH)),
+        )
+}
+
+fn weather(server: Arc<dyn Server>) -> CommandBuilder {
+    let set = |name: &str, weather: Weather, feedback: &'static str| {
+        let run = {
+            let server = server.clone();
+            move |context: &CommandContext, duration: i32| {
+                server.set_weather(weather, duration);
+                context.send_message(feedback);
+                Ok(duration)
+            }
+        };
+        let with_duration = run.clone();
+        literal(name)
+            .executes(move |context| run(context, WEATHER_DURATION))
+            .then(
+                argument("duration", ArgumentType::Time)
+                    .executes(move |context| with_duration(context, context.time("duration")?)),
+            )
+    };
+    literal("weather")
+        .requires_permission("minecraft.command.weather", 2)
+        .then(set("clear", Weather::Clear, "Set the weather to clear"))
+        .then(set("rain", Weather::Rain, "Set the weather to rain"))
+        .then(set(
+            "thunder",
+            Weather::Thunder,
+            "Set the weather to rain & thunder",
+        ))
+}
+
+fn say(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("say")
+        .requires_permission("minecraft.command.say", 2)
+        .then(
+            argument("message", ArgumentType::Message).executes(move |context| {
+                let message = context.string("message")?;
+                server.broadcast(&format!("[{}] {message}", context.source.name()));
+                Ok(1)
+            }),
+        )
+}
+
+fn msg(server: Arc<dyn Server>) -> CommandBuilder {
+    let send = server.clone();
+    literal("msg")
+        .requires_permission("minecraft.command.msg", 0)
+        .then(
+            targets(&server, false).then(argument("message", ArgumentType::Message).executes(
+                move |context| {
+                    let message = context.string("message")?;
+                    let source = context.source.name();
+                    let players = players(context, send.as_ref(), "targets")?;
+                    for player in &players {
+                        send.send_message(player, &format!("{source} whispers to you: {message}"));
+                        context.send_message(&format!("You whisper to {player}: {message}"));
+                    }
+                    Ok(players.len() as i32)
+                },
+            )),
+        )
+}
+
+fn seed(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("seed")
+        .requires_permission("minecraft.command.seed", 2)
+        .executes(move |context| {
+            let seed = server.seed();
+            context.send_message(&format!("Seed: [{seed}]"));
+            Ok(seed as i32)
+        })
+}
+
+fn tps(server: Arc<dyn Server>) -> CommandBuilder {
+    literal("tps")
+        .requires_permission("minecraft.command.tps", 2)
+        .executes(move |context| {
+            let report = server
+                .tick_report()
+                .ok_or(CommandError::new(NO_TICK_REPORT))?;
+            for line in report.to_string().lines() {
+                context.send_message(line);
+            }
+            Ok(report.tps[0].round() as i32)
+        })
+}
+
+fn difficulty(server: Arc<dyn Server>) -> CommandBuilder {
+    let query = server.clone();
+    Difficulty::ALL.into_iter().fold(
+        literal("difficulty")
+            .requires_permission("minecraft.command.difficulty", 2)
+            .executes(move |context| {
+                let difficulty = query.difficulty();
+                context.send_message(&format!(
+                    "The difficulty is {}",
+                    capitalized(difficulty.name())
+                ));
+                Ok(difficulty.id() as i32)
+            }),
+        |command, difficulty| {
+            let server = server.clone();
+            command.then(literal(difficulty.name()).executes(move |context| {
+                if server.difficulty() == difficulty {
+                    return Err(CommandError::new(format!(
+                        "The difficulty did not change; it is already set to {}",
+                        difficulty.name()
+                    )));
+                }
+                server.set_difficulty(difficulty);
+                context.send_message(&format!(
+                    "The difficulty has been set to {}",
+                    capitalized(difficulty.name())
+                ));
+                Ok(difficulty.id() as i32)
+            }))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeSet, HashMap},
+        sync::{Arc, Mutex},
+    };
+
+    use uuid::Uuid;
+    use zr_data_type::vector::Vector3;
+    use zr_registry::item::Item;
+
+    use super::{register, Server};
+    use crate::{
+        command::{CommandSource, Dispatcher},
+        entity::player::GameMode,
+        item::ItemStack,
+        tick::TickReport,
+        world::{Difficulty, Weather},
+    };
+
+    #[derive(Default)]
+    struct State {
+        stopped: bool,
+        players: Vec<(String, Vector3<f64>)>,
+        /// offline players who joined before
+        known: Vec<String>,
+        messages: Vec<(Option<String>, String)>,
+        kicked: Vec<(String, String)>,
+        banned: HashMap<String, String>,
+        ops: BTreeSet<String>,
+        whitelist: BTreeSet<String>,
+        whitelist_enabled: bool,
+        game_modes: HashMap<String, GameMode>,
+        given: Vec<(String, ItemStack)>,
+        day_time: i64,
+        weather: (Weather, i32),
+        difficulty: Difficulty,
+    }
+
+    #[derive(Default)]
+    struct FakeServer(Mutex<State>);
+
+    impl FakeServer {
+        fn with_players(players: &[&str]) -> Arc<Self> {
+            let server = Self::default();
+            server.0.lock().unwrap().players = players
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.to_string(), Vector3::new(i as f64 * 10.0, 64.0, 0.0)))
+                .collect();
+            Arc::new(server)
+        }
+
+        fn state(&self) -> std::sync::MutexGuard<'_, State> {
+            self.0.lock().unwrap()
+        }
+    }
+
+    impl Server for FakeServer {
+        fn stop(&self) {
+            self.state().stopped = true;
+        }
+
+        fn players(&self) -> Vec<String> {
+            self.state()
+                .players
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect()
+        }
+
+        fn max_players(&self) -> usize {
+            20
+        }
+
+        fn position(&self, player: &str) -> Option<(Vector3<f64>, (f32, f32))> {
+            self.state()
+                .players
+                .iter()
+                .find(|(name, _)| name == player)
+                .map(|(_, position)| (*position, (0.0, 0.0)))
+        }
+
+        fn send_message(&self, player: &str, message: &str) {
+            self.state()
+                .messages
+                .push((Some(player.to_string()), message.to_string()));
+        }
+
+        fn broadcast(&self, message: &str) {
+            self.state().messages.push((None, message.to_string()));
+        }
+
+        fn kick(&self, player: &str, reason: &str) {
+            let mut state = self.state();
+            state.players.retain(|(name, _)| name != player);
+            state.kicked.push((player.to_string(), reason.to_string()));
+        }
+
+        fn uuid(&self, player: &str) -> Option<Uuid> {
+            let state = self.state();
+            let mut names = state
+                .players
+                .iter()
+                .map(|(name, _)| name)
+                .chain(&state.known);
+            let index = names.position(|name| name == player)?;
+            Some(Uuid::from_u128(index as u128 + 1))
+        }
+
+        fn ban(&self, player: &str, _uuid: Uuid, _source: &str, reason: &str) -> bool {
+            self.state()
+                .banned
+                .insert(player.to_string(), reason.to_string())
+                .is_none()
+        }
+
+        fn pardon(&self, player: &str) -> bool {
+            self.state().banned.remove(player).is_some()
+        }
+
+        fn op(&self, player: &str, _uuid: Uuid) -> bool {
+            self.state().ops.insert(player.to_string())
+        }
+
+        fn deop(&self, player: &str) -> bool {
+            self.state().ops.remove(player)
+        }
+
+        fn whitelist(&self) -> Vec<String> {
+            self.state().whitelist.iter().cloned().collect()
+        }
+
+        fn is_whitelist_enabled(&self) -> bool {
+            self.state().whitelist_enabled
+        }
+
+        fn set_whitelist_enabled(&self, enabled: bool) {
+            self.state().whitelist_enabled = enabled;
+        }
+
+        fn whitelist_add(&self, player: &str) -> bool {
+            self.state().whitelist.insert(player.to_string())
+        }
+
+        fn whitelist_remove(&self, player: &str) -> bool {
+            self.state().whitelist.remove(player)
+        }
+
+        fn reload_whitelist(&self) {}
+
+        fn teleport(&self, player: &str, position: Vector3<f64>, _rotation: (f32, f32)) {
+            if let Some((_, current)) = self
+                .state()
+                .players
+                .iter_mut()
+                .find(|(name, _)| name == player)
+            {
+                *current = position;
+            }
+        }
+
+        fn set_game_mode(&self, player: &str, game_mode: GameMode) {
+            self.state()
+                .game_modes
+                .insert(player.to_string(), game_mode);
+        }
+
+        fn give(&self, player: &str, stack: ItemStack) {
+            self.state().given.push((player.to_string(), stack));
+        }
+
+        fn game_time(&self) -> i64 {
+            123456
+        }
+
+        fn day_time(&self) -> i64 {
+            self.state().day_time
+        }
+
+        fn set_day_time(&self, time: i64) {
+            self.state().day_time = time;
+        }
+
+        fn set_weather(&self, weather: Weather, duration: i32) {
+            self.state().weather = (weather, duration);
+        }
+
+        fn seed(&self) -> i64 {
+            42
+        }
+
+        fn difficulty(&self) -> Difficulty {
+            self.state().difficulty
+        }
+
+        fn set_difficulty(&self, difficulty: Difficulty) {
+            self.state().difficulty = difficulty;
+        }
+
+        fn tick_report(&self) -> Option<TickReport> {
+            Some(TickReport {
+                tick: 1200,
+                skipped: 0,
+                tps: [19.6, 19.9, 20.0],
+                mspt_avg: 12.5,
+                mspt_min: 3.0,
+                mspt_max: 48.25,
+            })
+        }
+    }
+
+    struct FakeSource {
+        name: String,
+        op_level: u8,
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl FakeSource {
+        fn new(name: &str, op_level: u8) -> Self {
+            Self {
+                name: name.to_string(),
+                op_level,
+                messages: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn messages(&self) -> Vec<String> {
+            std::mem::take(&mut self.messages.lock().unwrap())
+        }
+    }
+
+    impl CommandSource for FakeSource {
+        fn name(&self) -> String {
+            self.name.clone()
+        }
+
+        fn send_message(&self, message: &str) {
+            self.messages.lock().unwrap().push(message.to_string());
+        }
+
+        fn op_level(&self) -> u8 {
+            self.op_level
+        }
+
+        fn permission(&self, _node: &str) -> Option<bool> {
+            None
+        }
+    }
+
+    fn setup(players: &[&str]) -> (Arc<FakeServer>, Dispatcher) {
+        let server = FakeServer::with_players(players);
+        let mut dispatcher = Dispatcher::new();
+        register(&mut dispatcher, server.clone());
+        (server, dispatcher)
+    }
+
+    #[test]
+    fn test_stop() {
+        let (server, dispatcher) = setup(&[]);
+        assert!(dispatcher
+            .execute(&FakeSource::new("Alice", 3), "stop")
+            .is_err());
+        assert!(!server.state().stopped);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "stop"), Ok(1));
+        assert!(server.state().stopped);
+        assert_eq!(admin.messages(), vec!["Stopping the server"]);
+    }
+
+    #[test]
+    fn test_list() {
+        let (_, dispatcher) = setup(&["Alice", "Bob"]);
+        let source = FakeSource::new("Alice", 0);
+        assert_eq!(dispatcher.execute(&source, "list"), Ok(2));
+        assert_eq!(
+            source.messages(),
+            vec!["There are 2 of a max of 20 players online: Alice, Bob"]
+        );
+    }
+
+    #[test]
+    fn test_kick() {
+        let (server, dispatcher) = setup(&["Alice", "Bob"]);
+        assert!(dispatcher
+            .execute(&FakeSource::new("Alice", 2), "kick Bob")
+            .is_err());
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "kick Bob being rude"), Ok(1));
+        assert_eq!(
+            server.state().kicked,
+            vec![("Bob".to_string(), "being rude".to_string())]
+        );
+        assert_eq!(admin.messages(), vec!["Kicked Bob: being rude"]);
+        let err = dispatcher.execute(&admin, "kick Bob").unwrap_err();
+        assert_eq!(err.message, "No player was found");
+        assert_eq!(dispatcher.execute(&admin, "kick @a"), Ok(1));
+        assert_eq!(server.state().kicked[1].1, "Kicked by an operator");
+    }
+
+    #[test]
+    fn test_ban() {
+        let (server, dispatcher) = setup(&["Alice"]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "ban Alice griefing"), Ok(1));
+        assert_eq!(server.state().banned["Alice"], "griefing");
+        assert_eq!(server.state().kicked[0].0, "Alice");
+        // offline players must have joined before
+        let err = dispatcher.execute(&admin, "ban Carol").unwrap_err();
+        assert_eq!(err.message, "That player does not exist");
+        assert!(!server.state().banned.contains_key("Carol"));
+        server.state().known.push("Carol".to_string());
+        assert_eq!(dispatcher.execute(&admin, "ban Carol"), Ok(1));
+        assert_eq!(server.state().banned["Carol"], "Banned by an operator");
+        let err = dispatcher.execute(&admin, "ban Carol").unwrap_err();
+        assert_eq!(err.message, "Nothing changed. The player is already banned");
+        assert!(dispatcher
+            .execute(&FakeSource::new("Bob", 2), "ban Carol")
+            .is_err());
+    }
+
+    #[test]
+    fn test_pardon() {
+        let (server, dispatcher) = setup(&[]);
+        let admin = FakeSource::new("Server", 4);
+        server.ban("Alice", Uuid::new_v4(), "Server", "griefing");
+        assert_eq!(dispatcher.execute(&admin, "pardon Alice"), Ok(1));
+        assert!(server.state().banned.is_empty());
+        assert_eq!(admin.messages(), vec!["Unbanned Alice"]);
+        assert!(dispatcher.execute(&admin, "pardon Alice").is_err());
+    }
+
+    #[test]
+    fn test_op() {
+        let (server, dispatcher) = setup(&["Alice"]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "op Alice"), Ok(1));
+        assert!(server.state().ops.contains("Alice"));
+        assert_eq!(admin.messages(), vec!["Made Alice a server operator"]);
+        let err = dispatcher.execute(&admin, "op Alice").unwrap_err();
+        assert_eq!(
+            err.message,
+            "Nothing changed. The player already is an operator"
+        );
+        let err = dispatcher.execute(&admin, "op Carol").unwrap_err();
+        assert_eq!(err.message, "That player does not exist");
+        assert!(!server.state().ops.contains("Carol"));
+    }
+
+    #[test]
+    fn test_deop() {
+        let (server, dispatcher) = setup(&["Alice"]);
+        let admin = FakeSource::new("Server", 4);
+        server.op("Alice", Uuid::new_v4());
+        assert_eq!(dispatcher.execute(&admin, "deop Alice"), Ok(1));
+        assert!(server.state().ops.is_empty());
+        assert_eq!(
+            admin.messages(),
+            vec!["Made Alice no longer a server operator"]
+        );
+        assert!(dispatcher.execute(&admin, "deop Alice").is_err());
+    }
+
+    #[test]
+    fn test_whitelist() {
+        let (server, dispatcher) = setup(&[]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "whitelist on"), Ok(1));
+        assert!(server.state().whitelist_enabled);
+        assert!(dispatcher.execute(&admin, "whitelist on").is_err());
+        assert_eq!(dispatcher.execute(&admin, "whitelist add Alice"), Ok(1));
+        assert_eq!(dispatcher.execute(&admin, "whitelist add Bob"), Ok(1));
+        assert!(dispatcher.execute(&admin, "whitelist add Bob").is_err());
+        assert_eq!(dispatcher.execute(&admin, "whitelist remove Alice"), Ok(1));
+        admin.messages();
+        assert_eq!(dispatcher.execute(&admin, "whitelist list"), Ok(1));
+        assert_eq!(
+            admin.messages(),
+            vec!["There are 1 whitelisted player(s): Bob"]
+        );
+        assert_eq!(dispatcher.execute(&admin, "whitelist reload"), Ok(1));
+        assert_eq!(dispatcher.execute(&admin, "whitelist off"), Ok(1));
+        assert!(!server.state().whitelist_enabled);
+    }
+
+    #[test]
+    fn test_teleport() {
+        let (server, dispatcher) = setup(&["Alice", "Bob"]);
+        let alice = FakeSource::new("Alice", 2);
+        assert_eq!(dispatcher.execute(&alice, "tp ~ ~10 5"), Ok(1));
+        assert_eq!(
+            server.position("Alice").unwrap().0,
+            Vector3::new(0.0, 74.0, 5.5)
+        );
+        assert_eq!(
+            alice.messages(),
+            vec!["Teleported Alice to 0.000000, 74.000000, 5.500000"]
+        );
+        assert_eq!(dispatcher.execute(&alice, "teleport Bob"), Ok(1));
+        assert_eq!(
+            server.position("Alice").unwrap().0,
+            Vector3::new(10.0, 64.0, 0.0)
+        );
+        assert_eq!(dispatcher.execute(&alice, "tp @a 1 2 3"), Ok(2));
+        assert_eq!(
+            server.position("Bob").unwrap().0,
+            Vector3::new(1.5, 2.0, 3.5)
+        );
+        assert_eq!(
+            alice.messages()[1],
+            "Teleported 2 players to 1.500000, 2.000000, 3.500000"
+        );
+
+        // the console has no position to move
+        let err = dispatcher
+            .execute(&FakeSource::new("Server", 4), "tp 1 2 3")
+            .unwrap_err();
+        assert_eq!(err.message, "A player is required to run this command here");
+        assert!(dispatcher
+            .execute(&FakeSource::new("Bob", 0), "tp 1 2 3")
+            .is_err());
+    }
+
+    #[test]
+    fn test_gamemode() {
+        let (server, dispatcher) = setup(&["Alice", "Bob"]);
+        let alice = FakeSource::new("Alice", 2);
+        assert_eq!(dispatcher.execute(&alice, "gamemode creative"), Ok(1));
+        assert_eq!(server.state().game_modes["Alice"], GameMode::Creative);
+        assert_eq!(alice.messages(), vec!["Set own game mode to Creative Mode"]);
+        assert_eq!(dispatcher.execute(&alice, "gamemode spectator Bob"), Ok(1));
+        assert_eq!(server.state().game_modes["Bob"], GameMode::Spectator);
+        assert_eq!(
+            alice.messages(),
+            vec!["Set Bob's game mode to Spectator Mode"]
+        );
+        assert_eq!(
+            server.state().messages,
+            vec![(
+                Some("Bob".to_string()),
+                "Your game mode has been updated to Spectator Mode".to_string()
+            )]
+        );
+        assert!(dispatcher.execute(&alice, "gamemode flying").is_err());
+    }
+
+    #[test]
+    fn test_give() {
+        let (server, dispatcher) = setup(&["Alice"]);
+        let alice = FakeSource::new("Alice", 2);
+        assert_eq!(
+            dispatcher.execute(&alice, "give @s minecraft:stone 100"),
+            Ok(1)
+        );
+        assert_eq!(
+            server.state().given,
+            vec![
+                ("Alice".to_string(), ItemStack::new(Item::Stone, 64)),
+                ("Alice".to_string(), ItemStack::new(Item::Stone, 36)),
+            ]
+        );
+        assert_eq!(
+            alice.messages(),
+            vec!["Gave 100 [minecraft:stone] to Alice"]
+        );
+        let err = dispatcher
+            .execute(&alice, "give Alice minecraft:nope")
+            .unwrap_err();
+        assert_eq!(err.message, "Unknown item 'minecraft:nope'");
+        assert!(dispatcher.execute(&alice, "give Alice dirt 6401").is_err());
+    }
+
+    #[test]
+    fn test_time() {
+        let (server, dispatcher) = setup(&[]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "time set night"), Ok(13000));
+        assert_eq!(server.day_time(), 13000);
+        assert_eq!(dispatcher.execute(&admin, "time add 1d"), Ok(13000));
+        assert_eq!(server.day_time(), 37000);
+        assert_eq!(dispatcher.execute(&admin, "time query day"), Ok(1));
+        assert_eq!(dispatcher.execute(&admin, "time query daytime"), Ok(13000));
+        assert_eq!(
+            dispatcher.execute(&admin, "time query gametime"),
+            Ok(123456)
+        );
+        assert_eq!(dispatcher.execute(&admin, "time set 100"), Ok(100));
+        assert_eq!(admin.messages().last().unwrap(), "Set the time to 100");
+    }
+
+    #[test]
+    fn test_weather() {
+        let (server, dispatcher) = setup(&[]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "weather thunder 10s"), Ok(200));
+        assert_eq!(server.state().weather, (Weather::Thunder, 200));
+        assert_eq!(admin.messages(), vec!["Set the weather to rain & thunder"]);
+        assert_eq!(dispatcher.execute(&admin, "weather clear"), Ok(6000));
+        assert_eq!(server.state().weather.0, Weather::Clear);
+    }
+
+    #[test]
+    fn test_say() {
+        let (server, dispatcher) = setup(&["Alice"]);
+        assert!(dispatcher
+            .execute(&FakeSource::new("Alice", 0), "say hi")
+            .is_err());
+        assert_eq!(
+            dispatcher.execute(&FakeSource::new("Alice", 2), "say hello there"),
+            Ok(1)
+        );
+        assert_eq!(
+            server.state().messages,
+            vec![(None, "[Alice] hello there".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_msg() {
+        let (server, dispatcher) = setup(&["Alice", "Bob"]);
+        let alice = FakeSource::new("Alice", 0);
+        assert_eq!(dispatcher.execute(&alice, "msg Bob see you"), Ok(1));
+        assert_eq!(dispatcher.execute(&alice, "w Bob later"), Ok(1));
+        assert_eq!(
+            server.state().messages[0],
+            (
+                Some("Bob".to_string()),
+                "Alice whispers to you: see you".to_string()
+            )
+        );
+        assert_eq!(alice.messages()[1], "You whisper to Bob: later");
+        assert!(dispatcher.execute(&alice, "tell Carol hello").is_err());
+    }
+
+    #[test]
+    fn test_seed() {
+        let (_, dispatcher) = setup(&[]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "seed"), Ok(42));
+        assert_eq!(admin.messages(), vec!["Seed: [42]"]);
+        assert!(dispatcher
+            .execute(&FakeSource::new("Alice", 0), "seed")
+            .is_err());
+    }
+
+    #[test]
+    fn test_tps() {
+        let (_, dispatcher) = setup(&[]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "tps"), Ok(20));
+        assert_eq!(
+            admin.messages(),
+            vec![
+                "TPS from last 1m, 5m, 15m: 19.60, 19.90, 20.00",
+                "MSPT avg/min/max: 12.50/3.00/48.25, skipped ticks: 0"
+            ]
+        );
+        assert!(dispatcher
+            .execute(&FakeSource::new("Alice", 0), "tps")
+            .is_err());
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let (server, dispatcher) = setup(&[]);
+        let admin = FakeSource::new("Server", 4);
+        assert_eq!(dispatcher.execute(&admin, "difficulty"), Ok(2));
+        assert_eq!(admin.messages(), vec!["The difficulty is Normal"]);
+        assert_eq!(dispatcher.execute(&admin, "difficulty hard"), Ok(3));
+        assert_eq!(server.difficulty(), Difficulty::Hard);
+        assert_eq!(
+            admin.messages(),
+            vec!["The difficulty has been set to Hard"]
+        );
+        let err = dispatcher.execute(&admin, "difficulty hard").unwrap_err();
+        assert_eq!(
+            err.message,
+            "The difficulty did not change; it is already set to hard"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+use std::{
+    fmt::Display,
+    io::{BufRead, IsTerminal},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+};
+
+use rustyline::{
+    completion::Completer, error::ReadlineError, highlight::Highlighter, hint::Hinter,
+    history::DefaultHistory, validate::Validator, Context, Editor, ExternalPrinter, Helper,
+};
+
+use crate::command::{CommandHandler, ConsoleSource};
+
+const PROMPT: &str = "> ";
+
+/// Prints above the prompt while the console is reading a line
+static PRINTER: Mutex<Option<Box<dyn ExternalPrinter + Send>>> = Mutex::new(None);
+
+fn print(message: impl Display, error: bool) {
+    if let Ok(mut printer) = PRINTER.lock() {
+        if let Some(printer) = printer.as_mut() {
+            if printer.print(format!("{message}\n")).is_ok() {
+                return;
+            }
+        }
+    }
+    if error {
+        eprintln!("{message}");
+    } else {
+        println!("{message}");
+    }
+}
+
+/// Print a line of log without breaking the line being typed in the console
+pub fn log(message: impl Display) {
+    print(message, false);
+}
+
+/// Same as [`log`], on stderr when there is no interactive console
+pub fn error(message: impl Display) {
+    print(message, true);
+}
+
+/// Run a line typed in the console, with or without its leading `/`
+fn dispatch<H: CommandHandler>(handler: &Mutex<H>, line: &str) {
+    let command = line.trim();
+    let command = command.strip_prefix('/').unwrap_or(command);
+    if command.is_empty() {
+        return;
+    }
+    match handler.lock() {
+        Ok(mut handler) => {
+            if let Err(err) = handler.execute(&ConsoleSource, command) {
+                error(err);
+            }
+        }
+        Err(err) => error(format!("{err:?}")),
+    }
+}
+
+/// Tab completion from the command handler
+struct ConsoleHelper<H> {
+    handler: Arc<Mutex<H>>,
+}
+
+impl<H: CommandHandler> Completer for ConsoleHelper<H> {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let (prefix, command) = match line.strip_prefix('/') {
+            Some(command) => (1, command),
+            None => (0, line),
+        };
+        let suggestions = match self.handler.lock() {
+            Ok(handler) => handler.complete(&ConsoleSource, command),
+            Err(_) => return Ok((pos, Vec::new())),
+        };
+        // the suggestions start after the `/`
+        let start = prefix + suggestions.start;
+        let candidates = suggestions.matches;
+        Ok((start, candidates))
+    }
+}
+
+impl<H> Hinter for ConsoleHelper<H> {
+    type Hint = String;
+}
+
+impl<H> Highlighter for ConsoleHelper<H> {}
+
+impl<H> Validator for ConsoleHelper<H> {}
+
+impl<H: CommandHandler> Helper for ConsoleHelper<H> {}
+
+/// Read the commands typed on stdin, with history and tab completion on a terminal
+#[derive(Debug, Default)]
+pub struct Console {
+    history: Option<PathBuf>,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the history of the commands in `path` between two runs
+    pub fn with_history(mut self, path: impl Into<PathBuf>) -> Self {
+        self.history = Some(path.into());
+        self
+    }
+
+    /// Read lines until stdin is closed, Ctrl-C runs `stop`
+    pub fn run<H>(self, handler: Arc<Mutex<H>>) -> JoinHandle<()>
+    where
+        H: CommandHandler + 'static,
+    {
+        std::thread::spawn(move || {
+            if !std::io::stdin().is_terminal() {
+                return Self::read_plain(&handler);
+            }
+            if let Err(err) = self.read_interactive(&handler) {
+                error(format!("console: {err}"));
+                Self::read_plain(&handler);
+            }
+        })
+    }
+
+    fn read_plain<H: CommandHandler>(handler: &Mutex<H>) {
+        for line in std::io::stdin().lock().lines() {
+            match line {
+                Ok(line) => dispatch(handler, &line),
+                Err(_) => break,
+            }
+        }
+    }
+
+    fn read_interactive<H>(&self, handler: &Arc<Mutex<H>>) -> rustyline::Result<()>
+    where
+        H: CommandHandler + 'static,
+    {
+        let mut editor: Editor<ConsoleHelper<H>, DefaultHistory> = Editor::new()?;
+        editor.set_helper(Some(ConsoleHelper {
+            handler: handler.clone(),
+        }));
+        if let Some(history) = &self.history {
+            // missing on the first run
+            let _ = editor.load_history(history);
+        }
+        if let Ok(mut printer) = PRINTER.lock() {
+            *printer = Some(Box::new(editor.create_external_printer()?));
+        }
+        loop {
+            match editor.readline(PROMPT) {
+                Ok(line) => {
+                    if !line.trim().is_empty() {
+                        editor.add_history_entry(line.as_str())?;
+                    }
+                    dispatch(handler, &line);
+                }
+                // the terminal is in raw mode, no SIGINT is sent
+                Err(ReadlineError::Interrupted) => dispatch(handler, "stop"),
+                Err(ReadlineError::Eof) => break,
+                Err(err) => {
+                    error(format!("console: {err}"));
+                    break;
+                }
+            }
+        }
+        if let Ok(mut printer) = PRINTER.lock() {
+            *printer = None;
+        }
+        if let Some(history) = &self.history {
+            editor.save_history(history)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::dispatch;
+    use crate::command::{CommandError, CommandHandler, CommandSource};
+
+    #[derive(Default)]
+    struct Recorder {
+        commands: Vec<(String, u8)>,
+    }
+
+    impl CommandHandler for Recorder {
+        fn execute(
+            &mut self,
+            source: &dyn CommandSource,
+            command: &str,
+        ) -> Result<i32, CommandError> {
+            self.commands.push((command.to_string(), source.op_level()));
+            Err(CommandError::new("Unknown command"))
+        }
+    }
+
+    #[test]
+    fn test_dispatch() {
+        let handler = Mutex::new(Recorder::default());
+        dispatch(&handler, "/list");
+        dispatch(&handler, "  say hello \n");
+        dispatch(&handler, "   ");
+        let handler = handler.into_inner().unwrap();
+        assert_eq!(
+            handler.commands,
+            vec![("list".to_string(), 4), ("say hello".to_string(), 4)]
+        );
+    }
+}
@@ -0,0 +1,419 @@
+use rand::Rng;
+use zr_data_type::vector::Vector3;
+use zr_registry::{damage_type::DamageType, entity_type::EntityType};
+
+use super::{
+    entity_kind,
+    traits::{
+        ageable::AgeState, breedable::LoveState, damageable::Health, steerable::SteeringState,
+        Ageable, Breedable, Damageable, Shearable, Sittable, Steerable, Tameable,
+    },
+    Entity, EntityKind,
+};
+use crate::{
+    item::ItemStack,
+    metadata::{
+        schema::{living_entity, pig, sheep, strider},
+        MetadataKey,
+    },
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DyeColor {
+    #[default]
+    White,
+    Orange,
+    Magenta,
+    LightBlue,
+    Yellow,
+    Lime,
+    Pink,
+    Gray,
+    LightGray,
+    Cyan,
+    Purple,
+    Blue,
+    Brown,
+    Green,
+    Red,
+    Black,
+}
+
+impl DyeColor {
+    pub const ALL: [DyeColor; 16] = [
+        Self::White,
+        Self::Orange,
+        Self::Magenta,
+        Self::LightBlue,
+        Self::Yellow,
+        Self::Lime,
+        Self::Pink,
+        Self::Gray,
+        Self::LightGray,
+        Self::Cyan,
+        Self::Purple,
+        Self::Blue,
+        Self::Brown,
+        Self::Green,
+        Self::Red,
+        Self::Black,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::White => "white",
+            Self::Orange => "orange",
+            Self::Magenta => "magenta",
+            Self::LightBlue => "light_blue",
+            Self::Yellow => "yellow",
+            Self::Lime => "lime",
+            Self::Pink => "pink",
+            Self::Gray => "gray",
+            Self::LightGray => "light_gray",
+            Self::Cyan => "cyan",
+            Self::Purple => "purple",
+            Self::Blue => "blue",
+            Self::Brown => "brown",
+            Self::Green => "green",
+            Self::Red => "red",
+            Self::Black => "black",
+        }
+    }
+}
+
+/// Health, age and love mode shared by every animal
+#[derive(Debug)]
+pub struct Animal {
+    entity: Entity,
+    health: Health,
+    age: AgeState,
+    love: LoveState,
+}
+
+impl Animal {
+    fn new(entity_type: EntityType, position: Vector3<f64>, max_health: f32) -> Self {
+        let mut entity = Entity::new(entity_type, position);
+        entity.metadata_mut().set(living_entity::HEALTH, max_health);
+        Self {
+            entity,
+            health: Health::new(max_health),
+            age: AgeState::default(),
+            love: LoveState::default(),
+        }
+    }
+}
+
+/// Implement the animal behaviours of a kind holding an [`Animal`] in its `animal` field,
+/// the extra items are added to its `Damageable` implementation
+macro_rules! animal {
+    ($ty: ident, $food: expr $(, $damageable: item)*) => {
+        entity_kind!($ty, animal.entity);
+
+        impl Damageable for $ty {
+            fn health_state(&self) -> &Health {
+                &self.animal.health
+            }
+
+            fn health_state_mut(&mut self) -> &mut Health {
+                &mut self.animal.health
+            }
+
+            $($damageable)*
+        }
+
+        impl Ageable for $ty {
+            fn age_state(&self) -> &AgeState {
+                &self.animal.age
+            }
+
+            fn age_state_mut(&mut self) -> &mut AgeState {
+                &mut self.animal.age
+            }
+        }
+
+        impl Breedable for $ty {
+            fn love_state(&self) -> &LoveState {
+                &self.animal.love
+            }
+
+            fn love_state_mut(&mut self) -> &mut LoveState {
+                &mut self.animal.love
+            }
+
+            fn food(&self) -> &'static [&'static str] {
+                $food
+            }
+        }
+    };
+}
+
+#[derive(Debug)]
+pub struct Cow {
+    animal: Animal,
+}
+
+animal!(Cow, &["minecraft:wheat"]);
+
+impl Cow {
+    pub fn new(position: Vector3<f64>) -> Self {
+        Self {
+            animal: Animal::new(EntityType::Cow, position, 10.0),
+        }
+    }
+}
+
+impl EntityKind for Cow {
+    fn tick(&mut self) {
+        self.tick_damage();
+        self.tick_age();
+        self.tick_love();
+    }
+}
+
+#[derive(Debug)]
+pub struct Sheep {
+    animal: Animal,
+}
+
+animal!(Sheep, &["minecraft:wheat"]);
+
+impl Sheep {
+    pub fn new(position: Vector3<f64>) -> Self {
+        Self {
+            animal: Animal::new(EntityType::Sheep, position, 8.0),
+        }
+    }
+
+    pub fn color(&self) -> DyeColor {
+        let flags = self.animal.entity.metadata().bits(sheep::FLAGS);
+        DyeColor::ALL[(flags & 0x0F) as usize]
+    }
+
+    pub fn set_color(&mut self, color: DyeColor) {
+        let flags = self.animal.entity.metadata().bits(sheep::FLAGS);
+        let flags = (flags & !0x0F) | color as u8;
+        self.animal
+            .entity
+            .metadata_mut()
+            .set(sheep::FLAGS, flags as i8);
+    }
+
+    /// Identifier of the wool item of its color
+    pub fn wool(&self) -> String {
+        format!("minecraft:{}_wool", self.color().name())
+    }
+
+    /// Eating grass regrows the wool and makes a baby grow up faster
+    pub fn eat_grass(&mut self) {
+        self.set_sheared(false);
+        if self.is_baby() {
+            self.age_up(60);
+        }
+    }
+}
+
+impl EntityKind for Sheep {
+    fn tick(&mut self) {
+        self.tick_damage();
+        self.tick_age();
+        self.tick_love();
+    }
+}
+
+impl Shearable for Sheep {
+    fn is_sheared(&self) -> bool {
+        self.animal.entity.metadata().bits(sheep::FLAGS) & sheep::SHEARED != 0
+    }
+
+    fn set_sheared(&mut self, sheared: bool) {
+        self.animal
+            .entity
+            .metadata_mut()
+            .set_bit(sheep::FLAGS, sheep::SHEARED, sheared);
+    }
+
+    fn can_shear(&self) -> bool {
+        !self.is_sheared() && !self.is_baby()
+    }
+
+    /// 1 to 3 wool of the sheep color
+    fn shear_drops(&mut self) -> Vec<ItemStack> {
+        match self.wool().parse() {
+            Ok(item) => vec![ItemStack::new(item, rand::thread_rng().gen_range(1..=3))],
+            // not in the checked-in registry report
+            Err(_) => Vec::new(),
+        }
+    }
+}
+
+/// Boost time and saddle metadata keys of a steerable kind
+macro_rules! steerable {
+    ($ty: ident, $item: expr, $schema: ident) => {
+        impl Steerable for $ty {
+            fn steering_state(&self) -> &SteeringState {
+                &self.steering
+            }
+
+            fn steering_state_mut(&mut self) -> &mut SteeringState {
+                &mut self.steering
+            }
+
+            fn steering_item(&self) -> &'static str {
+                $item
+            }
+
+            fn saddle_key(&self) -> MetadataKey<bool> {
+                $schema::SADDLE
+            }
+
+            fn boost_time_key(&self) -> MetadataKey<i32> {
+                $schema::BOOST_TIME
+            }
+        }
+    };
+}
+
+#[derive(Debug)]
+pub struct Pig {
+    animal: Animal,
+    steering: SteeringState,
+}
+
+animal!(
+    Pig,
+    &["minecraft:carrot", "minecraft:potato", "minecraft:beetroot"]
+);
+steerable!(Pig, "minecraft:carrot_on_a_stick", pig);
+
+impl Pig {
+    pub fn new(position: Vector3<f64>) -> Self {
+        Self {
+            animal: Animal::new(EntityType::Pig, position, 10.0),
+            steering: SteeringState::default(),
+        }
+    }
+}
+
+impl EntityKind for Pig {
+    fn tick(&mut self) {
+        self.tick_damage();
+        self.tick_age();
+        self.tick_love();
+        self.tick_boost();
+    }
+}
+
+#[derive(Debug)]
+pub struct Strider {
+    animal: Animal,
+    steering: SteeringState,
+}
+
+animal!(
+    Strider,
+    &["minecraft:warped_fungus"],
+    fn is_immune_to(&self, damage_type: DamageType) -> bool {
+        damage_type.is_fire()
+    }
+);
+steerable!(Strider, "minecraft:warped_fungus_on_a_stick", strider);
+
+impl Strider {
+    pub fn new(position: Vector3<f64>) -> Self {
+        Self {
+            animal: Animal::new(EntityType::Strider, position, 20.0),
+            steering: SteeringState::default(),
+        }
+    }
+}
+
+impl EntityKind for Strider {
+    fn tick(&mut self) {
+        self.tick_damage();
+        self.tick_age();
+        self.tick_love();
+        self.tick_boost();
+    }
+}
+
+#[derive(Debug)]
+pub struct Wolf {
+    animal: Animal,
+}
+
+animal!(
+    Wolf,
+    &[
+        "minecraft:beef",
+        "minecraft:cooked_beef",
+        "minecraft:chicken",
+        "minecraft:cooked_chicken",
+        "minecraft:porkchop",
+        "minecraft:cooked_porkchop",
+        "minecraft:mutton",
+        "minecraft:cooked_mutton",
+        "minecraft:rabbit",
+        "minecraft:cooked_rabbit",
+        "minecraft:rotten_flesh",
+    ]
+);
+
+impl Wolf {
+    pub fn new(position: Vector3<f64>) -> Self {
+        Self {
+            animal: Animal::new(EntityType::Wolf, position, 8.0),
+        }
+    }
+}
+
+impl EntityKind for Wolf {
+    fn tick(&mut self) {
+        self.tick_damage();
+        self.tick_age();
+        self.tick_love();
+    }
+}
+
+impl Tameable for Wolf {
+    fn taming_items(&self) -> &'static [&'static str] {
+        &["minecraft:bone"]
+    }
+
+    fn on_tamed(&mut self) {
+        self.set_max_health(40.0);
+        self.set_health(40.0);
+    }
+}
+
+impl Sittable for Wolf {}
+
+#[derive(Debug)]
+pub struct Cat {
+    animal: Animal,
+}
+
+animal!(Cat, &["minecraft:cod", "minecraft:salmon"]);
+
+impl Cat {
+    pub fn new(position: Vector3<f64>) -> Self {
+        Self {
+            animal: Animal::new(EntityType::Cat, position, 10.0),
+        }
+    }
+}
+
+impl EntityKind for Cat {
+    fn tick(&mut self) {
+        self.tick_damage();
+        self.tick_age();
+        self.tick_love();
+    }
+}
+
+impl Tameable for Cat {
+    fn taming_items(&self) -> &'static [&'static str] {
+        &["minecraft:cod", "minecraft:salmon"]
+    }
+}
+
+impl Sittable for Cat {}
@@ -1,8 +1,185 @@
+pub mod animal;
 pub mod player;
-use player::Player;
+pub mod projectile;
+pub mod store;
+pub mod tracker;
+pub mod traits;
+
+use std::{
+    any::Any,
+    fmt::Debug,
+    sync::atomic::{AtomicI32, Ordering},
+};
+
+use uuid::Uuid;
+use zr_data_type::vector::Vector3;
+pub use zr_registry::entity_type::EntityType;
+
+use crate::metadata::Metadata;
 
 // https://hub.spigotmc.org/javadocs/spigot/org/bukkit/entity/Entity.html
 
-pub enum EntityType {
-    Player(Player),
+static NEXT_ENTITY_ID: AtomicI32 = AtomicI32::new(1);
+
+/// Entity ids are shared by every world, like vanilla does
+pub fn next_entity_id() -> i32 {
+    NEXT_ENTITY_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// An entity with its kind specific state, stored in an [`EntityStore`](store::EntityStore)
+pub trait EntityKind: AsRef<Entity> + AsMut<Entity> + Any + Debug + Send {
+    /// Advance the kind specific state by one tick
+    fn tick(&mut self) {}
+}
+
+#[derive(Debug, Clone)]
+pub struct Entity {
+    id: i32,
+    uuid: Uuid,
+    entity_type: EntityType,
+    position: Vector3<f64>,
+    velocity: Vector3<f64>,
+    yaw: f32,
+    pitch: f32,
+    head_yaw: f32,
+    on_ground: bool,
+    /// `data` field of `SpawnEntity`, meaning depends on the entity type
+    data: i32,
+    metadata: Metadata,
+    teleported: bool,
+}
+
+impl Entity {
+    /// New entity with a fresh id and a random UUID
+    pub fn new(entity_type: EntityType, position: Vector3<f64>) -> Self {
+        Self::with_uuid(entity_type, Uuid::new_v4(), position)
+    }
+
+    pub fn with_uuid(entity_type: EntityType, uuid: Uuid, position: Vector3<f64>) -> Self {
+        Self {
+            id: next_entity_id(),
+            uuid,
+            entity_type,
+            position,
+            velocity: Vector3::default(),
+            yaw: 0.0,
+            pitch: 0.0,
+            head_yaw: 0.0,
+            on_ground: false,
+            data: 0,
+            metadata: Metadata::new(),
+            teleported: false,
+        }
+    }
+
+    pub fn id(&self) -> i32 {
+        self.id
+    }
+
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    pub fn entity_type(&self) -> EntityType {
+        self.entity_type
+    }
+
+    pub fn position(&self) -> Vector3<f64> {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Vector3<f64>) {
+        self.position = position;
+    }
+
+    /// Move the entity and send its absolute position instead of a delta
+    pub fn teleport(&mut self, position: Vector3<f64>) {
+        self.position = position;
+        self.teleported = true;
+    }
+
+    pub fn velocity(&self) -> Vector3<f64> {
+        self.velocity
+    }
+
+    pub fn set_velocity(&mut self, velocity: Vector3<f64>) {
+        self.velocity = velocity;
+    }
+
+    pub fn yaw(&self) -> f32 {
+        self.yaw
+    }
+
+    pub fn pitch(&self) -> f32 {
+        self.pitch
+    }
+
+    pub fn set_rotation(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = yaw;
+        self.pitch = pitch;
+    }
+
+    pub fn head_yaw(&self) -> f32 {
+        self.head_yaw
+    }
+
+    pub fn set_head_yaw(&mut self, head_yaw: f32) {
+        self.head_yaw = head_yaw;
+    }
+
+    pub fn on_ground(&self) -> bool {
+        self.on_ground
+    }
+
+    pub fn set_on_ground(&mut self, on_ground: bool) {
+        self.on_ground = on_ground;
+    }
+
+    pub fn data(&self) -> i32 {
+        self.data
+    }
+
+    pub fn set_data(&mut self, data: i32) {
+        self.data = data;
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+}
+
+impl AsRef<Entity> for Entity {
+    fn as_ref(&self) -> &Entity {
+        self
+    }
+}
+
+impl AsMut<Entity> for Entity {
+    fn as_mut(&mut self) -> &mut Entity {
+        self
+    }
+}
+
+impl EntityKind for Entity {}
+
+/// Implement `AsRef<Entity>`, `AsMut<Entity>` for a kind holding its entity in `$field`
+macro_rules! entity_kind {
+    ($ty: ty, $($field: ident).+) => {
+        impl AsRef<$crate::entity::Entity> for $ty {
+            fn as_ref(&self) -> &$crate::entity::Entity {
+                &self.$($field).+
+            }
+        }
+
+        impl AsMut<$crate::entity::Entity> for $ty {
+            fn as_mut(&mut self) -> &mut $crate::entity::Entity {
+                &mut self.$($field).+
+            }
+        }
+    };
 }
+pub(crate) use entity_kind;
@@ -0,0 +1,28 @@
+use zr_data_type::vector::Vector3;
+use zr_registry::entity_type::EntityType;
+
+use super::{entity_kind, traits::Throwable, Entity, EntityKind};
+
+/// Snowball, egg, ender pearl and other thrown items
+#[derive(Debug)]
+pub struct ThrownItem {
+    entity: Entity,
+}
+
+entity_kind!(ThrownItem, entity);
+
+impl ThrownItem {
+    pub fn new(entity_type: EntityType, position: Vector3<f64>) -> Self {
+        Self {
+            entity: Entity::new(entity_type, position),
+        }
+    }
+}
+
+impl EntityKind for ThrownItem {
+    fn tick(&mut self) {
+        self.tick_projectile();
+    }
+}
+
+impl Throwable for ThrownItem {}
@@ -0,0 +1,95 @@
+use std::{any::Any, collections::BTreeMap};
+
+use uuid::Uuid;
+use zr_data_type::vector::Vector3;
+
+use super::{Entity, EntityKind, EntityType};
+
+/// Entities of a world, indexed by entity id
+#[derive(Debug, Default)]
+pub struct EntityStore {
+    entities: BTreeMap<i32, Box<dyn EntityKind>>,
+}
+
+impl EntityStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn an entity with a random UUID, returns its id
+    pub fn spawn(&mut self, entity_type: EntityType, position: Vector3<f64>) -> i32 {
+        self.insert(Entity::new(entity_type, position))
+    }
+
+    /// Spawn an entity with a known UUID (e.g. a player), returns its id
+    pub fn spawn_with_uuid(
+        &mut self,
+        entity_type: EntityType,
+        uuid: Uuid,
+        position: Vector3<f64>,
+    ) -> i32 {
+        self.insert(Entity::with_uuid(entity_type, uuid, position))
+    }
+
+    /// Add an entity of any kind, returns its id
+    pub fn insert<K: EntityKind>(&mut self, kind: K) -> i32 {
+        let id = kind.as_ref().id;
+        self.entities.insert(id, Box::new(kind));
+        id
+    }
+
+    /// Remove the entity, viewers are notified on the next tracker tick
+    pub fn despawn(&mut self, id: i32) -> Option<Box<dyn EntityKind>> {
+        self.entities.remove(&id)
+    }
+
+    pub fn get(&self, id: i32) -> Option<&Entity> {
+        self.entities.get(&id).map(|kind| (**kind).as_ref())
+    }
+
+    pub fn get_mut(&mut self, id: i32) -> Option<&mut Entity> {
+        self.entities.get_mut(&id).map(|kind| (**kind).as_mut())
+    }
+
+    /// The entity as its kind, `None` if it is another kind
+    pub fn get_as<K: EntityKind>(&self, id: i32) -> Option<&K> {
+        let kind: &dyn Any = &**self.entities.get(&id)?;
+        kind.downcast_ref()
+    }
+
+    pub fn get_as_mut<K: EntityKind>(&mut self, id: i32) -> Option<&mut K> {
+        let kind: &mut dyn Any = &mut **self.entities.get_mut(&id)?;
+        kind.downcast_mut()
+    }
+
+    pub fn by_uuid(&self, uuid: Uuid) -> Option<&Entity> {
+        self.iter().find(|entity| entity.uuid == uuid)
+    }
+
+    pub fn contains(&self, id: i32) -> bool {
+        self.entities.contains_key(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+        self.entities.values().map(|kind| (**kind).as_ref())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Entity> {
+        self.entities.values_mut().map(|kind| (**kind).as_mut())
+    }
+
+    /// Tick the kind specific state of every entity
+    pub fn tick(&mut self) {
+        for kind in self.entities.values_mut() {
+            kind.tick();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+}
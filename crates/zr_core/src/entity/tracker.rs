@@ -0,0 +1,394 @@
+use std::collections::{BTreeSet, HashMap};
+
+use uuid::Uuid;
+use zr_data_type::vector::Vector3;
+
+use super::{store::EntityStore, Entity, EntityType};
+use crate::metadata::Metadata;
+
+/// Rotation in 1/256 of a turn, as sent on the wire
+pub fn angle(degrees: f32) -> u8 {
+    (degrees * 256.0 / 360.0).floor() as i32 as u8
+}
+
+/// Fixed point position used for the relative moves (1/4096 of a block)
+fn encode_position(position: Vector3<f64>) -> Vector3<i64> {
+    position.map(|c| (c * 4096.0).round() as i64)
+}
+
+/// Velocity in 1/8000 of a block per tick
+fn encode_velocity(velocity: Vector3<f64>) -> Vector3<i16> {
+    velocity.map(|c| (c.clamp(-3.9, 3.9) * 8000.0) as i16)
+}
+
+/// What a viewer has to be told about the entities around it,
+/// each one is mapped to a play packet by the protocol layer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityUpdate {
+    Spawn {
+        id: i32,
+        uuid: Uuid,
+        entity_type: EntityType,
+        position: Vector3<f64>,
+        yaw: u8,
+        pitch: u8,
+        head_yaw: u8,
+        data: i32,
+        velocity: Vector3<i16>,
+        metadata: Metadata,
+    },
+    Remove(Vec<i32>),
+    Move {
+        id: i32,
+        delta: Vector3<i16>,
+        on_ground: bool,
+    },
+    MoveRotate {
+        id: i32,
+        delta: Vector3<i16>,
+        yaw: u8,
+        pitch: u8,
+        on_ground: bool,
+    },
+    Rotate {
+        id: i32,
+        yaw: u8,
+        pitch: u8,
+        on_ground: bool,
+    },
+    Teleport {
+        id: i32,
+        position: Vector3<f64>,
+        yaw: u8,
+        pitch: u8,
+        on_ground: bool,
+    },
+    HeadRotation {
+        id: i32,
+        head_yaw: u8,
+    },
+    Metadata {
+        id: i32,
+        metadata: Metadata,
+    },
+}
+
+impl EntityUpdate {
+    fn spawn(entity: &Entity) -> Self {
+        Self::Spawn {
+            id: entity.id,
+            uuid: entity.uuid,
+            entity_type: entity.entity_type,
+            position: entity.position,
+            yaw: angle(entity.yaw),
+            pitch: angle(entity.pitch),
+            head_yaw: angle(entity.head_yaw),
+            data: entity.data,
+            velocity: encode_velocity(entity.velocity),
+            metadata: entity.metadata.clone(),
+        }
+    }
+}
+
+/// Last state sent to the viewers, deltas are computed against it
+#[derive(Debug, Clone, Copy)]
+struct SentState {
+    position: Vector3<i64>,
+    yaw: u8,
+    pitch: u8,
+    head_yaw: u8,
+}
+
+impl SentState {
+    fn of(entity: &Entity) -> Self {
+        Self {
+            position: encode_position(entity.position),
+            yaw: angle(entity.yaw),
+            pitch: angle(entity.pitch),
+            head_yaw: angle(entity.head_yaw),
+        }
+    }
+}
+
+/// Keep track of the entities each viewer (a player entity) can see
+/// and of what has to be sent to it every tick.
+#[derive(Debug)]
+pub struct EntityTracker {
+    /// in chunks
+    view_distance: u8,
+    viewers: HashMap<i32, BTreeSet<i32>>,
+    sent: HashMap<i32, SentState>,
+}
+
+impl EntityTracker {
+    pub fn new(view_distance: u8) -> Self {
+        Self {
+            view_distance,
+            viewers: HashMap::new(),
+            sent: HashMap::new(),
+        }
+    }
+
+    pub fn set_view_distance(&mut self, view_distance: u8) {
+        self.view_distance = view_distance;
+    }
+
+    /// Start sending entities to `viewer`, the entities in range are spawned on the next tick
+    pub fn add_viewer(&mut self, viewer: i32) {
+        self.viewers.entry(viewer).or_default();
+    }
+
+    pub fn remove_viewer(&mut self, viewer: i32) {
+        self.viewers.remove(&viewer);
+    }
+
+    pub fn is_visible(&self, viewer: i32, entity: i32) -> bool {
+        self.viewers
+            .get(&viewer)
+            .is_some_and(|visible| visible.contains(&entity))
+    }
+
+    /// Movement and metadata updates of the entity since the last tick
+    fn entity_updates(&mut self, entity: &mut Entity) -> Vec<EntityUpdate> {
+        let mut updates = Vec::new();
+        let current = SentState::of(entity);
+        let id = entity.id;
+        let on_ground = entity.on_ground;
+        if let Some(sent) = self.sent.insert(id, current) {
+            let delta = current.position - sent.position;
+            let relative = [delta.x, delta.y, delta.z]
+                .into_iter()
+                .all(|c| i16::try_from(c).is_ok());
+            let moved = delta != Vector3::default();
+            let rotated = current.yaw != sent.yaw || current.pitch != sent.pitch;
+            let (yaw, pitch) = (current.yaw, current.pitch);
+            if entity.teleported || !relative {
+                updates.push(EntityUpdate::Teleport {
+                    id,
+                    position: entity.position,
+                    yaw,
+                    pitch,
+                    on_ground,
+                });
+            } else {
+                let delta = delta.map(|c| c as i16);
+                match (moved, rotated) {
+                    (true, true) => updates.push(EntityUpdate::MoveRotate {
+                        id,
+                        delta,
+                        yaw,
+                        pitch,
+                        on_ground,
+                    }),
+                    (true, false) => updates.push(EntityUpdate::Move {
+                        id,
+                        delta,
+                        on_ground,
+                    }),
+                    (false, true) => updates.push(EntityUpdate::Rotate {
+                        id,
+                        yaw,
+                        pitch,
+                        on_ground,
+                    }),
+                    (false, false) => {}
+                }
+            }
+            if current.head_yaw != sent.head_yaw {
+                updates.push(EntityUpdate::HeadRotation {
+                    id,
+                    head_yaw: current.head_yaw,
+                });
+            }
+        }
+        entity.teleported = false;
+
+        // a newly tracked entity is spawned with its whole metadata
+        let metadata = entity.metadata.take_changes();
+        if !metadata.is_empty() {
+            updates.push(EntityUpdate::Metadata { id, metadata });
+        }
+        updates
+    }
+
+    /// Updates to send to each viewer for this tick, in order
+    pub fn tick(&mut self, store: &mut EntityStore) -> Vec<(i32, EntityUpdate)> {
+        let mut entity_updates = HashMap::new();
+        for entity in store.iter_mut() {
+            entity_updates.insert(entity.id, self.entity_updates(entity));
+        }
+        self.sent.retain(|id, _| store.contains(*id));
+        self.viewers.retain(|viewer, _| store.contains(*viewer));
+
+        let view_distance = self.view_distance;
+        let in_range = |origin: Vector3<f64>, entity: &Entity| {
+            let range = entity.entity_type.tracking_range().min(view_distance) as f64 * 16.0;
+            origin.horizontal_distance_squared(&entity.position) <= range * range
+        };
+
+        let mut updates = Vec::new();
+        for (viewer, visible) in self.viewers.iter_mut() {
+            let origin = match store.get(*viewer) {
+                Some(entity) => entity.position,
+                None => continue,
+            };
+
+            let removed: Vec<i32> = visible
+                .iter()
+                .copied()
+                .filter(|id| {
+                    !store
+                        .get(*id)
+                        .is_some_and(|entity| in_range(origin, entity))
+                })
+                .collect();
+            if !removed.is_empty() {
+                visible.retain(|id| !removed.contains(id));
+                updates.push((*viewer, EntityUpdate::Remove(removed)));
+            }
+
+            for entity in store.iter() {
+                let entity_updates = &entity_updates[&entity.id];
+                if entity.id == *viewer {
+                    // a player sees its own metadata
+                    updates.extend(
+                        entity_updates
+                            .iter()
+                            .filter(|update| matches!(update, EntityUpdate::Metadata { .. }))
+                            .map(|update| (*viewer, update.clone())),
+                    );
+                } else if visible.contains(&entity.id) {
+                    updates.extend(
+                        entity_updates
+                            .iter()
+                            .map(|update| (*viewer, update.clone())),
+                    );
+                } else if in_range(origin, entity) {
+                    visible.insert(entity.id);
+                    updates.push((*viewer, EntityUpdate::spawn(entity)));
+                }
+            }
+        }
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_data_type::vector::Vector3;
+
+    use super::{EntityTracker, EntityUpdate};
+    use crate::{
+        entity::{store::EntityStore, EntityType},
+        metadata::schema::entity,
+    };
+
+    fn setup() -> (EntityStore, EntityTracker, i32) {
+        let mut store = EntityStore::new();
+        let mut tracker = EntityTracker::new(10);
+        let viewer = store.spawn(EntityType::Player, Vector3::new(0.0, 64.0, 0.0));
+        tracker.add_viewer(viewer);
+        (store, tracker, viewer)
+    }
+
+    #[test]
+    fn test_spawn_by_distance() {
+        let (mut store, mut tracker, viewer) = setup();
+        let near = store.spawn(EntityType::Pig, Vector3::new(10.0, 64.0, 10.0));
+        // pigs are tracked up to 8 chunks
+        let far = store.spawn(EntityType::Pig, Vector3::new(200.0, 64.0, 0.0));
+        let updates = tracker.tick(&mut store);
+        assert_eq!(updates.len(), 1);
+        assert!(
+            matches!(updates[0], (v, EntityUpdate::Spawn { id, .. }) if v == viewer && id == near)
+        );
+        assert!(!tracker.is_visible(viewer, far));
+
+        store
+            .get_mut(far)
+            .unwrap()
+            .set_position(Vector3::new(100.0, 64.0, 0.0));
+        let updates = tracker.tick(&mut store);
+        assert!(matches!(updates[..], [(_, EntityUpdate::Spawn { id, .. })] if id == far));
+        assert!(tracker.tick(&mut store).is_empty());
+    }
+
+    #[test]
+    fn test_delta_encoding() {
+        let (mut store, mut tracker, _) = setup();
+        let pig = store.spawn(EntityType::Pig, Vector3::new(0.5, 64.0, 0.5));
+        tracker.tick(&mut store);
+
+        store
+            .get_mut(pig)
+            .unwrap()
+            .set_position(Vector3::new(1.0, 64.0, 0.25));
+        let updates = tracker.tick(&mut store);
+        assert!(matches!(
+            updates[..],
+            [(
+                _,
+                EntityUpdate::Move {
+                    delta: Vector3 {
+                        x: 2048,
+                        y: 0,
+                        z: -1024
+                    },
+                    ..
+                }
+            )]
+        ));
+
+        let entity = store.get_mut(pig).unwrap();
+        entity.set_position(Vector3::new(1.0, 65.0, 0.25));
+        entity.set_rotation(90.0, 0.0);
+        entity.set_head_yaw(90.0);
+        let updates = tracker.tick(&mut store);
+        assert!(matches!(
+            updates[..],
+            [
+                (
+                    _,
+                    EntityUpdate::MoveRotate {
+                        delta: Vector3 {
+                            x: 0,
+                            y: 4096,
+                            z: 0
+                        },
+                        yaw: 64,
+                        ..
+                    }
+                ),
+                (_, EntityUpdate::HeadRotation { head_yaw: 64, .. }),
+            ]
+        ));
+
+        // more than 8 blocks does not fit in a delta
+        store
+            .get_mut(pig)
+            .unwrap()
+            .set_position(Vector3::new(20.0, 65.0, 0.25));
+        let updates = tracker.tick(&mut store);
+        assert!(matches!(updates[..], [(_, EntityUpdate::Teleport { .. })]));
+    }
+
+    #[test]
+    fn test_metadata_and_removal() {
+        let (mut store, mut tracker, viewer) = setup();
+        let pig = store.spawn(EntityType::Pig, Vector3::new(0.0, 64.0, 0.0));
+        tracker.tick(&mut store);
+
+        store
+            .get_mut(pig)
+            .unwrap()
+            .metadata_mut()
+            .set(entity::SILENT, true);
+        let updates = tracker.tick(&mut store);
+        assert!(matches!(updates[..], [(_, EntityUpdate::Metadata { id, .. })] if id == pig));
+
+        store.despawn(pig);
+        let updates = tracker.tick(&mut store);
+        assert_eq!(updates, vec![(viewer, EntityUpdate::Remove(vec![pig]))]);
+        assert!(!tracker.is_visible(viewer, pig));
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{entity::Entity, metadata::schema::ageable_mob};
+
+/// Age of a new baby, it grows up when its age reaches 0
+pub const BABY_AGE: i32 = -24000;
+
+/// Negative while a baby, positive while the entity cannot breed again
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AgeState {
+    age: i32,
+}
+
+pub trait Ageable: AsRef<Entity> + AsMut<Entity> {
+    fn age_state(&self) -> &AgeState;
+    fn age_state_mut(&mut self) -> &mut AgeState;
+
+    fn age(&self) -> i32 {
+        self.age_state().age
+    }
+
+    fn set_age(&mut self, age: i32) {
+        self.age_state_mut().age = age;
+        self.as_mut().metadata_mut().set(ageable_mob::BABY, age < 0);
+    }
+
+    fn is_baby(&self) -> bool {
+        self.age() < 0
+    }
+
+    fn set_baby(&mut self, baby: bool) {
+        self.set_age(if baby { BABY_AGE } else { 0 });
+    }
+
+    /// Make a baby grow up `seconds` faster
+    fn age_up(&mut self, seconds: i32) {
+        let age = self.age();
+        if age < 0 {
+            self.set_age((age + seconds * 20).min(0));
+        }
+    }
+
+    fn tick_age(&mut self) {
+        match self.age() {
+            age if age < 0 => self.set_age(age + 1),
+            age if age > 0 => self.set_age(age - 1),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_data_type::vector::Vector3;
+
+    use super::{Ageable, BABY_AGE};
+    use crate::{
+        entity::{animal::Cow, EntityKind},
+        metadata::MetadataValue,
+    };
+
+    #[test]
+    fn test_baby_grows_up() {
+        let mut cow = Cow::new(Vector3::default());
+        assert!(!cow.is_baby());
+        cow.set_baby(true);
+        assert_eq!(cow.age(), BABY_AGE);
+        assert_eq!(
+            cow.as_ref().metadata().get(16),
+            Some(&MetadataValue::Boolean(true))
+        );
+
+        cow.age_up(1195);
+        assert_eq!(cow.age(), -100);
+        for _ in 0..100 {
+            cow.tick();
+        }
+        assert!(!cow.is_baby());
+        assert_eq!(
+            cow.as_ref().metadata().get(16),
+            Some(&MetadataValue::Boolean(false))
+        );
+
+        // growing up never gives a breeding cooldown
+        cow.set_age(-10);
+        cow.age_up(60);
+        assert_eq!(cow.age(), 0);
+    }
+}
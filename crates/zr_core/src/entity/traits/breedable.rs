@@ -0,0 +1,154 @@
+use uuid::Uuid;
+use zr_registry::item::Item;
+
+use super::Ageable;
+
+/// Ticks spent in love mode after being fed
+pub const LOVE_TICKS: i32 = 600;
+/// Age given to the parents after breeding
+pub const BREEDING_COOLDOWN: i32 = 6000;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoveState {
+    ticks: i32,
+    /// player who fed the entity
+    cause: Option<Uuid>,
+}
+
+pub trait Breedable: Ageable {
+    fn love_state(&self) -> &LoveState;
+    fn love_state_mut(&mut self) -> &mut LoveState;
+
+    /// Content of the `minecraft:<kind>_food` item tag
+    fn food(&self) -> &'static [&'static str];
+
+    fn is_food(&self, item: Item) -> bool {
+        self.food().contains(&item.name())
+    }
+
+    fn is_in_love(&self) -> bool {
+        self.love_state().ticks > 0
+    }
+
+    fn love_cause(&self) -> Option<Uuid> {
+        self.love_state().cause
+    }
+
+    fn can_fall_in_love(&self) -> bool {
+        self.age() == 0 && !self.is_in_love()
+    }
+
+    fn set_in_love(&mut self, cause: Option<Uuid>) {
+        let state = self.love_state_mut();
+        state.ticks = LOVE_TICKS;
+        state.cause = cause;
+    }
+
+    /// Feed `item` to the entity, returns whether the item is consumed.
+    /// A baby grows up faster, an adult falls in love.
+    fn feed(&mut self, item: Item, player: Option<Uuid>) -> bool {
+        if !self.is_food(item) {
+            false
+        } else if self.is_baby() {
+            self.age_up(-self.age() / 20 / 10);
+            true
+        } else if self.can_fall_in_love() {
+            self.set_in_love(player);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn can_mate(&self, other: &Self) -> bool
+    where
+        Self: Sized,
+    {
+        self.as_ref().id() != other.as_ref().id()
+            && self.is_in_love()
+            && other.is_in_love()
+            && !self.is_baby()
+            && !other.is_baby()
+    }
+
+    /// Reset both parents, returns whether they mated.
+    /// The caller spawns the baby.
+    fn breed(&mut self, other: &mut Self) -> bool
+    where
+        Self: Sized,
+    {
+        if !self.can_mate(other) {
+            return false;
+        }
+        for parent in [self, other] {
+            parent.set_age(BREEDING_COOLDOWN);
+            *parent.love_state_mut() = LoveState::default();
+        }
+        true
+    }
+
+    fn tick_love(&mut self) {
+        let state = self.love_state_mut();
+        if state.ticks > 0 {
+            state.ticks -= 1;
+            if state.ticks == 0 {
+                state.cause = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+    use zr_data_type::vector::Vector3;
+    use zr_registry::item::Item;
+
+    use super::{Breedable, BREEDING_COOLDOWN, LOVE_TICKS};
+    use crate::entity::{animal::Cow, traits::Ageable, EntityKind};
+
+    #[test]
+    fn test_food() {
+        let mut cow = Cow::new(Vector3::default());
+        assert_eq!(cow.food(), &["minecraft:wheat"]);
+        assert!(!cow.is_food(Item::Dirt));
+        assert!(!cow.feed(Item::Dirt, None));
+        assert!(!cow.is_in_love());
+    }
+
+    #[test]
+    fn test_breeding() {
+        let player = Uuid::new_v4();
+        let mut cow = Cow::new(Vector3::default());
+        let mut other = Cow::new(Vector3::default());
+        assert!(!cow.breed(&mut other));
+
+        cow.set_in_love(Some(player));
+        other.set_in_love(None);
+        assert_eq!(cow.love_cause(), Some(player));
+        assert!(!cow.can_fall_in_love());
+        assert!(cow.breed(&mut other));
+        assert_eq!(cow.age(), BREEDING_COOLDOWN);
+        assert!(!other.is_in_love());
+        assert!(!other.can_fall_in_love());
+
+        // love mode ends by itself
+        cow.set_age(0);
+        cow.set_in_love(Some(player));
+        for _ in 0..LOVE_TICKS {
+            cow.tick();
+        }
+        assert!(!cow.is_in_love());
+        assert_eq!(cow.love_cause(), None);
+    }
+
+    #[test]
+    fn test_babies_cannot_mate() {
+        let mut cow = Cow::new(Vector3::default());
+        let mut baby = Cow::new(Vector3::default());
+        baby.set_baby(true);
+        cow.set_in_love(None);
+        baby.set_in_love(None);
+        assert!(!cow.breed(&mut baby));
+    }
+}
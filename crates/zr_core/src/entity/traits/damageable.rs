@@ -0,0 +1,159 @@
+use zr_registry::damage_type::DamageType;
+
+use crate::{entity::Entity, metadata::schema::living_entity};
+
+/// Ticks after being hurt during which only stronger damage applies
+pub const INVULNERABLE_TICKS: u8 = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Health {
+    health: f32,
+    max_health: f32,
+    invulnerable_ticks: u8,
+    last_damage: f32,
+    last_damage_type: Option<DamageType>,
+}
+
+impl Health {
+    pub fn new(max_health: f32) -> Self {
+        Self {
+            health: max_health,
+            max_health,
+            invulnerable_ticks: 0,
+            last_damage: 0.0,
+            last_damage_type: None,
+        }
+    }
+}
+
+pub trait Damageable: AsRef<Entity> + AsMut<Entity> {
+    fn health_state(&self) -> &Health;
+    fn health_state_mut(&mut self) -> &mut Health;
+
+    fn is_immune_to(&self, _damage_type: DamageType) -> bool {
+        false
+    }
+
+    fn health(&self) -> f32 {
+        self.health_state().health
+    }
+
+    fn max_health(&self) -> f32 {
+        self.health_state().max_health
+    }
+
+    fn set_health(&mut self, health: f32) {
+        let state = self.health_state_mut();
+        state.health = health.clamp(0.0, state.max_health);
+        let health = state.health;
+        self.as_mut()
+            .metadata_mut()
+            .set(living_entity::HEALTH, health);
+    }
+
+    fn set_max_health(&mut self, max_health: f32) {
+        self.health_state_mut().max_health = max_health;
+        self.set_health(self.health());
+    }
+
+    fn is_dead(&self) -> bool {
+        self.health() <= 0.0
+    }
+
+    fn last_damage_type(&self) -> Option<DamageType> {
+        self.health_state().last_damage_type
+    }
+
+    fn heal(&mut self, amount: f32) {
+        if !self.is_dead() {
+            self.set_health(self.health() + amount);
+        }
+    }
+
+    /// Hurt the entity, returns whether any damage was dealt.
+    /// While invulnerable, only the part above the last damage is dealt.
+    fn damage(&mut self, amount: f32, damage_type: DamageType) -> bool {
+        if self.is_dead() || amount <= 0.0 || self.is_immune_to(damage_type) {
+            return false;
+        }
+        let state = self.health_state_mut();
+        let dealt = if !damage_type.bypasses_invulnerability()
+            && state.invulnerable_ticks > INVULNERABLE_TICKS / 2
+        {
+            if amount <= state.last_damage {
+                return false;
+            }
+            amount - state.last_damage
+        } else {
+            state.invulnerable_ticks = INVULNERABLE_TICKS;
+            amount
+        };
+        state.last_damage = amount;
+        state.last_damage_type = Some(damage_type);
+        self.set_health(self.health() - dealt);
+        true
+    }
+
+    fn tick_damage(&mut self) {
+        let state = self.health_state_mut();
+        state.invulnerable_ticks = state.invulnerable_ticks.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_data_type::vector::Vector3;
+    use zr_registry::damage_type::DamageType;
+
+    use super::Damageable;
+    use crate::{
+        entity::{
+            animal::{Cow, Strider},
+            EntityKind,
+        },
+        metadata::MetadataValue,
+    };
+
+    #[test]
+    fn test_damage_and_heal() {
+        let mut cow = Cow::new(Vector3::default());
+        assert_eq!(cow.health(), 10.0);
+        assert!(cow.damage(4.0, DamageType::PlayerAttack));
+        assert_eq!(cow.health(), 6.0);
+        assert_eq!(cow.last_damage_type(), Some(DamageType::PlayerAttack));
+        assert_eq!(
+            cow.as_ref().metadata().get(9),
+            Some(&MetadataValue::Float(6.0))
+        );
+
+        cow.heal(100.0);
+        assert_eq!(cow.health(), 10.0);
+    }
+
+    #[test]
+    fn test_invulnerability() {
+        let mut cow = Cow::new(Vector3::default());
+        assert!(cow.damage(3.0, DamageType::MobAttack));
+        assert!(!cow.damage(2.0, DamageType::MobAttack));
+        // only the part above the last damage is dealt
+        assert!(cow.damage(5.0, DamageType::MobAttack));
+        assert_eq!(cow.health(), 5.0);
+
+        for _ in 0..10 {
+            cow.tick();
+        }
+        assert!(cow.damage(1.0, DamageType::MobAttack));
+        assert_eq!(cow.health(), 4.0);
+
+        assert!(cow.damage(10.0, DamageType::OutOfWorld));
+        assert!(cow.is_dead());
+        assert!(!cow.damage(1.0, DamageType::OutOfWorld));
+    }
+
+    #[test]
+    fn test_immunity() {
+        let mut strider = Strider::new(Vector3::default());
+        assert!(!strider.damage(4.0, DamageType::Lava));
+        assert!(strider.damage(4.0, DamageType::Cactus));
+    }
+}
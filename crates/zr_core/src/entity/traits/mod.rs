@@ -0,0 +1,20 @@
+//! Behaviours shared by several entity kinds. The state of a behaviour is kept in a
+//! small struct owned by the kind, the provided methods keep the entity metadata in sync.
+
+pub mod ageable;
+pub mod breedable;
+pub mod damageable;
+pub mod shearable;
+pub mod sittable;
+pub mod steerable;
+pub mod tameable;
+pub mod throwable;
+
+pub use ageable::Ageable;
+pub use breedable::Breedable;
+pub use damageable::Damageable;
+pub use shearable::Shearable;
+pub use sittable::Sittable;
+pub use steerable::Steerable;
+pub use tameable::Tameable;
+pub use throwable::Throwable;
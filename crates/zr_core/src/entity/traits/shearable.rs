@@ -0,0 +1,71 @@
+use crate::{entity::Entity, item::ItemStack};
+
+pub trait Shearable: AsRef<Entity> + AsMut<Entity> {
+    fn is_sheared(&self) -> bool;
+    fn set_sheared(&mut self, sheared: bool);
+
+    /// Items dropped when the entity is sheared
+    fn shear_drops(&mut self) -> Vec<ItemStack>;
+
+    fn can_shear(&self) -> bool {
+        !self.is_sheared()
+    }
+
+    /// Shear the entity, `None` if it is not ready for shearing
+    fn shear(&mut self) -> Option<Vec<ItemStack>> {
+        if !self.can_shear() {
+            return None;
+        }
+        let drops = self.shear_drops();
+        self.set_sheared(true);
+        Some(drops)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_data_type::vector::Vector3;
+    use zr_registry::item::Item;
+
+    use super::Shearable;
+    use crate::{
+        entity::{
+            animal::{DyeColor, Sheep},
+            traits::Ageable,
+        },
+        metadata::MetadataValue,
+    };
+
+    #[test]
+    #[ignore = "the checked-in item report stops before the wool, run generate_reports.sh"]
+    fn test_shear() {
+        let mut sheep = Sheep::new(Vector3::default());
+        sheep.set_color(DyeColor::Orange);
+        assert_eq!(sheep.wool(), "minecraft:orange_wool");
+        let drops = sheep.shear().unwrap();
+        let [stack] = drops.as_slice() else {
+            panic!("expected one stack of wool, got {drops:?}");
+        };
+        // parsed as the variant only exists with the full report
+        assert_eq!(Some(stack.item()), sheep.wool().parse::<Item>().ok());
+        assert!((1..=3).contains(&stack.count()));
+        assert!(sheep.is_sheared());
+        assert!(sheep.shear().is_none());
+        // color in the low bits, sheared bit
+        assert_eq!(
+            sheep.as_ref().metadata().get(17),
+            Some(&MetadataValue::Byte(0x11))
+        );
+
+        sheep.eat_grass();
+        assert!(sheep.can_shear());
+    }
+
+    #[test]
+    fn test_baby_cannot_be_sheared() {
+        let mut sheep = Sheep::new(Vector3::default());
+        sheep.set_baby(true);
+        assert!(sheep.shear().is_none());
+        assert!(!sheep.is_sheared());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{entity::Entity, metadata::schema::tamable_animal};
+
+/// Sitting on order of its owner, the state is the sitting bit of the tamable animal flags
+pub trait Sittable: AsRef<Entity> + AsMut<Entity> {
+    fn is_sitting(&self) -> bool {
+        self.as_ref().metadata().bits(tamable_animal::FLAGS) & tamable_animal::SITTING != 0
+    }
+
+    fn set_sitting(&mut self, sitting: bool) {
+        self.as_mut().metadata_mut().set_bit(
+            tamable_animal::FLAGS,
+            tamable_animal::SITTING,
+            sitting,
+        );
+    }
+
+    /// Sit or stand up, returns the new state
+    fn toggle_sitting(&mut self) -> bool {
+        let sitting = !self.is_sitting();
+        self.set_sitting(sitting);
+        sitting
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_data_type::vector::Vector3;
+
+    use super::Sittable;
+    use crate::{entity::animal::Wolf, metadata::MetadataValue};
+
+    #[test]
+    fn test_sitting() {
+        let mut wolf = Wolf::new(Vector3::default());
+        assert!(!wolf.is_sitting());
+        assert!(wolf.toggle_sitting());
+        assert!(wolf.is_sitting());
+        assert_eq!(
+            wolf.as_ref().metadata().get(17),
+            Some(&MetadataValue::Byte(0x01))
+        );
+        assert!(!wolf.toggle_sitting());
+        assert_eq!(
+            wolf.as_ref().metadata().get(17),
+            Some(&MetadataValue::Byte(0x00))
+        );
+    }
+}
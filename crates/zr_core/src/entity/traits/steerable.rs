@@ -0,0 +1,144 @@
+use std::f32::consts::PI;
+
+use rand::Rng;
+use zr_registry::item::Item;
+
+use crate::{entity::Entity, metadata::MetadataKey};
+
+/// Boost given by the steering item (`ItemBasedSteering`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SteeringState {
+    saddled: bool,
+    boosting: bool,
+    boost_time: i32,
+    boost_time_total: i32,
+}
+
+/// Ridden with a saddle and steered with an item on a stick
+pub trait Steerable: AsRef<Entity> + AsMut<Entity> {
+    fn steering_state(&self) -> &SteeringState;
+    fn steering_state_mut(&mut self) -> &mut SteeringState;
+
+    /// Item held by the rider to steer the entity
+    fn steering_item(&self) -> &'static str;
+    fn saddle_key(&self) -> MetadataKey<bool>;
+    fn boost_time_key(&self) -> MetadataKey<i32>;
+
+    fn has_saddle(&self) -> bool {
+        self.steering_state().saddled
+    }
+
+    fn set_saddle(&mut self, saddled: bool) {
+        self.steering_state_mut().saddled = saddled;
+        let key = self.saddle_key();
+        self.as_mut().metadata_mut().set(key, saddled);
+    }
+
+    /// Whether a rider holding `item` can steer the entity
+    fn can_steer_with(&self, item: Item) -> bool {
+        self.has_saddle() && item.name() == self.steering_item()
+    }
+
+    fn is_boosting(&self) -> bool {
+        self.steering_state().boosting
+    }
+
+    /// Start a boost, returns false if the entity is already boosting
+    fn boost(&mut self, rng: &mut impl Rng) -> bool
+    where
+        Self: Sized,
+    {
+        if self.is_boosting() {
+            return false;
+        }
+        let total = rng.gen_range(140..981);
+        let state = self.steering_state_mut();
+        state.boosting = true;
+        state.boost_time = 0;
+        state.boost_time_total = total;
+        let key = self.boost_time_key();
+        self.as_mut().metadata_mut().set(key, total);
+        true
+    }
+
+    /// Multiplier of the riding speed, peaks at the middle of a boost
+    fn speed_multiplier(&self) -> f32 {
+        let state = self.steering_state();
+        if state.boosting {
+            let progress = state.boost_time as f32 / state.boost_time_total as f32;
+            1.0 + 1.15 * (progress * PI).sin()
+        } else {
+            1.0
+        }
+    }
+
+    fn tick_boost(&mut self) {
+        let state = self.steering_state_mut();
+        if state.boosting {
+            state.boost_time += 1;
+            if state.boost_time > state.boost_time_total {
+                state.boosting = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use zr_data_type::vector::Vector3;
+    use zr_registry::item::Item;
+
+    use super::Steerable;
+    use crate::{
+        entity::{
+            animal::{Pig, Strider},
+            EntityKind,
+        },
+        metadata::MetadataValue,
+    };
+
+    #[test]
+    fn test_saddle() {
+        let mut pig = Pig::new(Vector3::default());
+        assert!(!pig.has_saddle());
+        pig.set_saddle(true);
+        assert_eq!(
+            pig.as_ref().metadata().get(17),
+            Some(&MetadataValue::Boolean(true))
+        );
+        assert_eq!(pig.steering_item(), "minecraft:carrot_on_a_stick");
+        assert!(!pig.can_steer_with(Item::Stone));
+
+        let mut strider = Strider::new(Vector3::default());
+        strider.set_saddle(true);
+        assert_eq!(
+            strider.as_ref().metadata().get(19),
+            Some(&MetadataValue::Boolean(true))
+        );
+    }
+
+    #[test]
+    fn test_boost() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut pig = Pig::new(Vector3::default());
+        assert_eq!(pig.speed_multiplier(), 1.0);
+        assert!(pig.boost(&mut rng));
+        assert!(!pig.boost(&mut rng));
+        let total = match pig.as_ref().metadata().get(18) {
+            Some(MetadataValue::VarInt(total)) => *total,
+            value => panic!("unexpected boost time {value:?}"),
+        };
+        assert!((140..=980).contains(&total));
+
+        for _ in 0..total / 2 {
+            pig.tick();
+        }
+        assert!(pig.speed_multiplier() > 2.0);
+        for _ in 0..total {
+            pig.tick();
+        }
+        assert!(!pig.is_boosting());
+        assert_eq!(pig.speed_multiplier(), 1.0);
+    }
+}
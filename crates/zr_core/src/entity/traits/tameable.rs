@@ -0,0 +1,115 @@
+use rand::Rng;
+use uuid::Uuid;
+use zr_registry::item::Item;
+
+use crate::{
+    entity::Entity,
+    metadata::{schema::tamable_animal, MetadataValue},
+};
+
+/// Tamed state and owner, kept in the tamable animal metadata
+pub trait Tameable: AsRef<Entity> + AsMut<Entity> {
+    /// Content of the item tag used to tame the entity
+    fn taming_items(&self) -> &'static [&'static str];
+
+    /// Chance for a taming item to tame the entity
+    fn taming_chance(&self) -> f64 {
+        1.0 / 3.0
+    }
+
+    /// Called once the entity is tamed
+    fn on_tamed(&mut self) {}
+
+    fn is_taming_item(&self, item: Item) -> bool {
+        self.taming_items().contains(&item.name())
+    }
+
+    fn owner(&self) -> Option<Uuid> {
+        match self.as_ref().metadata().get(tamable_animal::OWNER.index) {
+            Some(MetadataValue::OptionalUuid(owner)) => *owner,
+            _ => None,
+        }
+    }
+
+    fn is_tamed(&self) -> bool {
+        self.as_ref().metadata().bits(tamable_animal::FLAGS) & tamable_animal::TAMED != 0
+    }
+
+    fn is_owned_by(&self, player: Uuid) -> bool {
+        self.owner() == Some(player)
+    }
+
+    fn tame(&mut self, owner: Uuid) {
+        let metadata = self.as_mut().metadata_mut();
+        metadata.set_bit(tamable_animal::FLAGS, tamable_animal::TAMED, true);
+        metadata.set(tamable_animal::OWNER, Some(owner));
+        self.on_tamed();
+    }
+
+    fn untame(&mut self) {
+        let metadata = self.as_mut().metadata_mut();
+        metadata.set_bit(tamable_animal::FLAGS, tamable_animal::TAMED, false);
+        metadata.set(tamable_animal::OWNER, None);
+    }
+
+    /// Feed a taming item, returns whether the entity is now tamed by `player`
+    fn try_tame(&mut self, player: Uuid, item: Item, rng: &mut impl Rng) -> bool
+    where
+        Self: Sized,
+    {
+        if self.is_tamed() || !self.is_taming_item(item) {
+            return false;
+        }
+        if rng.gen_bool(self.taming_chance()) {
+            self.tame(player);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use uuid::Uuid;
+    use zr_data_type::vector::Vector3;
+    use zr_registry::item::Item;
+
+    use super::Tameable;
+    use crate::entity::{
+        animal::{Cat, Wolf},
+        traits::Damageable,
+    };
+
+    #[test]
+    fn test_tame() {
+        let player = Uuid::new_v4();
+        let mut cat = Cat::new(Vector3::default());
+        assert!(!cat.is_tamed());
+        assert_eq!(cat.owner(), None);
+        cat.tame(player);
+        assert!(cat.is_tamed());
+        assert!(cat.is_owned_by(player));
+        assert!(!cat.is_owned_by(Uuid::new_v4()));
+        cat.untame();
+        assert_eq!(cat.owner(), None);
+    }
+
+    #[test]
+    fn test_taming_items() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut wolf = Wolf::new(Vector3::default());
+        assert_eq!(wolf.taming_items(), &["minecraft:bone"]);
+        assert!(!wolf.try_tame(Uuid::new_v4(), Item::Dirt, &mut rng));
+        assert!(!wolf.is_tamed());
+    }
+
+    #[test]
+    fn test_tamed_wolf_health() {
+        let mut wolf = Wolf::new(Vector3::default());
+        assert_eq!(wolf.max_health(), 8.0);
+        wolf.tame(Uuid::new_v4());
+        assert_eq!(wolf.max_health(), 40.0);
+    }
+}
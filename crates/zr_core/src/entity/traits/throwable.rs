@@ -0,0 +1,109 @@
+use rand::Rng;
+use zr_data_type::vector::Vector3;
+
+use crate::entity::Entity;
+
+/// Gravity of thrown items (snowballs, eggs, ender pearls)
+pub const THROWN_GRAVITY: f64 = 0.03;
+/// Velocity kept after each tick in the air
+pub const AIR_DRAG: f64 = 0.99;
+
+/// A projectile thrown by an entity, the shooter id is the `data` of the spawn packet
+pub trait Throwable: AsRef<Entity> + AsMut<Entity> {
+    fn gravity(&self) -> f64 {
+        THROWN_GRAVITY
+    }
+
+    fn shooter(&self) -> Option<i32> {
+        Some(self.as_ref().data()).filter(|id| *id != 0)
+    }
+
+    /// Throw the projectile from the eyes of `shooter`, in the direction it is looking
+    fn launch(
+        &mut self,
+        shooter: &Entity,
+        eye_height: f64,
+        speed: f64,
+        inaccuracy: f64,
+        rng: &mut impl Rng,
+    ) where
+        Self: Sized,
+    {
+        let (yaw, pitch) = (
+            (shooter.yaw() as f64).to_radians(),
+            (shooter.pitch() as f64).to_radians(),
+        );
+        let direction = Vector3::new(
+            -yaw.sin() * pitch.cos(),
+            -pitch.sin(),
+            yaw.cos() * pitch.cos(),
+        );
+        let spread = 0.0172275 * inaccuracy;
+        let velocity = direction.map(|c| (c + rng.gen_range(-spread..=spread)) * speed);
+        // the projectile keeps the horizontal momentum of its shooter
+        let momentum = shooter.velocity();
+        let momentum = Vector3::new(
+            momentum.x,
+            if shooter.on_ground() { 0.0 } else { momentum.y },
+            momentum.z,
+        );
+
+        let origin = shooter.position() + Vector3::new(0.0, eye_height - 0.1, 0.0);
+        let entity = self.as_mut();
+        entity.set_data(shooter.id());
+        entity.set_position(origin);
+        entity.set_velocity(velocity + momentum);
+        entity.set_rotation(
+            velocity.x.atan2(velocity.z).to_degrees() as f32,
+            velocity.y.atan2(velocity.x.hypot(velocity.z)).to_degrees() as f32,
+        );
+    }
+
+    /// Move the projectile, then apply drag and gravity
+    fn tick_projectile(&mut self) {
+        let gravity = self.gravity();
+        let entity = self.as_mut();
+        entity.set_position(entity.position() + entity.velocity());
+        let velocity = entity.velocity().map(|c| c * AIR_DRAG);
+        entity.set_velocity(velocity - Vector3::new(0.0, gravity, 0.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use zr_data_type::vector::Vector3;
+    use zr_registry::entity_type::EntityType;
+
+    use super::{Throwable, THROWN_GRAVITY};
+    use crate::entity::{projectile::ThrownItem, Entity, EntityKind};
+
+    #[test]
+    fn test_launch() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut shooter = Entity::new(EntityType::Player, Vector3::new(0.0, 64.0, 0.0));
+        shooter.set_on_ground(true);
+        // looking south, straight ahead
+        shooter.set_rotation(0.0, 0.0);
+        let mut snowball = ThrownItem::new(EntityType::Snowball, Vector3::default());
+        assert_eq!(snowball.shooter(), None);
+        snowball.launch(&shooter, 1.62, 1.5, 0.0, &mut rng);
+
+        let entity = snowball.as_ref();
+        assert_eq!(snowball.shooter(), Some(shooter.id()));
+        assert!((entity.position().y - 65.52).abs() < 1e-9);
+        assert!(entity.velocity().x.abs() < 1e-9);
+        assert!((entity.velocity().z - 1.5).abs() < 1e-9);
+        assert!(entity.yaw().abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let mut snowball = ThrownItem::new(EntityType::Snowball, Vector3::new(0.0, 64.0, 0.0));
+        snowball.as_mut().set_velocity(Vector3::new(1.0, 0.0, 0.0));
+        snowball.tick();
+        let entity = snowball.as_ref();
+        assert_eq!(entity.position(), Vector3::new(1.0, 64.0, 0.0));
+        assert_eq!(entity.velocity(), Vector3::new(0.99, -THROWN_GRAVITY, 0.0));
+    }
+}
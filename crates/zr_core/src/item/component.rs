@@ -0,0 +1,398 @@
+use zr_binary::{
+    binary::{read, Binary},
+    error::{BinaryError, Result},
+    varint::VarInt,
+};
+use zr_nbt::{raw::RawNbt, string::NbtString};
+
+// ids of the `minecraft:data_component_type` registry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum DataComponentType {
+    CustomData = 0,
+    MaxStackSize = 1,
+    MaxDamage = 2,
+    Damage = 3,
+    Unbreakable = 4,
+    CustomName = 5,
+    ItemName = 6,
+    Lore = 7,
+    Rarity = 8,
+    Enchantments = 9,
+    CanPlaceOn = 10,
+    CanBreak = 11,
+    AttributeModifiers = 12,
+    CustomModelData = 13,
+    HideAdditionalTooltip = 14,
+    HideTooltip = 15,
+    RepairCost = 16,
+    CreativeSlotLock = 17,
+    EnchantmentGlintOverride = 18,
+    IntangibleProjectile = 19,
+    Food = 20,
+    FireResistant = 21,
+    Tool = 22,
+    StoredEnchantments = 23,
+    DyedColor = 24,
+    MapColor = 25,
+    MapId = 26,
+    MapDecorations = 27,
+    MapPostProcessing = 28,
+    ChargedProjectiles = 29,
+    BundleContents = 30,
+    PotionContents = 31,
+    SuspiciousStewEffects = 32,
+    WritableBookContent = 33,
+    WrittenBookContent = 34,
+    Trim = 35,
+    DebugStickState = 36,
+    EntityData = 37,
+    BucketEntityData = 38,
+    BlockEntityData = 39,
+    Instrument = 40,
+    OminousBottleAmplifier = 41,
+    JukeboxPlayable = 42,
+    Recipes = 43,
+    LodestoneTracker = 44,
+    FireworkExplosion = 45,
+    Fireworks = 46,
+    Profile = 47,
+    NoteBlockSound = 48,
+    BannerPatterns = 49,
+    BaseColor = 50,
+    PotDecorations = 51,
+    Container = 52,
+    BlockState = 53,
+    Bees = 54,
+    Lock = 55,
+    ContainerLoot = 56,
+}
+
+impl DataComponentType {
+    /// Every type, in id order
+    pub const ALL: [Self; 57] = [
+        Self::CustomData,
+        Self::MaxStackSize,
+        Self::MaxDamage,
+        Self::Damage,
+        Self::Unbreakable,
+        Self::CustomName,
+        Self::ItemName,
+        Self::Lore,
+        Self::Rarity,
+        Self::Enchantments,
+        Self::CanPlaceOn,
+        Self::CanBreak,
+        Self::AttributeModifiers,
+        Self::CustomModelData,
+        Self::HideAdditionalTooltip,
+        Self::HideTooltip,
+        Self::RepairCost,
+        Self::CreativeSlotLock,
+        Self::EnchantmentGlintOverride,
+        Self::IntangibleProjectile,
+        Self::Food,
+        Self::FireResistant,
+        Self::Tool,
+        Self::StoredEnchantments,
+        Self::DyedColor,
+        Self::MapColor,
+        Self::MapId,
+        Self::MapDecorations,
+        Self::MapPostProcessing,
+        Self::ChargedProjectiles,
+        Self::BundleContents,
+        Self::PotionContents,
+        Self::SuspiciousStewEffects,
+        Self::WritableBookContent,
+        Self::WrittenBookContent,
+        Self::Trim,
+        Self::DebugStickState,
+        Self::EntityData,
+        Self::BucketEntityData,
+        Self::BlockEntityData,
+        Self::Instrument,
+        Self::OminousBottleAmplifier,
+        Self::JukeboxPlayable,
+        Self::Recipes,
+        Self::LodestoneTracker,
+        Self::FireworkExplosion,
+        Self::Fireworks,
+        Self::Profile,
+        Self::NoteBlockSound,
+        Self::BannerPatterns,
+        Self::BaseColor,
+        Self::PotDecorations,
+        Self::Container,
+        Self::BlockState,
+        Self::Bees,
+        Self::Lock,
+        Self::ContainerLoot,
+    ];
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        usize::try_from(id)
+            .ok()
+            .and_then(|id| Self::ALL.get(id))
+            .copied()
+    }
+}
+
+impl Binary for DataComponentType {
+    fn binary_len(&self) -> usize {
+        VarInt::new(*self as i32).binary_len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        VarInt::new(self as i32).to_binary()
+    }
+
+    fn from_binary(bin: Vec<u8>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_id(VarInt::<i32>::from_binary(bin)?.0).ok_or(BinaryError::FormatError)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+impl Rarity {
+    fn from_id(id: i32) -> Option<Self> {
+        [Self::Common, Self::Uncommon, Self::Rare, Self::Epic]
+            .get(id as usize)
+            .copied()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Enchantment {
+    /// id in the `minecraft:enchantment` registry sent during configuration
+    pub id: i32,
+    pub level: i32,
+}
+
+/// Structured data attached to an [`ItemStack`](super::ItemStack).
+/// Text components are plain strings for now.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataComponent {
+    CustomData(RawNbt),
+    MaxStackSize(u8),
+    MaxDamage(i32),
+    Damage(i32),
+    Unbreakable {
+        show_in_tooltip: bool,
+    },
+    CustomName(String),
+    ItemName(String),
+    Lore(Vec<String>),
+    Rarity(Rarity),
+    Enchantments {
+        enchantments: Vec<Enchantment>,
+        show_in_tooltip: bool,
+    },
+    HideAdditionalTooltip,
+    HideTooltip,
+    RepairCost(i32),
+    EnchantmentGlintOverride(bool),
+    FireResistant,
+    /// A component the server does not model, kept as its payload to be sent back
+    Raw {
+        component_type: DataComponentType,
+        payload: Vec<u8>,
+    },
+}
+
+impl DataComponent {
+    pub fn component_type(&self) -> DataComponentType {
+        match self {
+            Self::CustomData(_) => DataComponentType::CustomData,
+            Self::MaxStackSize(_) => DataComponentType::MaxStackSize,
+            Self::MaxDamage(_) => DataComponentType::MaxDamage,
+            Self::Damage(_) => DataComponentType::Damage,
+            Self::Unbreakable { .. } => DataComponentType::Unbreakable,
+            Self::CustomName(_) => DataComponentType::CustomName,
+            Self::ItemName(_) => DataComponentType::ItemName,
+            Self::Lore(_) => DataComponentType::Lore,
+            Self::Rarity(_) => DataComponentType::Rarity,
+            Self::Enchantments { .. } => DataComponentType::Enchantments,
+            Self::HideAdditionalTooltip => DataComponentType::HideAdditionalTooltip,
+            Self::HideTooltip => DataComponentType::HideTooltip,
+            Self::RepairCost(_) => DataComponentType::RepairCost,
+            Self::EnchantmentGlintOverride(_) => DataComponentType::EnchantmentGlintOverride,
+            Self::FireResistant => DataComponentType::FireResistant,
+            Self::Raw { component_type, .. } => *component_type,
+        }
+    }
+
+    fn payload(self) -> Vec<u8> {
+        match self {
+            Self::CustomData(nbt) => nbt.0,
+            Self::MaxStackSize(size) => VarInt::<i32>::from(size).to_binary(),
+            Self::MaxDamage(value) | Self::Damage(value) | Self::RepairCost(value) => {
+                VarInt::new(value).to_binary()
+            }
+            Self::Unbreakable { show_in_tooltip } => show_in_tooltip.to_binary(),
+            Self::CustomName(name) | Self::ItemName(name) => NbtString(name).to_binary(),
+            Self::Lore(lines) => {
+                let mut bin = VarInt::<i32>::from(lines.len()).to_binary();
+                for line in lines {
+                    bin.extend(NbtString(line).to_binary());
+                }
+                bin
+            }
+            Self::Rarity(rarity) => VarInt::new(rarity as i32).to_binary(),
+            Self::Enchantments {
+                enchantments,
+                show_in_tooltip,
+            } => {
+                let mut bin = VarInt::<i32>::from(enchantments.len()).to_binary();
+                for Enchantment { id, level } in enchantments {
+                    bin.extend(VarInt::new(id).to_binary());
+                    bin.extend(VarInt::new(level).to_binary());
+                }
+                bin.extend(show_in_tooltip.to_binary());
+                bin
+            }
+            Self::HideAdditionalTooltip | Self::HideTooltip | Self::FireResistant => Vec::new(),
+            Self::EnchantmentGlintOverride(glint) => glint.to_binary(),
+            Self::Raw { payload, .. } => payload,
+        }
+    }
+
+    fn from_payload(component_type: DataComponentType, bin: &[u8], cursor: &mut usize) -> Result<Self> {
+        Ok(match component_type {
+            DataComponentType::CustomData => Self::CustomData(read(bin, cursor)?),
+            DataComponentType::MaxStackSize => {
+                let size = read::<VarInt<i32>>(bin, cursor)?.0;
+                Self::MaxStackSize(u8::try_from(size).map_err(|_| BinaryError::FormatError)?)
+            }
+            DataComponentType::MaxDamage => Self::MaxDamage(read::<VarInt<i32>>(bin, cursor)?.0),
+            DataComponentType::Damage => Self::Damage(read::<VarInt<i32>>(bin, cursor)?.0),
+            DataComponentType::RepairCost => Self::RepairCost(read::<VarInt<i32>>(bin, cursor)?.0),
+            DataComponentType::Unbreakable => Self::Unbreakable {
+                show_in_tooltip: read(bin, cursor)?,
+            },
+            DataComponentType::CustomName => Self::CustomName(read::<NbtString>(bin, cursor)?.0),
+            DataComponentType::ItemName => Self::ItemName(read::<NbtString>(bin, cursor)?.0),
+            DataComponentType::Lore => {
+                let len = read::<VarInt<i32>>(bin, cursor)?.0;
+                let lines = (0..len)
+                    .map(|_| read::<NbtString>(bin, cursor).map(|line| line.0))
+                    .collect::<Result<_>>()?;
+                Self::Lore(lines)
+            }
+            DataComponentType::Rarity => Self::Rarity(
+                Rarity::from_id(read::<VarInt<i32>>(bin, cursor)?.0).ok_or(BinaryError::FormatError)?,
+            ),
+            DataComponentType::Enchantments => {
+                let len = read::<VarInt<i32>>(bin, cursor)?.0;
+                let enchantments = (0..len)
+                    .map(|_| {
+                        Ok(Enchantment {
+                            id: read::<VarInt<i32>>(bin, cursor)?.0,
+                            level: read::<VarInt<i32>>(bin, cursor)?.0,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Self::Enchantments {
+                    enchantments,
+                    show_in_tooltip: read(bin, cursor)?,
+                }
+            }
+            DataComponentType::HideAdditionalTooltip => Self::HideAdditionalTooltip,
+            DataComponentType::HideTooltip => Self::HideTooltip,
+            DataComponentType::EnchantmentGlintOverride => {
+                Self::EnchantmentGlintOverride(read(bin, cursor)?)
+            }
+            DataComponentType::FireResistant => Self::FireResistant,
+            component_type => {
+                let start = *cursor;
+                Self::skip(component_type, bin, cursor)?;
+                Self::Raw {
+                    component_type,
+                    payload: bin[start..*cursor].to_vec(),
+                }
+            }
+        })
+    }
+
+    /// Move the cursor after the payload of a component that is not modelled
+    fn skip(component_type: DataComponentType, bin: &[u8], cursor: &mut usize) -> Result<()> {
+        match component_type {
+            DataComponentType::CustomModelData
+            | DataComponentType::MapId
+            | DataComponentType::MapPostProcessing
+            | DataComponentType::OminousBottleAmplifier
+            | DataComponentType::BaseColor => {
+                read::<VarInt<i32>>(bin, cursor)?;
+            }
+            DataComponentType::CreativeSlotLock | DataComponentType::IntangibleProjectile => {}
+            DataComponentType::MapColor => {
+                read::<i32>(bin, cursor)?;
+            }
+            DataComponentType::DyedColor => {
+                read::<i32>(bin, cursor)?;
+                read::<bool>(bin, cursor)?;
+            }
+            DataComponentType::StoredEnchantments => {
+                let len = read::<VarInt<i32>>(bin, cursor)?.0;
+                for _ in 0..len {
+                    read::<VarInt<i32>>(bin, cursor)?;
+                    read::<VarInt<i32>>(bin, cursor)?;
+                }
+                read::<bool>(bin, cursor)?;
+            }
+            DataComponentType::MapDecorations
+            | DataComponentType::DebugStickState
+            | DataComponentType::EntityData
+            | DataComponentType::BucketEntityData
+            | DataComponentType::BlockEntityData
+            | DataComponentType::Recipes
+            | DataComponentType::Lock
+            | DataComponentType::ContainerLoot => {
+                read::<RawNbt>(bin, cursor)?;
+            }
+            DataComponentType::NoteBlockSound => {
+                read::<String>(bin, cursor)?;
+            }
+            DataComponentType::BlockState => {
+                let len = read::<VarInt<i32>>(bin, cursor)?.0;
+                for _ in 0..len {
+                    read::<String>(bin, cursor)?;
+                    read::<String>(bin, cursor)?;
+                }
+            }
+            // nested item stacks, holder sets or particles, no way to tell where they end yet
+            _ => return Err(BinaryError::FormatError),
+        }
+        Ok(())
+    }
+}
+
+impl Binary for DataComponent {
+    fn binary_len(&self) -> usize {
+        self.clone().to_binary().len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        let mut bin = self.component_type().to_binary();
+        bin.extend(self.payload());
+        bin
+    }
+
+    fn from_binary(bin: Vec<u8>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = 0;
+        let component_type = read::<DataComponentType>(&bin, &mut cursor)?;
+        Self::from_payload(component_type, &bin, &mut cursor)
+    }
+}
@@ -0,0 +1,100 @@
+use super::ItemStack;
+
+/// Slots of the player inventory without the armor and the offhand, hotbar first
+pub const PLAYER_INVENTORY_SIZE: usize = 36;
+pub const HOTBAR_SIZE: usize = 9;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inventory {
+    slots: Vec<ItemStack>,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new(PLAYER_INVENTORY_SIZE)
+    }
+}
+
+impl Inventory {
+    pub fn new(size: usize) -> Self {
+        Self {
+            slots: vec![ItemStack::empty(); size],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn slots(&self) -> &[ItemStack] {
+        &self.slots
+    }
+
+    pub fn slot(&self, index: usize) -> Option<&ItemStack> {
+        self.slots.get(index)
+    }
+
+    pub fn set_slot(&mut self, index: usize, stack: ItemStack) {
+        if let Some(slot) = self.slots.get_mut(index) {
+            *slot = stack;
+        }
+    }
+
+    /// Merge `stack` into the similar stacks then into the empty slots,
+    /// returns the changed slots and what did not fit
+    pub fn add(&mut self, mut stack: ItemStack) -> (Vec<usize>, ItemStack) {
+        let mut changed = Vec::new();
+        let max = stack.max_stack_size();
+        for index in 0..self.slots.len() {
+            if stack.is_empty() {
+                break;
+            }
+            let slot = &mut self.slots[index];
+            if slot.is_empty() || !slot.is_similar(&stack) || slot.count() >= max {
+                continue;
+            }
+            let moved = stack.count().min(max - slot.count());
+            slot.set_count(slot.count() + moved);
+            stack.set_count(stack.count() - moved);
+            changed.push(index);
+        }
+        for index in 0..self.slots.len() {
+            if stack.is_empty() {
+                break;
+            }
+            if !self.slots[index].is_empty() {
+                continue;
+            }
+            let moved = stack.count().min(max);
+            let mut placed = stack.clone();
+            placed.set_count(moved);
+            self.slots[index] = placed;
+            stack.set_count(stack.count() - moved);
+            changed.push(index);
+        }
+        (changed, stack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_registry::item::Item;
+
+    use super::{Inventory, ItemStack};
+
+    #[test]
+    fn test_add() {
+        let mut inventory = Inventory::new(3);
+        inventory.set_slot(1, ItemStack::new(Item::Stone, 60));
+        let (changed, left) = inventory.add(ItemStack::new(Item::Stone, 68));
+        assert_eq!(changed, vec![1, 0]);
+        assert!(left.is_empty());
+        assert_eq!(inventory.slot(0).unwrap().count(), 64);
+        assert_eq!(inventory.slot(1).unwrap().count(), 64);
+
+        let (changed, left) = inventory.add(ItemStack::new(Item::Dirt, 100));
+        assert_eq!(changed, vec![2]);
+        assert_eq!(left.count(), 36);
+        assert_eq!(inventory.slot(2), Some(&ItemStack::new(Item::Dirt, 64)));
+    }
+}
@@ -0,0 +1,278 @@
+pub mod component;
+pub mod inventory;
+
+use component::{DataComponent, DataComponentType};
+use serde::{de, Deserialize, Serialize};
+use zr_binary::{
+    binary::{read, Binary},
+    error::Result,
+    varint::VarInt,
+};
+use zr_registry::item::Item;
+
+/// Content of an inventory slot (`Slot` on the wire).
+/// Components are stored as a patch over the item prototype:
+/// `components` are added or overridden and `removed` are dropped.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ItemStack {
+    item: Item,
+    count: u8,
+    components: Vec<DataComponent>,
+    removed: Vec<DataComponentType>,
+}
+
+impl ItemStack {
+    pub fn new(item: Item, count: u8) -> Self {
+        Self {
+            item,
+            count,
+            components: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0 || self.item.is_air()
+    }
+
+    pub fn item(&self) -> Item {
+        self.item
+    }
+
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count;
+    }
+
+    pub fn with(mut self, component: DataComponent) -> Self {
+        self.set_component(component);
+        self
+    }
+
+    pub fn components(&self) -> &[DataComponent] {
+        &self.components
+    }
+
+    pub fn component(&self, component_type: DataComponentType) -> Option<&DataComponent> {
+        self.components
+            .iter()
+            .find(|component| component.component_type() == component_type)
+    }
+
+    pub fn set_component(&mut self, component: DataComponent) {
+        let component_type = component.component_type();
+        self.removed.retain(|removed| *removed != component_type);
+        match self
+            .components
+            .iter_mut()
+            .find(|current| current.component_type() == component_type)
+        {
+            Some(current) => *current = component,
+            None => self.components.push(component),
+        }
+    }
+
+    /// Remove the component, including the one given by the item prototype
+    pub fn remove_component(&mut self, component_type: DataComponentType) {
+        self.components
+            .retain(|component| component.component_type() != component_type);
+        if !self.removed.contains(&component_type) {
+            self.removed.push(component_type);
+        }
+    }
+
+    /// Same item and components, whatever the count: the stacks can be merged
+    pub fn is_similar(&self, other: &ItemStack) -> bool {
+        self.item == other.item
+            && self.components == other.components
+            && self.removed == other.removed
+    }
+
+    pub fn max_stack_size(&self) -> u8 {
+        match self.component(DataComponentType::MaxStackSize) {
+            Some(DataComponent::MaxStackSize(size)) => *size,
+            _ => self.item.max_stack_size(),
+        }
+    }
+
+    pub fn custom_name(&self) -> Option<&str> {
+        match self.component(DataComponentType::CustomName) {
+            Some(DataComponent::CustomName(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn damage(&self) -> i32 {
+        match self.component(DataComponentType::Damage) {
+            Some(DataComponent::Damage(damage)) => *damage,
+            _ => 0,
+        }
+    }
+}
+
+impl Binary for ItemStack {
+    fn binary_len(&self) -> usize {
+        self.clone().to_binary().len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        if self.is_empty() {
+            return VarInt::new(0).to_binary();
+        }
+        let mut bin = VarInt::<i32>::from(self.count).to_binary();
+        bin.extend(self.item.to_binary());
+        bin.extend(VarInt::<i32>::from(self.components.len()).to_binary());
+        bin.extend(VarInt::<i32>::from(self.removed.len()).to_binary());
+        for component in self.components {
+            bin.extend(component.to_binary());
+        }
+        for removed in self.removed {
+            bin.extend(removed.to_binary());
+        }
+        bin
+    }
+
+    fn from_binary(bin: Vec<u8>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = 0;
+        let count = read::<VarInt<i32>>(&bin, &mut cursor)?.0;
+        if count <= 0 {
+            return Ok(Self::empty());
+        }
+        let item = read::<Item>(&bin, &mut cursor)?;
+        let added = read::<VarInt<i32>>(&bin, &mut cursor)?.0;
+        let removed = read::<VarInt<i32>>(&bin, &mut cursor)?.0;
+        Ok(Self {
+            item,
+            count: count.min(u8::MAX as i32) as u8,
+            components: (0..added)
+                .map(|_| read(&bin, &mut cursor))
+                .collect::<Result<_>>()?,
+            removed: (0..removed)
+                .map(|_| read(&bin, &mut cursor))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// Vanilla representation of an item stack, components are not kept
+#[derive(Serialize, Deserialize)]
+struct ItemStackCodec {
+    id: String,
+    #[serde(default = "ItemStackCodec::default_count")]
+    count: u8,
+}
+
+impl ItemStackCodec {
+    fn default_count() -> u8 {
+        1
+    }
+}
+
+impl Serialize for ItemStack {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        ItemStackCodec {
+            id: self.item.name().to_string(),
+            count: self.count,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemStack {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let ItemStackCodec { id, count } = ItemStackCodec::deserialize(deserializer)?;
+        let item = id.parse().map_err(de::Error::custom)?;
+        Ok(Self::new(item, count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_binary::binary::Binary;
+    use zr_registry::item::Item;
+
+    use super::{
+        component::{DataComponent, DataComponentType, Enchantment},
+        ItemStack,
+    };
+
+    #[test]
+    fn test_empty_slot() {
+        assert_eq!(ItemStack::empty().to_binary(), vec![0x00]);
+        assert_eq!(ItemStack::new(Item::Air, 12).to_binary(), vec![0x00]);
+        assert!(ItemStack::from_binary(vec![0x00]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_slot_wire_format() {
+        let stack = ItemStack::new(Item::Stone, 16).with(DataComponent::Damage(3));
+        assert_eq!(stack.clone().to_binary(), vec![16, 1, 1, 0, 3, 3]);
+        assert_eq!(stack.binary_len(), 6);
+    }
+
+    #[test]
+    fn test_slot_round_trip() {
+        let mut stack = ItemStack::new(Item::OakPlanks, 42)
+            .with(DataComponent::CustomName("Planche".to_string()))
+            .with(DataComponent::Lore(vec!["first".to_string(), "second".to_string()]))
+            .with(DataComponent::MaxStackSize(16))
+            .with(DataComponent::Enchantments {
+                enchantments: vec![Enchantment { id: 4, level: 2 }],
+                show_in_tooltip: true,
+            });
+        stack.remove_component(DataComponentType::Rarity);
+        let decoded = ItemStack::from_binary(stack.clone().to_binary()).unwrap();
+        assert_eq!(decoded, stack);
+        assert_eq!(decoded.custom_name(), Some("Planche"));
+        assert_eq!(decoded.max_stack_size(), 16);
+    }
+
+    #[test]
+    fn test_unmodelled_components() {
+        // custom_data {a: 1b}, then map_color and dyed_color kept as they are
+        let mut bin = vec![1, 1, 3, 0, 0, 0x0A, 0x01, 0, 1, b'a', 1, 0x00];
+        bin.extend([25, 0, 0xFF, 0, 0]);
+        bin.extend([24, 0, 0, 0, 0x2A, 1]);
+        let stack = ItemStack::from_binary(bin.clone()).unwrap();
+        assert_eq!(stack.components().len(), 3);
+        assert_eq!(
+            stack.component(DataComponentType::MapColor),
+            Some(&DataComponent::Raw {
+                component_type: DataComponentType::MapColor,
+                payload: vec![0, 0xFF, 0, 0],
+            })
+        );
+        assert_eq!(stack.binary_len(), bin.len());
+        assert_eq!(stack.to_binary(), bin);
+
+        // food holds item stacks, its end is unknown
+        assert!(ItemStack::from_binary(vec![1, 1, 1, 0, 20, 1]).is_err());
+    }
+
+    #[test]
+    fn test_component_override() {
+        let mut stack = ItemStack::new(Item::Dirt, 1);
+        stack.remove_component(DataComponentType::Damage);
+        stack.set_component(DataComponent::Damage(1));
+        stack.set_component(DataComponent::Damage(2));
+        assert_eq!(stack.components().len(), 1);
+        assert_eq!(stack.damage(), 2);
+        assert_eq!(stack.max_stack_size(), 64);
+    }
+}
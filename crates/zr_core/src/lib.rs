@@ -1,7 +1,19 @@
+pub mod access;
 pub mod cancellable;
+pub mod command;
+pub mod console;
 pub mod entity;
 pub mod handler;
+pub mod item;
 pub mod metadata;
 pub mod name;
 pub mod particle;
+pub mod permission;
+pub mod save;
+pub mod scheduler;
+pub mod settings;
+pub mod supervisor;
+pub mod tick;
+pub mod user_cache;
+pub mod world;
 pub mod worker; // maybe remove
@@ -1,9 +1,661 @@
-// maybe C code ???
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+};
 
-pub struct Metadata {
+use uuid::Uuid;
+use zr_binary::{
+    binary::{read, Binary},
+    error::{BinaryError, Result},
+    varint::VarInt,
+};
+use zr_data_type::{position::Position, string::Identifier};
+use zr_nbt::string::NbtString;
+use zr_registry::block::BlockState;
+
+use crate::{item::ItemStack, particle::Particle};
+
+const END_OF_METADATA: u8 = 0xFF;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Down,
+    Up,
+    North,
+    South,
+    West,
+    East,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pose {
+    Standing,
+    FallFlying,
+    Sleeping,
+    Swimming,
+    SpinAttack,
+    Sneaking,
+    LongJumping,
+    Dying,
+    Croaking,
+    UsingTongue,
+    Sitting,
+    Roaring,
+    Sniffing,
+    Emerging,
+    Digging,
+    Sliding,
+    Shooting,
+    Inhaling,
+}
+
+impl Direction {
+    const ALL: [Direction; 6] = [
+        Self::Down,
+        Self::Up,
+        Self::North,
+        Self::South,
+        Self::West,
+        Self::East,
+    ];
+}
+
+impl Pose {
+    const ALL: [Pose; 18] = [
+        Self::Standing,
+        Self::FallFlying,
+        Self::Sleeping,
+        Self::Swimming,
+        Self::SpinAttack,
+        Self::Sneaking,
+        Self::LongJumping,
+        Self::Dying,
+        Self::Croaking,
+        Self::UsingTongue,
+        Self::Sitting,
+        Self::Roaring,
+        Self::Sniffing,
+        Self::Emerging,
+        Self::Digging,
+        Self::Sliding,
+        Self::Shooting,
+        Self::Inhaling,
+    ];
+}
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VillagerData {
+    pub villager_type: i32,
+    pub profession: i32,
+    pub level: i32,
 }
 
+/// One value of each serializer type, in the order of the protocol type ids.
+/// Text components are plain strings for now.
+#[derive(Debug, Clone, PartialEq)]
 pub enum MetadataValue {
+    Byte(i8),
+    VarInt(i32),
+    VarLong(i64),
+    Float(f32),
+    String(String),
+    TextComponent(String),
+    OptionalTextComponent(Option<String>),
+    Slot(ItemStack),
+    Boolean(bool),
+    Rotations(f32, f32, f32),
+    Position(Position),
+    OptionalPosition(Option<Position>),
+    Direction(Direction),
+    OptionalUuid(Option<Uuid>),
+    BlockState(BlockState),
+    OptionalBlockState(Option<BlockState>),
+    Nbt(Vec<u8>), // TODO : network nbt
+    Particle(Particle),
+    Particles(Vec<Particle>),
+    VillagerData(VillagerData),
+    OptionalVarInt(Option<i32>),
+    Pose(Pose),
+    CatVariant(i32),
+    WolfVariant(i32),
+    FrogVariant(i32),
+    OptionalGlobalPosition(Option<(Identifier, Position)>),
+    PaintingVariant(i32),
+    SnifferState(i32),
+    ArmadilloState(i32),
+    Vector3(f32, f32, f32),
+    Quaternion(f32, f32, f32, f32),
+}
+
+fn write_optional<T: Binary>(bin: &mut Vec<u8>, value: Option<T>) {
+    bin.extend(value.is_some().to_binary());
+    if let Some(value) = value {
+        bin.extend(value.to_binary());
+    }
+}
+
+fn read_optional<T: Binary>(bin: &[u8], cursor: &mut usize) -> Result<Option<T>> {
+    if read::<bool>(bin, cursor)? {
+        Ok(Some(read(bin, cursor)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn read_varint(bin: &[u8], cursor: &mut usize) -> Result<i32> {
+    Ok(read::<VarInt<i32>>(bin, cursor)?.0)
+}
+
+impl MetadataValue {
+    pub fn type_id(&self) -> i32 {
+        match self {
+            Self::Byte(_) => 0,
+            Self::VarInt(_) => 1,
+            Self::VarLong(_) => 2,
+            Self::Float(_) => 3,
+            Self::String(_) => 4,
+            Self::TextComponent(_) => 5,
+            Self::OptionalTextComponent(_) => 6,
+            Self::Slot(_) => 7,
+            Self::Boolean(_) => 8,
+            Self::Rotations(..) => 9,
+            Self::Position(_) => 10,
+            Self::OptionalPosition(_) => 11,
+            Self::Direction(_) => 12,
+            Self::OptionalUuid(_) => 13,
+            Self::BlockState(_) => 14,
+            Self::OptionalBlockState(_) => 15,
+            Self::Nbt(_) => 16,
+            Self::Particle(_) => 17,
+            Self::Particles(_) => 18,
+            Self::VillagerData(_) => 19,
+            Self::OptionalVarInt(_) => 20,
+            Self::Pose(_) => 21,
+            Self::CatVariant(_) => 22,
+            Self::WolfVariant(_) => 23,
+            Self::FrogVariant(_) => 24,
+            Self::OptionalGlobalPosition(_) => 25,
+            Self::PaintingVariant(_) => 26,
+            Self::SnifferState(_) => 27,
+            Self::ArmadilloState(_) => 28,
+            Self::Vector3(..) => 29,
+            Self::Quaternion(..) => 30,
+        }
+    }
+
+    fn payload(self) -> Vec<u8> {
+        let mut bin = Vec::new();
+        match self {
+            Self::Byte(value) => bin.extend(value.to_binary()),
+            Self::VarInt(value)
+            | Self::CatVariant(value)
+            | Self::WolfVariant(value)
+            | Self::FrogVariant(value)
+            | Self::PaintingVariant(value)
+            | Self::SnifferState(value)
+            | Self::ArmadilloState(value) => bin.extend(VarInt::new(value).to_binary()),
+            Self::VarLong(value) => bin.extend(VarInt::new(value).to_binary()),
+            Self::Float(value) => bin.extend(value.to_binary()),
+            Self::String(value) => bin.extend(value.to_binary()),
+            Self::TextComponent(value) => bin.extend(NbtString(value).to_binary()),
+            Self::OptionalTextComponent(value) => write_optional(&mut bin, value.map(NbtString)),
+            Self::Slot(value) => bin.extend(value.to_binary()),
+            Self::Boolean(value) => bin.extend(value.to_binary()),
+            Self::Rotations(x, y, z) | Self::Vector3(x, y, z) => {
+                bin.extend(x.to_binary());
+                bin.extend(y.to_binary());
+                bin.extend(z.to_binary());
+            }
+            Self::Position(value) => bin.extend(value.to_binary()),
+            Self::OptionalPosition(value) => write_optional(&mut bin, value),
+            Self::Direction(value) => bin.extend(VarInt::new(value as i32).to_binary()),
+            Self::OptionalUuid(value) => write_optional(&mut bin, value),
+            Self::BlockState(value) => bin.extend(value.to_binary()),
+            // air is used as absent
+            Self::OptionalBlockState(value) => bin.extend(value.unwrap_or_default().to_binary()),
+            Self::Nbt(value) => bin.extend(value),
+            Self::Particle(value) => bin.extend(value.to_binary()),
+            Self::Particles(values) => {
+                bin.extend(VarInt::<i32>::from(values.len()).to_binary());
+                for value in values {
+                    bin.extend(value.to_binary());
+                }
+            }
+            Self::VillagerData(VillagerData {
+                villager_type,
+                profession,
+                level,
+            }) => {
+                bin.extend(VarInt::new(villager_type).to_binary());
+                bin.extend(VarInt::new(profession).to_binary());
+                bin.extend(VarInt::new(level).to_binary());
+            }
+            // 0 is absent, otherwise value + 1
+            Self::OptionalVarInt(value) => {
+                bin.extend(VarInt::new(value.map_or(0, |value| value + 1)).to_binary())
+            }
+            Self::Pose(value) => bin.extend(VarInt::new(value as i32).to_binary()),
+            Self::OptionalGlobalPosition(value) => {
+                bin.extend(value.is_some().to_binary());
+                if let Some((dimension, position)) = value {
+                    bin.extend(dimension.to_binary());
+                    bin.extend(position.to_binary());
+                }
+            }
+            Self::Quaternion(x, y, z, w) => {
+                bin.extend(x.to_binary());
+                bin.extend(y.to_binary());
+                bin.extend(z.to_binary());
+                bin.extend(w.to_binary());
+            }
+        }
+        bin
+    }
+
+    fn from_payload(type_id: i32, bin: &[u8], cursor: &mut usize) -> Result<Self> {
+        Ok(match type_id {
+            0 => Self::Byte(read(bin, cursor)?),
+            1 => Self::VarInt(read_varint(bin, cursor)?),
+            2 => Self::VarLong(read::<VarInt<i64>>(bin, cursor)?.0),
+            3 => Self::Float(read(bin, cursor)?),
+            4 => Self::String(read(bin, cursor)?),
+            5 => Self::TextComponent(read::<NbtString>(bin, cursor)?.0),
+            6 => Self::OptionalTextComponent(
+                read_optional::<NbtString>(bin, cursor)?.map(|text| text.0),
+            ),
+            7 => Self::Slot(read(bin, cursor)?),
+            8 => Self::Boolean(read(bin, cursor)?),
+            9 => Self::Rotations(read(bin, cursor)?, read(bin, cursor)?, read(bin, cursor)?),
+            10 => Self::Position(read(bin, cursor)?),
+            11 => Self::OptionalPosition(read_optional(bin, cursor)?),
+            12 => Self::Direction(
+                *Direction::ALL
+                    .get(read_varint(bin, cursor)? as usize)
+                    .ok_or(BinaryError::FormatError)?,
+            ),
+            13 => Self::OptionalUuid(read_optional(bin, cursor)?),
+            14 => Self::BlockState(read(bin, cursor)?),
+            15 => {
+                let state = read::<BlockState>(bin, cursor)?;
+                Self::OptionalBlockState((!state.is_air()).then_some(state))
+            }
+            17 => Self::Particle(read(bin, cursor)?),
+            18 => {
+                let len = read_varint(bin, cursor)?;
+                Self::Particles((0..len).map(|_| read(bin, cursor)).collect::<Result<_>>()?)
+            }
+            19 => Self::VillagerData(VillagerData {
+                villager_type: read_varint(bin, cursor)?,
+                profession: read_varint(bin, cursor)?,
+                level: read_varint(bin, cursor)?,
+            }),
+            20 => {
+                let value = read_varint(bin, cursor)?;
+                Self::OptionalVarInt((value != 0).then_some(value - 1))
+            }
+            21 => Self::Pose(
+                *Pose::ALL
+                    .get(read_varint(bin, cursor)? as usize)
+                    .ok_or(BinaryError::FormatError)?,
+            ),
+            22 => Self::CatVariant(read_varint(bin, cursor)?),
+            23 => Self::WolfVariant(read_varint(bin, cursor)?),
+            24 => Self::FrogVariant(read_varint(bin, cursor)?),
+            25 => Self::OptionalGlobalPosition(if read::<bool>(bin, cursor)? {
+                Some((read(bin, cursor)?, read(bin, cursor)?))
+            } else {
+                None
+            }),
+            26 => Self::PaintingVariant(read_varint(bin, cursor)?),
+            27 => Self::SnifferState(read_varint(bin, cursor)?),
+            28 => Self::ArmadilloState(read_varint(bin, cursor)?),
+            29 => Self::Vector3(read(bin, cursor)?, read(bin, cursor)?, read(bin, cursor)?),
+            30 => Self::Quaternion(
+                read(bin, cursor)?,
+                read(bin, cursor)?,
+                read(bin, cursor)?,
+                read(bin, cursor)?,
+            ),
+            // 16 (nbt) : the payload length is unknown without a nbt reader
+            _ => return Err(BinaryError::FormatError),
+        })
+    }
+}
+
+/// Typed index of a metadata entry, see [`schema`].
+pub struct MetadataKey<T> {
+    pub index: u8,
+    wrap: fn(T) -> MetadataValue,
+}
+
+impl<T> MetadataKey<T> {
+    pub const fn new(index: u8, wrap: fn(T) -> MetadataValue) -> Self {
+        Self { index, wrap }
+    }
+}
+
+impl<T> Clone for MetadataKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MetadataKey<T> {}
+
+/// Entity metadata with dirty tracking:
+/// every changed entry is kept until [`Metadata::take_changes`] is called.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    entries: BTreeMap<u8, MetadataValue>,
+    dirty: BTreeSet<u8>,
+}
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, index: u8) -> Option<&MetadataValue> {
+        self.entries.get(&index)
+    }
+
+    pub fn set<T>(&mut self, key: MetadataKey<T>, value: T) {
+        self.set_value(key.index, (key.wrap)(value));
+    }
+
+    pub fn set_value(&mut self, index: u8, value: MetadataValue) {
+        if self.entries.get(&index) != Some(&value) {
+            self.entries.insert(index, value);
+            self.dirty.insert(index);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Entries changed since the last call, the tracking is reset
+    pub fn take_changes(&mut self) -> Metadata {
+        let dirty = std::mem::take(&mut self.dirty);
+        Metadata {
+            entries: dirty
+                .into_iter()
+                .filter_map(|index| Some((index, self.entries.get(&index)?.clone())))
+                .collect(),
+            dirty: BTreeSet::new(),
+        }
+    }
+
+    fn flags(&self, index: u8) -> i8 {
+        match self.entries.get(&index) {
+            Some(MetadataValue::Byte(flags)) => *flags,
+            _ => 0,
+        }
+    }
+
+    /// Bits of a byte entry, 0 when unset
+    pub fn bits(&self, key: MetadataKey<i8>) -> u8 {
+        self.flags(key.index) as u8
+    }
+
+    pub fn set_bit(&mut self, key: MetadataKey<i8>, mask: u8, value: bool) {
+        let flags = self.flags(key.index) as u8;
+        let flags = if value { flags | mask } else { flags & !mask };
+        self.set(key, flags as i8);
+    }
+
+    pub fn has_flag(&self, flag: schema::EntityFlag) -> bool {
+        self.flags(schema::entity::FLAGS.index) as u8 & flag as u8 != 0
+    }
+
+    pub fn set_flag(&mut self, flag: schema::EntityFlag, value: bool) {
+        self.set_bit(schema::entity::FLAGS, flag as u8, value);
+    }
+
+    pub fn has_skin_part(&self, part: schema::SkinPart) -> bool {
+        self.flags(schema::player::DISPLAYED_SKIN_PARTS.index) as u8 & part as u8 != 0
+    }
+
+    pub fn set_skin_part(&mut self, part: schema::SkinPart, value: bool) {
+        self.set_bit(schema::player::DISPLAYED_SKIN_PARTS, part as u8, value);
+    }
+}
+
+impl Binary for Metadata {
+    fn binary_len(&self) -> usize {
+        self.clone().to_binary().len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        let mut bin = Vec::new();
+        for (index, value) in self.entries {
+            bin.push(index);
+            bin.extend(VarInt::new(value.type_id()).to_binary());
+            bin.extend(value.payload());
+        }
+        bin.push(END_OF_METADATA);
+        bin
+    }
+
+    fn from_binary(bin: Vec<u8>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = 0;
+        let mut metadata = Metadata::new();
+        loop {
+            let index = read::<u8>(&bin, &mut cursor)?;
+            if index == END_OF_METADATA {
+                break Ok(metadata);
+            }
+            let type_id = read_varint(&bin, &mut cursor)?;
+            let value = MetadataValue::from_payload(type_id, &bin, &mut cursor)?;
+            metadata.entries.insert(index, value);
+        }
+    }
+}
+
+/// Metadata entries of each entity kind, a kind also owns the entries of its parents.
+pub mod schema {
+    use super::{MetadataKey, MetadataValue, Pose};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum EntityFlag {
+        OnFire = 0x01,
+        Crouching = 0x02,
+        Sprinting = 0x08,
+        Swimming = 0x10,
+        Invisible = 0x20,
+        Glowing = 0x40,
+        FallFlying = 0x80,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SkinPart {
+        Cape = 0x01,
+        Jacket = 0x02,
+        LeftSleeve = 0x04,
+        RightSleeve = 0x08,
+        LeftPants = 0x10,
+        RightPants = 0x20,
+        Hat = 0x40,
+    }
+
+    pub mod entity {
+        use super::*;
+
+        pub const FLAGS: MetadataKey<i8> = MetadataKey::new(0, MetadataValue::Byte);
+        pub const AIR_TICKS: MetadataKey<i32> = MetadataKey::new(1, MetadataValue::VarInt);
+        pub const CUSTOM_NAME: MetadataKey<Option<String>> =
+            MetadataKey::new(2, MetadataValue::OptionalTextComponent);
+        pub const CUSTOM_NAME_VISIBLE: MetadataKey<bool> =
+            MetadataKey::new(3, MetadataValue::Boolean);
+        pub const SILENT: MetadataKey<bool> = MetadataKey::new(4, MetadataValue::Boolean);
+        pub const NO_GRAVITY: MetadataKey<bool> = MetadataKey::new(5, MetadataValue::Boolean);
+        pub const POSE: MetadataKey<Pose> = MetadataKey::new(6, MetadataValue::Pose);
+        pub const TICKS_FROZEN: MetadataKey<i32> = MetadataKey::new(7, MetadataValue::VarInt);
+    }
+
+    pub mod living_entity {
+        use super::*;
+
+        pub const HAND_STATES: MetadataKey<i8> = MetadataKey::new(8, MetadataValue::Byte);
+        pub const HEALTH: MetadataKey<f32> = MetadataKey::new(9, MetadataValue::Float);
+        pub const POTION_EFFECT_PARTICLES: MetadataKey<Vec<crate::particle::Particle>> =
+            MetadataKey::new(10, MetadataValue::Particles);
+        pub const POTION_EFFECT_AMBIENT: MetadataKey<bool> =
+            MetadataKey::new(11, MetadataValue::Boolean);
+        pub const ARROWS: MetadataKey<i32> = MetadataKey::new(12, MetadataValue::VarInt);
+        pub const BEE_STINGERS: MetadataKey<i32> = MetadataKey::new(13, MetadataValue::VarInt);
+        pub const SLEEPING_BED: MetadataKey<Option<zr_data_type::position::Position>> =
+            MetadataKey::new(14, MetadataValue::OptionalPosition);
+    }
+
+    pub mod player {
+        use super::*;
+
+        pub const ADDITIONAL_HEARTS: MetadataKey<f32> = MetadataKey::new(15, MetadataValue::Float);
+        pub const SCORE: MetadataKey<i32> = MetadataKey::new(16, MetadataValue::VarInt);
+        pub const DISPLAYED_SKIN_PARTS: MetadataKey<i8> = MetadataKey::new(17, MetadataValue::Byte);
+        pub const MAIN_HAND: MetadataKey<i8> = MetadataKey::new(18, MetadataValue::Byte);
+    }
+
+    pub mod mob {
+        use super::*;
+
+        pub const FLAGS: MetadataKey<i8> = MetadataKey::new(15, MetadataValue::Byte);
+    }
+
+    pub mod ageable_mob {
+        use super::*;
+
+        pub const BABY: MetadataKey<bool> = MetadataKey::new(16, MetadataValue::Boolean);
+    }
+
+    pub mod tamable_animal {
+        use super::*;
+
+        pub const FLAGS: MetadataKey<i8> = MetadataKey::new(17, MetadataValue::Byte);
+        pub const OWNER: MetadataKey<Option<uuid::Uuid>> =
+            MetadataKey::new(18, MetadataValue::OptionalUuid);
+
+        pub const SITTING: u8 = 0x01;
+        pub const TAMED: u8 = 0x04;
+    }
+
+    pub mod sheep {
+        use super::*;
+
+        /// color in the low bits, see [`SHEARED`]
+        pub const FLAGS: MetadataKey<i8> = MetadataKey::new(17, MetadataValue::Byte);
+
+        pub const SHEARED: u8 = 0x10;
+    }
+
+    pub mod pig {
+        use super::*;
+
+        pub const SADDLE: MetadataKey<bool> = MetadataKey::new(17, MetadataValue::Boolean);
+        pub const BOOST_TIME: MetadataKey<i32> = MetadataKey::new(18, MetadataValue::VarInt);
+    }
+
+    pub mod strider {
+        use super::*;
+
+        pub const BOOST_TIME: MetadataKey<i32> = MetadataKey::new(17, MetadataValue::VarInt);
+        pub const SHAKING: MetadataKey<bool> = MetadataKey::new(18, MetadataValue::Boolean);
+        pub const SADDLE: MetadataKey<bool> = MetadataKey::new(19, MetadataValue::Boolean);
+    }
+
+    pub mod wolf {
+        use super::*;
+
+        pub const BEGGING: MetadataKey<bool> = MetadataKey::new(19, MetadataValue::Boolean);
+        pub const COLLAR_COLOR: MetadataKey<i32> = MetadataKey::new(20, MetadataValue::VarInt);
+        pub const ANGER_TIME: MetadataKey<i32> = MetadataKey::new(21, MetadataValue::VarInt);
+        pub const VARIANT: MetadataKey<i32> = MetadataKey::new(22, MetadataValue::WolfVariant);
+    }
+
+    pub mod cat {
+        use super::*;
+
+        pub const VARIANT: MetadataKey<i32> = MetadataKey::new(19, MetadataValue::CatVariant);
+        pub const LYING: MetadataKey<bool> = MetadataKey::new(20, MetadataValue::Boolean);
+        pub const RELAXED: MetadataKey<bool> = MetadataKey::new(21, MetadataValue::Boolean);
+        pub const COLLAR_COLOR: MetadataKey<i32> = MetadataKey::new(22, MetadataValue::VarInt);
+    }
+
+    pub mod thrown_item {
+        use super::*;
+
+        pub const ITEM: MetadataKey<crate::item::ItemStack> =
+            MetadataKey::new(8, MetadataValue::Slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_binary::binary::Binary;
+
+    use super::{
+        schema::{entity, living_entity, player, EntityFlag, SkinPart},
+        Metadata, MetadataValue, Pose,
+    };
+
+    #[test]
+    fn test_dirty_tracking() {
+        let mut metadata = Metadata::new();
+        metadata.set(living_entity::HEALTH, 20.0);
+        metadata.set(entity::POSE, Pose::Standing);
+        assert_eq!(
+            metadata.take_changes().binary_len(),
+            1 + 1 + 4 + 1 + 1 + 1 + 1
+        );
+        assert!(!metadata.is_dirty());
+
+        // same value is not sent again
+        metadata.set(living_entity::HEALTH, 20.0);
+        assert!(!metadata.is_dirty());
+
+        metadata.set(living_entity::HEALTH, 12.5);
+        let changes = metadata.take_changes();
+        assert_eq!(changes.get(9), Some(&MetadataValue::Float(12.5)));
+        assert_eq!(changes.get(6), None);
+        assert_eq!(metadata.get(6), Some(&MetadataValue::Pose(Pose::Standing)));
+    }
+
+    #[test]
+    fn test_flags() {
+        let mut metadata = Metadata::new();
+        metadata.set_flag(EntityFlag::OnFire, true);
+        metadata.set_flag(EntityFlag::Glowing, true);
+        metadata.set_flag(EntityFlag::OnFire, false);
+        assert!(metadata.has_flag(EntityFlag::Glowing));
+        assert!(!metadata.has_flag(EntityFlag::OnFire));
+        assert_eq!(metadata.get(0), Some(&MetadataValue::Byte(0x40)));
+
+        metadata.set_skin_part(SkinPart::Hat, true);
+        metadata.set_skin_part(SkinPart::Cape, true);
+        assert!(metadata.has_skin_part(SkinPart::Hat));
+        assert_eq!(metadata.get(17), Some(&MetadataValue::Byte(0x41)));
+    }
 
+    #[test]
+    fn test_wire_format() {
+        let mut metadata = Metadata::new();
+        metadata.set(entity::CUSTOM_NAME_VISIBLE, true);
+        metadata.set(player::SCORE, 300);
+        assert_eq!(
+            metadata.clone().to_binary(),
+            vec![3, 8, 1, 16, 1, 0xAC, 0x02, 0xFF]
+        );
+        metadata.set(entity::CUSTOM_NAME, Some("zirkonya".to_string()));
+        metadata.set(living_entity::SLEEPING_BED, None);
+        let decoded = Metadata::from_binary(metadata.clone().to_binary()).unwrap();
+        assert_eq!(decoded.entries, metadata.entries);
+    }
 }
@@ -1,17 +1,141 @@
 use serde::{Deserialize, Serialize};
-use zr_binary::varint::VarInt;
+use zr_binary::{
+    binary::{read, Binary},
+    error::{BinaryError, Result},
+    varint::VarInt,
+};
+use zr_data_type::position::Position;
+use zr_registry::block::BlockState;
 
-pub type Slot = (); // TODO : item
-pub type Position = (); // TODO : position
+use crate::item::ItemStack;
 
-#[derive(Clone, Serialize, Deserialize)]
-pub enum Particle {
+pub type Slot = ItemStack;
+
+/// Target of a vibration particle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PositionSource {
+    Block {
+        position: Position,
+    },
+    Entity {
+        entity_id: VarInt<i32>,
+        eye_height: f32,
+    },
+}
+
+impl Binary for PositionSource {
+    fn binary_len(&self) -> usize {
+        match self {
+            Self::Block { position } => 1 + position.binary_len(),
+            Self::Entity { entity_id, .. } => 1 + entity_id.binary_len() + 4,
+        }
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        match self {
+            Self::Block { position } => {
+                let mut bin = VarInt::new(0).to_binary();
+                bin.extend(position.to_binary());
+                bin
+            }
+            Self::Entity {
+                entity_id,
+                eye_height,
+            } => {
+                let mut bin = VarInt::new(1).to_binary();
+                bin.extend(entity_id.to_binary());
+                bin.extend(eye_height.to_binary());
+                bin
+            }
+        }
+    }
+
+    fn from_binary(bin: Vec<u8>) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = 0;
+        match read::<VarInt<i32>>(&bin, &mut cursor)?.0 {
+            0 => Ok(Self::Block {
+                position: read(&bin, &mut cursor)?,
+            }),
+            1 => Ok(Self::Entity {
+                entity_id: read(&bin, &mut cursor)?,
+                eye_height: read(&bin, &mut cursor)?,
+            }),
+            _ => Err(BinaryError::FormatError),
+        }
+    }
+}
+
+/// Declare `Particle` and `ParticleType`, variants are listed in registry order
+/// and their fields are encoded one after the other.
+macro_rules! particles {
+    ($($variant: ident $({ $($field: ident: $ty: ty),* $(,)? })?),* $(,)?) => {
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        pub enum Particle {
+            $($variant $({ $($field: $ty),* })?),*
+        }
+
+        /// Ids of the `minecraft:particle_type` registry
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ParticleType {
+            $($variant),*
+        }
+
+        impl ParticleType {
+            pub const ALL: &'static [ParticleType] = &[$(ParticleType::$variant),*];
+        }
+
+        impl Particle {
+            pub fn particle_type(&self) -> ParticleType {
+                match self {
+                    $(Particle::$variant { .. } => ParticleType::$variant),*
+                }
+            }
+        }
+
+        impl Binary for Particle {
+            fn binary_len(&self) -> usize {
+                self.clone().to_binary().len()
+            }
+
+            fn to_binary(self) -> Vec<u8> {
+                let mut bin = VarInt::new(self.particle_type() as i32).to_binary();
+                match self {
+                    $(Particle::$variant $({ $($field),* })? => {
+                        $($(bin.extend($field.to_binary());)*)?
+                    })*
+                }
+                bin
+            }
+
+            fn from_binary(bin: Vec<u8>) -> Result<Self>
+            where
+                Self: Sized,
+            {
+                let mut cursor = 0;
+                let id = read::<VarInt<i32>>(&bin, &mut cursor)?.0;
+                let particle_type = ParticleType::ALL
+                    .get(id as usize)
+                    .ok_or(BinaryError::FormatError)?;
+                Ok(match particle_type {
+                    $(ParticleType::$variant => Particle::$variant $({
+                        $($field: read::<$ty>(&bin, &mut cursor)?),*
+                    })?,)*
+                })
+            }
+        }
+    };
+}
+
+particles! {
     AngryVillager,
     Block {
-        block_state: VarInt<i32>,
+        block_state: BlockState,
     },
     BlockMarker {
-        block_state: VarInt<i32>,
+        block_state: BlockState,
     },
     Bubble,
     Cloud,
@@ -54,7 +178,7 @@ pub enum Particle {
     GustEmitterSmall,
     SonicBoom,
     FallingDust {
-        block_state: VarInt<i32>,
+        block_state: BlockState,
     },
     Firework,
     Fishing,
@@ -77,10 +201,7 @@ pub enum Particle {
         item: Slot,
     },
     Vibration {
-        position_source_type: VarInt<i32>,
-        block_position: Position,
-        entity_id: VarInt<i32>,
-        entity_eye_height: f32,
+        source: PositionSource,
         ticks: VarInt<i32>,
     },
     ItemSlime,
@@ -145,7 +266,7 @@ pub enum Particle {
     TrialSpawnerDetectionOminous,
     VaultConnection,
     DustPillar {
-        block_state: VarInt<i32>,
+        block_state: BlockState,
     },
     OminousSpawning,
     RaidOmen,
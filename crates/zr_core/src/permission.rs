@@ -0,0 +1,368 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const OPS_FILE: &str = "ops.json";
+pub const PERMISSIONS_FILE: &str = "permissions.json";
+/// Group of every player, even without any group of their own
+pub const DEFAULT_GROUP: &str = "default";
+/// Level given by `/op`, as the vanilla `op-permission-level`
+pub const DEFAULT_OP_LEVEL: u8 = 4;
+pub const MAX_OP_LEVEL: u8 = 4;
+
+/// Entry of `ops.json`, in the vanilla format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    pub level: u8,
+    pub bypasses_player_limit: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    /// groups whose permissions apply after the ones of this group
+    #[serde(default)]
+    pub inherits: Vec<String>,
+    #[serde(default)]
+    pub permissions: BTreeMap<String, bool>,
+}
+
+/// Groups and overrides of a player
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerPermissions {
+    #[serde(default)]
+    pub groups: Vec<String>,
+    #[serde(default)]
+    pub permissions: BTreeMap<String, bool>,
+}
+
+/// Content of `permissions.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct PermissionsFile {
+    #[serde(default)]
+    groups: BTreeMap<String, Group>,
+    /// by player name
+    #[serde(default)]
+    players: BTreeMap<String, PlayerPermissions>,
+}
+
+/// Value of `node` in `permissions`, the most specific match wins:
+/// `a.b.c`, then `a.b.*`, `a.*` and `*`
+fn lookup(permissions: &BTreeMap<String, bool>, node: &str) -> Option<bool> {
+    if let Some(value) = permissions.get(node) {
+        return Some(*value);
+    }
+    let mut prefix = node;
+    while let Some((parent, _)) = prefix.rsplit_once('.') {
+        if let Some(value) = permissions.get(&format!("{parent}.*")) {
+            return Some(*value);
+        }
+        prefix = parent;
+    }
+    permissions.get("*").copied()
+}
+
+pub(crate) fn read_json<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(io::Error::from),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(err),
+    }
+}
+
+pub(crate) fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(value)?)
+}
+
+/// Operator levels and permission nodes of the players
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Permissions {
+    /// where `ops.json` and `permissions.json` are saved, `None` to keep them in memory
+    directory: Option<PathBuf>,
+    ops: Vec<OpEntry>,
+    groups: BTreeMap<String, Group>,
+    players: BTreeMap<String, PlayerPermissions>,
+}
+
+impl Permissions {
+    /// Permissions kept in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read `ops.json` and `permissions.json` from `directory`, missing files are empty
+    pub fn load(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        let ops = read_json(&directory.join(OPS_FILE))?;
+        let PermissionsFile { groups, players } = read_json(&directory.join(PERMISSIONS_FILE))?;
+        Ok(Self {
+            directory: Some(directory),
+            ops,
+            groups,
+            players,
+        })
+    }
+
+    /// Read the files again, the changes which were not saved are lost
+    pub fn reload(&mut self) -> io::Result<()> {
+        if let Some(directory) = &self.directory {
+            *self = Self::load(directory.clone())?;
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(directory) = &self.directory else {
+            return Ok(());
+        };
+        fs::create_dir_all(directory)?;
+        write_json(&directory.join(OPS_FILE), &self.ops)?;
+        write_json(
+            &directory.join(PERMISSIONS_FILE),
+            &PermissionsFile {
+                groups: self.groups.clone(),
+                players: self.players.clone(),
+            },
+        )
+    }
+
+    pub fn ops(&self) -> &[OpEntry] {
+        &self.ops
+    }
+
+    /// Operator level of the player, 0 when not an operator
+    pub fn op_level(&self, name: &str) -> u8 {
+        self.ops
+            .iter()
+            .find(|op| op.name.eq_ignore_ascii_case(name))
+            .map_or(0, |op| op.level)
+    }
+
+    /// `false` if the player already had this level
+    pub fn op(&mut self, name: &str, uuid: Uuid, level: u8) -> bool {
+        let level = level.min(MAX_OP_LEVEL);
+        match self
+            .ops
+            .iter_mut()
+            .find(|op| op.name.eq_ignore_ascii_case(name))
+        {
+            Some(op) if op.level == level => false,
+            Some(op) => {
+                op.level = level;
+                true
+            }
+            None => {
+                self.ops.push(OpEntry {
+                    uuid,
+                    name: name.to_string(),
+                    level,
+                    bypasses_player_limit: false,
+                });
+                true
+            }
+        }
+    }
+
+    /// `false` if the player was not an operator
+    pub fn deop(&mut self, name: &str) -> bool {
+        let count = self.ops.len();
+        self.ops.retain(|op| !op.name.eq_ignore_ascii_case(name));
+        self.ops.len() != count
+    }
+
+    pub fn group(&self, name: &str) -> Option<&Group> {
+        self.groups.get(name)
+    }
+
+    /// The group, created when missing
+    pub fn group_mut(&mut self, name: &str) -> &mut Group {
+        self.groups.entry(name.to_string()).or_default()
+    }
+
+    pub fn player(&self, name: &str) -> Option<&PlayerPermissions> {
+        self.players.get(name)
+    }
+
+    pub fn player_mut(&mut self, name: &str) -> &mut PlayerPermissions {
+        self.players.entry(name.to_string()).or_default()
+    }
+
+    pub fn add_to_group(&mut self, player: &str, group: &str) {
+        let groups = &mut self.player_mut(player).groups;
+        if !groups.iter().any(|name| name == group) {
+            groups.push(group.to_string());
+        }
+    }
+
+    pub fn remove_from_group(&mut self, player: &str, group: &str) {
+        if let Some(permissions) = self.players.get_mut(player) {
+            permissions.groups.retain(|name| name != group);
+        }
+    }
+
+    /// Override `node` for the player, `None` removes the override
+    pub fn set_permission(&mut self, player: &str, node: &str, value: Option<bool>) {
+        let permissions = &mut self.player_mut(player).permissions;
+        match value {
+            Some(value) => permissions.insert(node.to_string(), value),
+            None => permissions.remove(node),
+        };
+    }
+
+    /// Value of `node` in the group or the groups it inherits
+    fn group_permission(
+        &self,
+        group: &str,
+        node: &str,
+        visited: &mut BTreeSet<String>,
+    ) -> Option<bool> {
+        if !visited.insert(group.to_string()) {
+            return None;
+        }
+        let group = self.groups.get(group)?;
+        lookup(&group.permissions, node).or_else(|| {
+            group
+                .inherits
+                .iter()
+                .find_map(|parent| self.group_permission(parent, node, visited))
+        })
+    }
+
+    /// Value of `node` for the player: its overrides first, then its groups
+    /// in order and the default group, `None` when none of them sets it
+    pub fn permission(&self, player: &str, node: &str) -> Option<bool> {
+        let mut visited = BTreeSet::new();
+        let player = self.players.get(player);
+        player
+            .and_then(|player| lookup(&player.permissions, node))
+            .or_else(|| {
+                player
+                    .into_iter()
+                    .flat_map(|player| player.groups.iter())
+                    .map(String::as_str)
+                    .chain([DEFAULT_GROUP])
+                    .find_map(|group| self.group_permission(group, node, &mut visited))
+            })
+    }
+
+    /// The node when it is set for the player, its operator level otherwise
+    pub fn has_permission(&self, player: &str, node: &str, level: u8) -> bool {
+        self.permission(player, node)
+            .unwrap_or_else(|| self.op_level(player) >= level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use super::{Permissions, DEFAULT_GROUP, OPS_FILE};
+
+    #[test]
+    fn test_op_levels() {
+        let mut permissions = Permissions::new();
+        let uuid = Uuid::new_v4();
+        assert_eq!(permissions.op_level("Alice"), 0);
+        assert!(permissions.op("Alice", uuid, 2));
+        assert!(!permissions.op("alice", uuid, 2));
+        assert_eq!(permissions.op_level("Alice"), 2);
+        assert!(permissions.op("Alice", uuid, 9));
+        assert_eq!(permissions.op_level("Alice"), 4);
+        assert!(permissions.has_permission("Alice", "minecraft.command.stop", 4));
+        assert!(permissions.deop("Alice"));
+        assert!(!permissions.deop("Alice"));
+        assert!(!permissions.has_permission("Alice", "minecraft.command.kick", 3));
+        assert!(permissions.has_permission("Alice", "minecraft.command.list", 0));
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let mut permissions = Permissions::new();
+        let default = permissions.group_mut(DEFAULT_GROUP);
+        default.permissions.insert("*".into(), false);
+        default
+            .permissions
+            .insert("minecraft.command.*".into(), true);
+        default
+            .permissions
+            .insert("minecraft.command.stop".into(), false);
+        assert_eq!(permissions.permission("Bob", "zirconium.fly"), Some(false));
+        assert_eq!(
+            permissions.permission("Bob", "minecraft.command.kick"),
+            Some(true)
+        );
+        assert_eq!(
+            permissions.permission("Bob", "minecraft.command.stop"),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_groups_and_overrides() {
+        let mut permissions = Permissions::new();
+        permissions
+            .group_mut("helper")
+            .permissions
+            .insert("minecraft.command.kick".into(), true);
+        let moderator = permissions.group_mut("moderator");
+        moderator.inherits.push("helper".into());
+        moderator
+            .permissions
+            .insert("minecraft.command.ban".into(), true);
+        // inheritance cycles are ignored
+        permissions
+            .group_mut("helper")
+            .inherits
+            .push("moderator".into());
+
+        permissions.add_to_group("Alice", "moderator");
+        assert!(permissions.has_permission("Alice", "minecraft.command.ban", 3));
+        assert!(permissions.has_permission("Alice", "minecraft.command.kick", 3));
+        assert!(!permissions.has_permission("Alice", "minecraft.command.op", 3));
+
+        // a player override beats its groups and its operator level
+        permissions.op("Alice", Uuid::new_v4(), 4);
+        permissions.set_permission("Alice", "minecraft.command.ban", Some(false));
+        assert!(!permissions.has_permission("Alice", "minecraft.command.ban", 3));
+        permissions.set_permission("Alice", "minecraft.command.ban", None);
+        assert!(permissions.has_permission("Alice", "minecraft.command.ban", 3));
+
+        permissions.remove_from_group("Alice", "moderator");
+        assert_eq!(
+            permissions.permission("Alice", "minecraft.command.kick"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_persistence() {
+        let directory = std::env::temp_dir().join(format!("zr_permissions_{}", Uuid::new_v4()));
+        let mut permissions = Permissions::load(&directory).unwrap();
+        assert!(permissions.ops().is_empty());
+        let uuid = Uuid::new_v4();
+        permissions.op("Alice", uuid, 3);
+        permissions.add_to_group("Bob", "builder");
+        permissions
+            .group_mut("builder")
+            .permissions
+            .insert("zirconium.build".into(), true);
+        permissions.save().unwrap();
+
+        let ops = fs::read_to_string(directory.join(OPS_FILE)).unwrap();
+        assert!(ops.contains("\"bypassesPlayerLimit\": false"));
+        assert!(ops.contains(&uuid.to_string()));
+        let loaded = Permissions::load(&directory).unwrap();
+        assert_eq!(loaded, permissions);
+        assert_eq!(loaded.permission("Bob", "zirconium.build"), Some(true));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
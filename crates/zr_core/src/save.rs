@@ -0,0 +1,143 @@
+use std::{io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use zr_data_type::vector::Vector3;
+
+use crate::{
+    entity::player::GameMode,
+    item::ItemStack,
+    permission::{read_json, write_json},
+    world::{Difficulty, Weather},
+};
+
+pub const WORLD_DIRECTORY: &str = "world";
+pub const LEVEL_FILE: &str = "level.json";
+/// One `<uuid>.json` file per player
+pub const PLAYER_DATA_DIRECTORY: &str = "playerdata";
+
+/// Seed, time, weather and difficulty of the world, kept between two runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct WorldData {
+    pub seed: i64,
+    pub game_time: i64,
+    pub day_time: i64,
+    pub weather: Weather,
+    /// ticks before the weather clears
+    pub weather_time: i32,
+    pub difficulty: Difficulty,
+}
+
+impl Default for WorldData {
+    /// A new world, with a random seed
+    fn default() -> Self {
+        Self {
+            seed: rand::random(),
+            game_time: 0,
+            day_time: 0,
+            weather: Weather::default(),
+            weather_time: 0,
+            difficulty: Difficulty::default(),
+        }
+    }
+}
+
+/// What a player gets back when joining again
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PlayerData {
+    pub game_mode: GameMode,
+    /// `None` to spawn at the world spawn
+    pub position: Option<Vector3<f64>>,
+    /// yaw and pitch
+    pub rotation: (f32, f32),
+    /// slots of the inventory, hotbar first
+    pub inventory: Vec<ItemStack>,
+}
+
+/// Where the world and the player data are saved
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldSave {
+    directory: PathBuf,
+}
+
+impl WorldSave {
+    /// Save in `directory`, created on the first save
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    fn player_file(&self, uuid: Uuid) -> PathBuf {
+        self.directory
+            .join(PLAYER_DATA_DIRECTORY)
+            .join(format!("{uuid}.json"))
+    }
+
+    /// A new world when it was never saved
+    pub fn load_world(&self) -> io::Result<WorldData> {
+        read_json(&self.directory.join(LEVEL_FILE))
+    }
+
+    pub fn save_world(&self, world: &WorldData) -> io::Result<()> {
+        std::fs::create_dir_all(&self.directory)?;
+        write_json(&self.directory.join(LEVEL_FILE), world)
+    }
+
+    /// `None` for a player who never joined
+    pub fn load_player(&self, uuid: Uuid) -> io::Result<Option<PlayerData>> {
+        read_json(&self.player_file(uuid))
+    }
+
+    pub fn save_player(&self, uuid: Uuid, player: &PlayerData) -> io::Result<()> {
+        std::fs::create_dir_all(self.directory.join(PLAYER_DATA_DIRECTORY))?;
+        write_json(&self.player_file(uuid), player)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+    use zr_data_type::vector::Vector3;
+    use zr_registry::item::Item;
+
+    use super::{PlayerData, WorldData, WorldSave};
+    use crate::{
+        entity::player::GameMode,
+        item::ItemStack,
+        world::{Difficulty, Weather},
+    };
+
+    #[test]
+    fn test_save() {
+        let directory = std::env::temp_dir().join(format!("zr_save_{}", Uuid::new_v4()));
+        let save = WorldSave::new(&directory);
+        assert_eq!(save.load_world().unwrap().game_time, 0);
+        let world = WorldData {
+            seed: -42,
+            game_time: 24042,
+            day_time: 42,
+            weather: Weather::Thunder,
+            weather_time: 600,
+            difficulty: Difficulty::Hard,
+        };
+        save.save_world(&world).unwrap();
+        assert_eq!(save.load_world().unwrap(), world);
+
+        let uuid = Uuid::new_v4();
+        assert_eq!(save.load_player(uuid).unwrap(), None);
+        let player = PlayerData {
+            game_mode: GameMode::Creative,
+            position: Some(Vector3::new(1.5, 64.0, -3.5)),
+            rotation: (90.0, 10.0),
+            inventory: vec![ItemStack::new(Item::Dirt, 12), ItemStack::empty()],
+        };
+        save.save_player(uuid, &player).unwrap();
+        assert_eq!(save.load_player(uuid).unwrap(), Some(player));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
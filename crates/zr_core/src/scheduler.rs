@@ -0,0 +1,560 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::{
+    cancellable::Cancellable,
+    console,
+    supervisor::{Child, Fault},
+    tick::TICK_DURATION,
+    worker::Worker,
+};
+
+/// Number of ticks in `duration`, rounded down
+pub fn ticks(duration: Duration) -> u64 {
+    (duration.as_millis() / TICK_DURATION.as_millis()) as u64
+}
+
+/// Where a task runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// On the tick thread, during the tick
+    Sync,
+    /// On a thread of the async pool, the tick does not wait for it
+    Async,
+}
+
+type Job = Arc<Mutex<dyn FnMut() + Send>>;
+
+/// Cancel a scheduled task, cancelling twice does nothing
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TaskHandle {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Cancellable for TaskHandle {
+    fn is_cancel(&self) -> bool {
+        self.is_cancelled()
+    }
+
+    fn cancel(&mut self, cancel: bool) {
+        self.cancelled.store(cancel, Ordering::Relaxed);
+    }
+}
+
+struct Task {
+    owner: String,
+    mode: Mode,
+    /// tick of the next run
+    next_run: u64,
+    period: Option<u64>,
+    job: Job,
+    handle: TaskHandle,
+}
+
+enum Command {
+    Schedule {
+        owner: String,
+        mode: Mode,
+        delay: u64,
+        period: Option<u64>,
+        job: Job,
+        handle: TaskHandle,
+    },
+    CancelOwner(String),
+}
+
+/// Schedule tasks from any thread, including from a running task.
+/// The tasks are picked up by the [`Scheduler`] at the start of its next tick.
+#[derive(Clone)]
+pub struct SchedulerHandle {
+    next_id: Arc<AtomicU64>,
+    sender: Sender<Command>,
+}
+
+impl SchedulerHandle {
+    fn new_handle(&self) -> TaskHandle {
+        TaskHandle {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn send(
+        &self,
+        owner: &str,
+        mode: Mode,
+        delay: u64,
+        period: Option<u64>,
+        handle: TaskHandle,
+        job: impl FnMut() + Send + 'static,
+    ) -> TaskHandle {
+        let command = Command::Schedule {
+            owner: owner.to_string(),
+            mode,
+            delay,
+            period: period.map(|period| period.max(1)),
+            job: Arc::new(Mutex::new(job)),
+            handle: handle.clone(),
+        };
+        if self.sender.send(command).is_err() {
+            // the scheduler is gone, the task never runs
+            handle.cancel();
+        }
+        handle
+    }
+
+    /// Run `job` in `delay` ticks, then every `period` ticks if any
+    pub fn schedule(
+        &self,
+        owner: &str,
+        mode: Mode,
+        delay: u64,
+        period: Option<u64>,
+        job: impl FnMut() + Send + 'static,
+    ) -> TaskHandle {
+        self.send(owner, mode, delay, period, self.new_handle(), job)
+    }
+
+    /// Run `job` on the next tick
+    pub fn run(&self, owner: &str, job: impl FnMut() + Send + 'static) -> TaskHandle {
+        self.schedule(owner, Mode::Sync, 0, None, job)
+    }
+
+    pub fn run_later(
+        &self,
+        owner: &str,
+        delay: u64,
+        job: impl FnMut() + Send + 'static,
+    ) -> TaskHandle {
+        self.schedule(owner, Mode::Sync, delay, None, job)
+    }
+
+    pub fn run_timer(
+        &self,
+        owner: &str,
+        delay: u64,
+        period: u64,
+        job: impl FnMut() + Send + 'static,
+    ) -> TaskHandle {
+        self.schedule(owner, Mode::Sync, delay, Some(period), job)
+    }
+
+    pub fn run_async(&self, owner: &str, job: impl FnMut() + Send + 'static) -> TaskHandle {
+        self.schedule(owner, Mode::Async, 0, None, job)
+    }
+
+    pub fn run_async_later(
+        &self,
+        owner: &str,
+        delay: u64,
+        job: impl FnMut() + Send + 'static,
+    ) -> TaskHandle {
+        self.schedule(owner, Mode::Async, delay, None, job)
+    }
+
+    pub fn run_async_timer(
+        &self,
+        owner: &str,
+        delay: u64,
+        period: u64,
+        job: impl FnMut() + Send + 'static,
+    ) -> TaskHandle {
+        self.schedule(owner, Mode::Async, delay, Some(period), job)
+    }
+
+    /// Drive a [`Worker`] from the async pool: `init` on the next tick,
+    /// then `handle` every tick while it is alive, and `end` once it is not.
+    /// An error stops the worker, use a [`crate::supervisor::Supervisor`] to restart it.
+    pub fn run_worker<W>(&self, owner: &str, worker: Arc<Mutex<W>>) -> TaskHandle
+    where
+        W: Worker + Send + 'static,
+    {
+        let handle = self.new_handle();
+        let task = handle.clone();
+        let name = owner.to_string();
+        let mut started = false;
+        self.send(owner, Mode::Async, 0, Some(1), handle, move || {
+            // the previous run may still be queued after the end
+            if task.is_cancelled() {
+                return;
+            }
+            let mut step = || -> Result<bool, Fault> {
+                if !started {
+                    started = true;
+                    worker.init()?;
+                }
+                if worker.is_alive()? {
+                    worker.handle()?;
+                    Ok(true)
+                } else {
+                    worker.end()?;
+                    Ok(false)
+                }
+            };
+            match step() {
+                Ok(true) => {}
+                Ok(false) => task.cancel(),
+                Err(fault) => {
+                    console::error(format!("worker of {name} stopped: {fault}"));
+                    let _ = worker.end();
+                    task.cancel();
+                }
+            }
+        })
+    }
+
+    /// Cancel every task of `owner`, e.g. when a plugin is disabled
+    pub fn cancel_owner(&self, owner: &str) {
+        let _ = self.sender.send(Command::CancelOwner(owner.to_string()));
+    }
+}
+
+/// Fixed pool of threads running the async tasks
+struct AsyncPool {
+    sender: Option<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl AsyncPool {
+    fn new(size: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = (0..size.max(1))
+            .map(|_| {
+                let receiver = receiver.clone();
+                std::thread::spawn(move || loop {
+                    let job = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => break,
+                    };
+                    match job {
+                        Ok(job) => {
+                            if let Ok(mut job) = job.lock() {
+                                job();
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+        Self {
+            sender: Some(sender),
+            threads,
+        }
+    }
+
+    fn execute(&self, job: Job) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(job);
+        }
+    }
+
+    /// Wait for the queued jobs and stop the threads
+    fn join(&mut self) {
+        self.sender.take();
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Run the scheduled tasks, [`Scheduler::tick`] is called once per server tick
+pub struct Scheduler {
+    tick: u64,
+    tasks: BTreeMap<u64, Task>,
+    receiver: Receiver<Command>,
+    handle: SchedulerHandle,
+    pool: AsyncPool,
+}
+
+impl Scheduler {
+    pub fn new(async_threads: usize) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            tick: 0,
+            tasks: BTreeMap::new(),
+            receiver,
+            handle: SchedulerHandle {
+                next_id: Arc::new(AtomicU64::new(1)),
+                sender,
+            },
+            pool: AsyncPool::new(async_threads),
+        }
+    }
+
+    pub fn handle(&self) -> SchedulerHandle {
+        self.handle.clone()
+    }
+
+    /// Number of tasks waiting for their next run
+    pub fn pending(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn pending_for(&self, owner: &str) -> usize {
+        self.tasks
+            .values()
+            .filter(|task| task.owner == owner)
+            .count()
+    }
+
+    fn receive(&mut self) {
+        while let Ok(command) = self.receiver.try_recv() {
+            match command {
+                Command::Schedule {
+                    owner,
+                    mode,
+                    delay,
+                    period,
+                    job,
+                    handle,
+                } => {
+                    self.tasks.insert(
+                        handle.id,
+                        Task {
+                            owner,
+                            mode,
+                            next_run: self.tick + delay,
+                            period,
+                            job,
+                            handle,
+                        },
+                    );
+                }
+                Command::CancelOwner(owner) => {
+                    for task in self.tasks.values().filter(|task| task.owner == owner) {
+                        task.handle.cancel();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run the tasks due this tick, in scheduling order
+    pub fn tick(&mut self) {
+        self.receive();
+        self.tasks.retain(|_, task| !task.handle.is_cancelled());
+
+        let due: Vec<u64> = self
+            .tasks
+            .iter()
+            .filter(|(_, task)| task.next_run <= self.tick)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in due {
+            let Some(task) = self.tasks.get_mut(&id) else {
+                continue;
+            };
+            // cancelled by a task run earlier in this tick
+            if task.handle.is_cancelled() {
+                self.tasks.remove(&id);
+                continue;
+            }
+            match task.mode {
+                Mode::Sync => match task.job.lock() {
+                    Ok(mut job) => job(),
+                    Err(err) => {
+                        console::error(format!("task {id} of {} poisoned: {err:?}", task.owner))
+                    }
+                },
+                Mode::Async => self.pool.execute(task.job.clone()),
+            }
+            match task.period {
+                Some(period) => task.next_run = self.tick + period,
+                None => {
+                    self.tasks.remove(&id);
+                }
+            }
+        }
+        self.tick += 1;
+    }
+
+    /// Drop the pending tasks and wait for the running async tasks
+    pub fn shutdown(&mut self) {
+        for task in self.tasks.values() {
+            task.handle.cancel();
+        }
+        self.tasks.clear();
+        self.pool.join();
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            mpsc, Arc, Mutex,
+        },
+        time::Duration,
+    };
+
+    use super::{ticks, Scheduler};
+    use crate::worker::Worker;
+
+    fn counter() -> (Arc<AtomicU32>, impl FnMut() + Send + 'static) {
+        let count = Arc::new(AtomicU32::new(0));
+        let task_count = count.clone();
+        (count, move || {
+            task_count.fetch_add(1, Ordering::Relaxed);
+        })
+    }
+
+    #[test]
+    fn test_delay_and_period() {
+        assert_eq!(ticks(Duration::from_secs(5)), 100);
+
+        let mut scheduler = Scheduler::new(1);
+        let handle = scheduler.handle();
+        let (later, job) = counter();
+        handle.run_later("test", 3, job);
+        let (timer, job) = counter();
+        handle.run_timer("test", 1, 2, job);
+
+        for _ in 0..3 {
+            scheduler.tick();
+        }
+        assert_eq!(later.load(Ordering::Relaxed), 0);
+        scheduler.tick();
+        assert_eq!(later.load(Ordering::Relaxed), 1);
+        // run on ticks 1 and 3
+        assert_eq!(timer.load(Ordering::Relaxed), 2);
+        for _ in 0..10 {
+            scheduler.tick();
+        }
+        assert_eq!(later.load(Ordering::Relaxed), 1);
+        assert_eq!(timer.load(Ordering::Relaxed), 7);
+        assert_eq!(scheduler.pending(), 1);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut scheduler = Scheduler::new(1);
+        let handle = scheduler.handle();
+        let (count, job) = counter();
+        let task = handle.run_timer("test", 0, 1, job);
+        scheduler.tick();
+        task.cancel();
+        scheduler.tick();
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+        assert_eq!(scheduler.pending(), 0);
+
+        // a task scheduled from a task runs on the next tick
+        let (count, job) = counter();
+        let job = Arc::new(Mutex::new(Some(job)));
+        let inner = handle.clone();
+        handle.run("test", move || {
+            if let Some(job) = job.lock().unwrap().take() {
+                inner.run("test", job);
+            }
+        });
+        scheduler.tick();
+        assert_eq!(count.load(Ordering::Relaxed), 0);
+        scheduler.tick();
+        assert_eq!(count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_cancel_owner() {
+        let mut scheduler = Scheduler::new(1);
+        let handle = scheduler.handle();
+        let (plugin, job) = counter();
+        handle.run_timer("plugin", 0, 1, job);
+        handle.run_later("plugin", 10, || {});
+        let (other, job) = counter();
+        handle.run_timer("other", 0, 1, job);
+        scheduler.tick();
+        assert_eq!(scheduler.pending_for("plugin"), 2);
+
+        handle.cancel_owner("plugin");
+        scheduler.tick();
+        assert_eq!(scheduler.pending_for("plugin"), 0);
+        assert_eq!(plugin.load(Ordering::Relaxed), 1);
+        assert_eq!(other.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_async() {
+        let mut scheduler = Scheduler::new(2);
+        let (sender, receiver) = mpsc::channel();
+        scheduler.handle().run_async("test", move || {
+            sender.send(std::thread::current().id()).unwrap();
+        });
+        scheduler.tick();
+        let thread = receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_ne!(thread, std::thread::current().id());
+    }
+
+    struct Countdown {
+        left: u32,
+        ended: bool,
+    }
+
+    impl Worker for Countdown {
+        type Err = ();
+
+        fn is_alive(&self) -> bool {
+            self.left > 0
+        }
+
+        fn init(&mut self) {}
+
+        fn handle(&mut self) -> Result<(), Self::Err> {
+            self.left -= 1;
+            Ok(())
+        }
+
+        fn end(&mut self) {
+            self.ended = true;
+        }
+    }
+
+    #[test]
+    fn test_run_worker() {
+        let mut scheduler = Scheduler::new(1);
+        let worker = Arc::new(Mutex::new(Countdown {
+            left: 3,
+            ended: false,
+        }));
+        let task = scheduler.handle().run_worker("test", worker.clone());
+        for _ in 0..10 {
+            scheduler.tick();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        scheduler.shutdown();
+        assert!(task.is_cancelled());
+        let worker = worker.lock().unwrap();
+        assert_eq!(worker.left, 0);
+        assert!(worker.ended);
+    }
+}
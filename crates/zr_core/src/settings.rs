@@ -0,0 +1,142 @@
+use std::{io, net::IpAddr, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::permission::{read_json, write_json};
+
+pub const SETTINGS_FILE: &str = "server.json";
+/// Sent to the connected players when the server stops, as vanilla
+pub const DEFAULT_SHUTDOWN_MESSAGE: &str = "Server closed";
+/// Players named in the sample of the server list, like vanilla
+pub const DEFAULT_SAMPLE_SIZE: usize = 12;
+
+/// Settings of `server.json`, the missing ones take their default value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ServerSettings {
+    pub shutdown_message: String,
+    /// only the whitelisted players and the operators join, saved with the access lists
+    pub white_list: bool,
+    /// load balancers sending the PROXY protocol header, none when the clients connect directly
+    pub trusted_proxies: Vec<IpAddr>,
+    pub forwarding: ForwardingSettings,
+    /// let in the players transferred from another server, refused like in vanilla by default
+    pub accept_transfers: bool,
+    /// the players sign their chat messages, off while the server does not authenticate them
+    pub enforce_secure_chat: bool,
+    /// text above the player list, `§` codes format it
+    pub tab_list_header: String,
+    /// text below the player list, `§` codes format it
+    pub tab_list_footer: String,
+    /// random players named in the server list, 0 to hide them
+    pub status_sample_size: usize,
+}
+
+/// How the proxy in front of the server gives the players it connects
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForwardingMode {
+    /// the players connect to the server directly
+    #[default]
+    None,
+    /// BungeeCord legacy forwarding
+    Bungee,
+    /// Velocity modern forwarding, needs the secret of the proxy
+    Velocity,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ForwardingSettings {
+    pub mode: ForwardingMode,
+    /// shared with Velocity, which signs the players it forwards with it
+    pub secret: String,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            shutdown_message: DEFAULT_SHUTDOWN_MESSAGE.to_string(),
+            white_list: false,
+            trusted_proxies: Vec::new(),
+            forwarding: ForwardingSettings::default(),
+            accept_transfers: false,
+            enforce_secure_chat: false,
+            tab_list_header: String::new(),
+            tab_list_footer: String::new(),
+            status_sample_size: DEFAULT_SAMPLE_SIZE,
+        }
+    }
+}
+
+impl ServerSettings {
+    /// Read the settings from `directory` and write them back with the missing ones,
+    /// as vanilla does with `server.properties`
+    pub fn load(directory: impl AsRef<Path>) -> io::Result<Self> {
+        let directory = directory.as_ref();
+        let settings: Self = read_json(&directory.join(SETTINGS_FILE))?;
+        std::fs::create_dir_all(directory)?;
+        write_json(&directory.join(SETTINGS_FILE), &settings)?;
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    };
+
+    use uuid::Uuid;
+
+    use super::{
+        ForwardingMode, ServerSettings, DEFAULT_SAMPLE_SIZE, DEFAULT_SHUTDOWN_MESSAGE,
+        SETTINGS_FILE,
+    };
+
+    #[test]
+    fn test_load() {
+        let directory = std::env::temp_dir().join(format!("zr_settings_{}", Uuid::new_v4()));
+        let settings = ServerSettings::load(&directory).unwrap();
+        assert_eq!(settings.shutdown_message, DEFAULT_SHUTDOWN_MESSAGE);
+        assert_eq!(settings.forwarding.mode, ForwardingMode::None);
+        assert!(!settings.accept_transfers);
+        assert!(!settings.enforce_secure_chat);
+        assert!(settings.tab_list_header.is_empty());
+        assert_eq!(settings.status_sample_size, DEFAULT_SAMPLE_SIZE);
+        assert!(directory.join(SETTINGS_FILE).exists());
+
+        fs::write(
+            directory.join(SETTINGS_FILE),
+            r#"{
+                "shutdown-message": "Back soon",
+                "trusted-proxies": ["10.0.0.2", "::1"],
+                "forwarding": {"mode": "velocity", "secret": "s3cr3t"},
+                "accept-transfers": true,
+                "enforce-secure-chat": true,
+                "tab-list-header": "§6Welcome",
+                "tab-list-footer": "Have fun",
+                "status-sample-size": 0
+            }"#,
+        )
+        .unwrap();
+        let settings = ServerSettings::load(&directory).unwrap();
+        assert_eq!(settings.shutdown_message, "Back soon");
+        assert_eq!(
+            settings.trusted_proxies,
+            [
+                IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)),
+                IpAddr::V6(Ipv6Addr::LOCALHOST)
+            ]
+        );
+        assert_eq!(settings.forwarding.mode, ForwardingMode::Velocity);
+        assert_eq!(settings.forwarding.secret, "s3cr3t");
+        assert!(settings.accept_transfers);
+        assert!(settings.enforce_secure_chat);
+        assert_eq!(settings.tab_list_header, "§6Welcome");
+        assert_eq!(settings.tab_list_footer, "Have fun");
+        assert_eq!(settings.status_sample_size, 0);
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,490 @@
+use std::{
+    fmt::{Debug, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::{
+    console,
+    worker::{work, Worker},
+};
+
+/// Pause between two rounds of `handle`
+const ROUND_INTERVAL: Duration = Duration::from_millis(1);
+
+/// What the supervisor does when `handle` returns an error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Log the error and keep the worker running
+    Ignore,
+    /// End and init the worker again, escalate after `max_restarts`
+    Restart { max_restarts: u32 },
+    /// The error is critical, shut the whole server down
+    Escalate,
+}
+
+/// Why a worker stopped working
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// A stage panicked earlier, the worker state can't be trusted anymore
+    Poisoned(&'static str),
+    /// Error returned by `handle`
+    Error(String),
+    /// A watched thread panicked, with the panic message
+    Panicked(String),
+    /// A watched thread returned before the shutdown
+    Exited,
+}
+
+impl Fault {
+    pub fn error(err: impl Debug) -> Self {
+        Self::Error(format!("{err:?}"))
+    }
+
+    /// The fault of a thread that ended, from the result of its join
+    fn ended(result: std::thread::Result<()>) -> Self {
+        match result {
+            Ok(()) => Self::Exited,
+            Err(panic) => Self::Panicked(
+                panic
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+impl Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Poisoned(stage) => write!(f, "crashed during {stage}"),
+            Self::Error(err) => write!(f, "{err}"),
+            Self::Panicked(message) => write!(f, "panicked: {message}"),
+            Self::Exited => write!(f, "stopped on its own"),
+        }
+    }
+}
+
+/// Critical error, the reason of a shutdown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Critical {
+    pub worker: String,
+    pub fault: Fault,
+}
+
+impl std::error::Error for Critical {}
+
+impl Display for Critical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "critical error in {}: {}", self.worker, self.fault)
+    }
+}
+
+/// Type erased worker behind a mutex
+pub(crate) trait Child: Send {
+    fn init(&self) -> Result<(), Fault>;
+    fn is_alive(&self) -> Result<bool, Fault>;
+    fn handle(&self) -> Result<(), Fault>;
+    fn end(&self) -> Result<(), Fault>;
+}
+
+impl<W> Child for Arc<Mutex<W>>
+where
+    W: Worker + Send,
+{
+    fn init(&self) -> Result<(), Fault> {
+        work!(init self)
+    }
+
+    fn is_alive(&self) -> Result<bool, Fault> {
+        work!(is_alive self)
+    }
+
+    fn handle(&self) -> Result<(), Fault> {
+        work!(handle self)?.map_err(Fault::error)
+    }
+
+    fn end(&self) -> Result<(), Fault> {
+        work!(end self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pending,
+    Running,
+    Ended,
+}
+
+struct Supervised {
+    name: String,
+    policy: Policy,
+    restarts: u32,
+    status: Status,
+    child: Box<dyn Child>,
+}
+
+impl Supervised {
+    fn critical(&self, fault: Fault) -> Critical {
+        Critical {
+            worker: self.name.clone(),
+            fault,
+        }
+    }
+
+    /// Run one stage of the worker, `Err` when the error must be escalated
+    fn step(&mut self) -> Result<(), Critical> {
+        match self.status {
+            Status::Pending => {
+                self.child.init().map_err(|fault| self.critical(fault))?;
+                self.status = Status::Running;
+            }
+            Status::Running => {
+                if !self
+                    .child
+                    .is_alive()
+                    .map_err(|fault| self.critical(fault))?
+                {
+                    self.status = Status::Ended;
+                    return self.child.end().map_err(|fault| self.critical(fault));
+                }
+                if let Err(fault) = self.child.handle() {
+                    self.recover(fault)?;
+                }
+            }
+            Status::Ended => {}
+        }
+        Ok(())
+    }
+
+    fn recover(&mut self, fault: Fault) -> Result<(), Critical> {
+        if let Fault::Poisoned(_) = fault {
+            return Err(self.critical(fault));
+        }
+        match self.policy {
+            Policy::Ignore => {
+                console::error(format!("{}: {fault}", self.name));
+                Ok(())
+            }
+            Policy::Restart { max_restarts } if self.restarts < max_restarts => {
+                self.restarts += 1;
+                console::error(format!(
+                    "{}: {fault}, restarting ({}/{max_restarts})",
+                    self.name, self.restarts
+                ));
+                self.child.end().map_err(|fault| self.critical(fault))?;
+                self.status = Status::Pending;
+                Ok(())
+            }
+            Policy::Restart { .. } | Policy::Escalate => Err(self.critical(fault)),
+        }
+    }
+}
+
+/// Thread running on its own, its end before the shutdown is critical
+struct Watched {
+    name: String,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watched {
+    fn step(&mut self) -> Result<(), Critical> {
+        match self.thread.take_if(|thread| thread.is_finished()) {
+            Some(thread) => Err(Critical {
+                worker: self.name.clone(),
+                fault: Fault::ended(thread.join()),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Ask a running [`Supervisor`] to shut down, from any thread
+#[derive(Debug, Clone)]
+pub struct SupervisorHandle {
+    shutdown: Arc<AtomicBool>,
+}
+
+impl SupervisorHandle {
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.load(Ordering::Relaxed)
+    }
+}
+
+/// Own the workers of the server, apply their [`Policy`] on errors
+/// and stop everything in order when one of them fails critically.
+pub struct Supervisor {
+    workers: Vec<Supervised>,
+    threads: Vec<Watched>,
+    /// stop the parts of the server which are not workers
+    hooks: Vec<(String, Box<dyn FnOnce() + Send>)>,
+    handle: SupervisorHandle,
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            workers: Vec::new(),
+            threads: Vec::new(),
+            hooks: Vec::new(),
+            handle: SupervisorHandle {
+                shutdown: Arc::new(AtomicBool::new(false)),
+            },
+        }
+    }
+
+    pub fn handle(&self) -> SupervisorHandle {
+        self.handle.clone()
+    }
+
+    pub fn supervise<W>(&mut self, name: &str, policy: Policy, worker: Arc<Mutex<W>>) -> &mut Self
+    where
+        W: Worker + Send + 'static,
+    {
+        self.workers.push(Supervised {
+            name: name.to_string(),
+            policy,
+            restarts: 0,
+            status: Status::Pending,
+            child: Box::new(worker),
+        });
+        self
+    }
+
+    /// Shut down when `thread` panics or returns, it is joined on shutdown after the hooks
+    pub fn watch(&mut self, name: &str, thread: JoinHandle<()>) -> &mut Self {
+        self.threads.push(Watched {
+            name: name.to_string(),
+            thread: Some(thread),
+        });
+        self
+    }
+
+    /// Called on shutdown, after the workers ended, in reverse registration order
+    pub fn on_shutdown(&mut self, name: &str, hook: impl FnOnce() + Send + 'static) -> &mut Self {
+        self.hooks.push((name.to_string(), Box::new(hook)));
+        self
+    }
+
+    /// Run one stage of every worker and check the watched threads
+    pub fn step(&mut self) -> Result<(), Critical> {
+        for worker in self.workers.iter_mut() {
+            worker.step()?;
+        }
+        for thread in self.threads.iter_mut() {
+            match thread.step() {
+                // it saw the shutdown before the supervisor
+                Err(critical)
+                    if critical.fault == Fault::Exited && self.handle.is_shutting_down() => {}
+                result => result?,
+            }
+        }
+        Ok(())
+    }
+
+    /// End the running workers, run the shutdown hooks then join the watched threads, in reverse order
+    pub fn shutdown(&mut self) {
+        // the watched threads stop on their own once they see it
+        self.handle.shutdown();
+        for worker in self.workers.iter_mut().rev() {
+            if worker.status == Status::Running {
+                worker.status = Status::Ended;
+                if let Err(fault) = worker.child.end() {
+                    console::error(format!("{}: {fault}", worker.name));
+                }
+            }
+        }
+        for (name, hook) in self.hooks.drain(..).rev() {
+            console::log(format!("Stopping {name}"));
+            hook();
+        }
+        for watched in self.threads.drain(..).rev() {
+            let Some(thread) = watched.thread else {
+                continue;
+            };
+            if let Fault::Panicked(message) = Fault::ended(thread.join()) {
+                console::error(format!("{} thread crashed: {message}", watched.name));
+            }
+        }
+    }
+
+    /// Run the workers until a shutdown is asked or a critical error occurs
+    pub fn run(mut self) -> JoinHandle<Result<(), Critical>> {
+        std::thread::spawn(move || {
+            let mut result = Ok(());
+            while !self.handle.is_shutting_down() {
+                if let Err(critical) = self.step() {
+                    console::error(format!("{critical}, shutting down"));
+                    result = Err(critical);
+                    break;
+                }
+                std::thread::sleep(ROUND_INTERVAL);
+            }
+            self.shutdown();
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    };
+
+    use super::{Critical, Fault, Policy, Supervisor};
+    use crate::worker::Worker;
+
+    #[derive(Default)]
+    struct Flaky {
+        inits: u32,
+        ends: u32,
+        fail: bool,
+    }
+
+    impl Worker for Flaky {
+        type Err = &'static str;
+
+        fn is_alive(&self) -> bool {
+            true
+        }
+
+        fn init(&mut self) {
+            self.inits += 1;
+        }
+
+        fn handle(&mut self) -> Result<(), Self::Err> {
+            if self.fail {
+                Err("failed")
+            } else {
+                Ok(())
+            }
+        }
+
+        fn end(&mut self) {
+            self.ends += 1;
+        }
+    }
+
+    fn flaky(fail: bool) -> Arc<Mutex<Flaky>> {
+        Arc::new(Mutex::new(Flaky {
+            fail,
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn test_restart_then_escalate() {
+        let worker = flaky(true);
+        let mut supervisor = Supervisor::new();
+        supervisor.supervise("flaky", Policy::Restart { max_restarts: 2 }, worker.clone());
+        // init, handle, (init, handle) * 2
+        for _ in 0..5 {
+            supervisor.step().unwrap();
+        }
+        assert_eq!(
+            supervisor.step(),
+            Err(Critical {
+                worker: "flaky".to_string(),
+                fault: Fault::Error("\"failed\"".to_string()),
+            })
+        );
+        let worker = worker.lock().unwrap();
+        assert_eq!(worker.inits, 3);
+        assert_eq!(worker.ends, 2);
+    }
+
+    #[test]
+    fn test_ignore() {
+        let worker = flaky(true);
+        let mut supervisor = Supervisor::new();
+        supervisor.supervise("flaky", Policy::Ignore, worker.clone());
+        for _ in 0..5 {
+            supervisor.step().unwrap();
+        }
+        assert_eq!(worker.lock().unwrap().inits, 1);
+    }
+
+    #[test]
+    fn test_poisoned() {
+        let worker = flaky(false);
+        let mut supervisor = Supervisor::new();
+        supervisor.supervise("flaky", Policy::Ignore, worker.clone());
+        supervisor.step().unwrap();
+        let poison = worker.clone();
+        let _ = std::thread::spawn(move || {
+            let _worker = poison.lock().unwrap();
+            panic!("worker crash");
+        })
+        .join();
+        let critical = supervisor.step().unwrap_err();
+        assert_eq!(critical.fault, Fault::Poisoned("is_alive"));
+    }
+
+    #[test]
+    fn test_watched_panic() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let hook_stopped = stopped.clone();
+        let mut supervisor = Supervisor::new();
+        let handle = supervisor.handle();
+        let running = handle.clone();
+        let healthy = std::thread::spawn(move || {
+            while !running.is_shutting_down() {
+                std::thread::yield_now();
+            }
+        });
+        supervisor
+            .watch("tick loop", healthy)
+            .watch("protocol", std::thread::spawn(|| panic!("protocol crash")))
+            .on_shutdown("network", move || {
+                hook_stopped.store(true, Ordering::Relaxed)
+            });
+
+        let critical = supervisor.run().join().unwrap().unwrap_err();
+        assert_eq!(critical.worker, "protocol");
+        assert_eq!(
+            critical.fault,
+            Fault::Panicked("protocol crash".to_string())
+        );
+        assert!(handle.is_shutting_down());
+        assert!(stopped.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_coordinated_shutdown() {
+        let healthy = flaky(false);
+        let stopped = Arc::new(AtomicBool::new(false));
+        let hook_stopped = stopped.clone();
+        let mut supervisor = Supervisor::new();
+        supervisor
+            .supervise("healthy", Policy::Escalate, healthy.clone())
+            .supervise("failing", Policy::Escalate, flaky(true))
+            .on_shutdown("network", move || {
+                hook_stopped.store(true, Ordering::Relaxed)
+            });
+        let handle = supervisor.handle();
+
+        let critical = supervisor.run().join().unwrap().unwrap_err();
+        assert_eq!(critical.worker, "failing");
+        assert!(handle.is_shutting_down());
+        assert!(stopped.load(Ordering::Relaxed));
+        assert_eq!(healthy.lock().unwrap().ends, 1);
+    }
+}
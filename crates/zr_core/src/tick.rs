@@ -0,0 +1,332 @@
+use std::{
+    collections::VecDeque,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use crate::console;
+
+pub const TICKS_PER_SECOND: u32 = 20;
+pub const TICK_DURATION: Duration = Duration::from_millis(1000 / TICKS_PER_SECOND as u64);
+/// Late ticks run back to back up to this delay, further ticks are skipped
+pub const MAX_CATCH_UP: Duration = Duration::from_secs(2);
+/// Number of ticks kept for the MSPT statistics
+const MSPT_SAMPLES: usize = 100;
+/// Longest window of the TPS statistics
+const TPS_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// State advanced by the tick loop
+pub trait Tickable {
+    fn tick(&mut self, tick: u64);
+}
+
+/// What the loop does once a tick is over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    /// Ahead of time, wait for the next tick
+    Sleep(Duration),
+    /// Late, run the next tick now
+    CatchUp,
+    /// Too late, these ticks are dropped and the schedule restarts from now
+    Skip(u64),
+}
+
+/// Fixed rate schedule of the ticks
+#[derive(Debug, Clone, Copy)]
+pub struct TickClock {
+    next: Instant,
+}
+
+impl TickClock {
+    pub fn new(start: Instant) -> Self {
+        Self { next: start }
+    }
+
+    /// Schedule the next tick after the one ending at `now`
+    pub fn pace(&mut self, now: Instant) -> Pace {
+        self.next += TICK_DURATION;
+        if now < self.next {
+            return Pace::Sleep(self.next - now);
+        }
+        let late = now - self.next;
+        if late <= MAX_CATCH_UP {
+            Pace::CatchUp
+        } else {
+            let skipped = (late.as_nanos() / TICK_DURATION.as_nanos()) as u64;
+            self.next = now;
+            Pace::Skip(skipped)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TickStats {
+    start: Instant,
+    tick: u64,
+    skipped: u64,
+    /// duration of the last ticks
+    mspt: VecDeque<Duration>,
+    /// end of the ticks of the last `TPS_WINDOW`
+    ends: VecDeque<Instant>,
+}
+
+impl TickStats {
+    pub fn new(start: Instant) -> Self {
+        Self {
+            start,
+            tick: 0,
+            skipped: 0,
+            mspt: VecDeque::with_capacity(MSPT_SAMPLES),
+            ends: VecDeque::new(),
+        }
+    }
+
+    pub fn record(&mut self, duration: Duration, end: Instant) {
+        self.tick += 1;
+        if self.mspt.len() == MSPT_SAMPLES {
+            self.mspt.pop_front();
+        }
+        self.mspt.push_back(duration);
+        self.ends.push_back(end);
+        while self
+            .ends
+            .front()
+            .is_some_and(|first| end.duration_since(*first) > TPS_WINDOW)
+        {
+            self.ends.pop_front();
+        }
+    }
+
+    pub fn record_skipped(&mut self, ticks: u64) {
+        self.skipped += ticks;
+    }
+
+    /// Ticks per second over the last `window`, capped to `TICKS_PER_SECOND`
+    pub fn tps(&self, window: Duration, now: Instant) -> f64 {
+        let window = window.min(now.duration_since(self.start));
+        if window.is_zero() {
+            return TICKS_PER_SECOND as f64;
+        }
+        let ticks = self
+            .ends
+            .iter()
+            .rev()
+            .take_while(|end| now.duration_since(**end) < window)
+            .count();
+        (ticks as f64 / window.as_secs_f64()).min(TICKS_PER_SECOND as f64)
+    }
+
+    pub fn report(&self, now: Instant) -> TickReport {
+        let millis = |duration: &Duration| duration.as_secs_f64() * 1000.0;
+        let samples = self.mspt.len().max(1) as f64;
+        TickReport {
+            tick: self.tick,
+            skipped: self.skipped,
+            tps: [60, 300, 900].map(|secs| self.tps(Duration::from_secs(secs), now)),
+            mspt_avg: self.mspt.iter().map(millis).sum::<f64>() / samples,
+            mspt_min: self.mspt.iter().map(millis).reduce(f64::min).unwrap_or(0.0),
+            mspt_max: self.mspt.iter().map(millis).reduce(f64::max).unwrap_or(0.0),
+        }
+    }
+}
+
+/// Snapshot of the tick statistics, as shown by `/tps`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TickReport {
+    pub tick: u64,
+    /// ticks dropped because the server was too late
+    pub skipped: u64,
+    /// over the last 1, 5 and 15 minutes
+    pub tps: [f64; 3],
+    pub mspt_avg: f64,
+    pub mspt_min: f64,
+    pub mspt_max: f64,
+}
+
+impl Display for TickReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [tps_1m, tps_5m, tps_15m] = self.tps;
+        writeln!(
+            f,
+            "TPS from last 1m, 5m, 15m: {tps_1m:.2}, {tps_5m:.2}, {tps_15m:.2}"
+        )?;
+        write!(
+            f,
+            "MSPT avg/min/max: {:.2}/{:.2}/{:.2}, skipped ticks: {}",
+            self.mspt_avg, self.mspt_min, self.mspt_max, self.skipped
+        )
+    }
+}
+
+/// Run a [`Tickable`] at `TICKS_PER_SECOND` on its own thread
+pub struct TickLoop {
+    running: Arc<AtomicBool>,
+    stats: Arc<Mutex<TickStats>>,
+}
+
+impl Default for TickLoop {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TickLoop {
+    pub fn new() -> Self {
+        Self {
+            running: Arc::new(AtomicBool::new(true)),
+            stats: Arc::new(Mutex::new(TickStats::new(Instant::now()))),
+        }
+    }
+
+    pub fn stats(&self) -> Arc<Mutex<TickStats>> {
+        self.stats.clone()
+    }
+
+    pub fn report(&self) -> Option<TickReport> {
+        self.stats
+            .lock()
+            .ok()
+            .map(|stats| stats.report(Instant::now()))
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// The loop ends after the current tick
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+
+    pub fn run<T>(&self, target: Arc<Mutex<T>>) -> JoinHandle<()>
+    where
+        T: Tickable + Send + 'static,
+    {
+        let running = self.running.clone();
+        let stats = self.stats.clone();
+        std::thread::spawn(move || {
+            let mut clock = TickClock::new(Instant::now());
+            let mut tick = 0;
+            while running.load(Ordering::Relaxed) {
+                let start = Instant::now();
+                match target.lock() {
+                    Ok(mut target) => target.tick(tick),
+                    Err(err) => {
+                        console::error(format!("{err:?}"));
+                        break;
+                    }
+                }
+                tick += 1;
+                let end = Instant::now();
+                let Ok(mut stats) = stats.lock() else {
+                    break;
+                };
+                stats.record(end - start, end);
+                match clock.pace(end) {
+                    Pace::Sleep(duration) => {
+                        drop(stats);
+                        std::thread::sleep(duration);
+                    }
+                    Pace::CatchUp => {}
+                    Pace::Skip(ticks) => {
+                        console::error(format!("Can't keep up! Skipping {ticks} ticks"));
+                        stats.record_skipped(ticks);
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        time::{Duration, Instant},
+    };
+
+    use super::{Pace, TickClock, TickLoop, TickStats, Tickable, TICK_DURATION};
+
+    #[test]
+    fn test_pace() {
+        let start = Instant::now();
+        let mut clock = TickClock::new(start);
+        assert_eq!(
+            clock.pace(start + Duration::from_millis(10)),
+            Pace::Sleep(Duration::from_millis(40))
+        );
+        // a 120ms tick is caught up by the next ones
+        let end = start + TICK_DURATION + Duration::from_millis(120);
+        assert_eq!(clock.pace(end), Pace::CatchUp);
+        assert_eq!(clock.pace(end + Duration::from_millis(5)), Pace::CatchUp);
+        assert_eq!(
+            clock.pace(end + Duration::from_millis(10)),
+            Pace::Sleep(Duration::from_millis(20))
+        );
+        // a 5s tick is not
+        let end = start + Duration::from_secs(5);
+        assert_eq!(clock.pace(end), Pace::Skip(95));
+        assert_eq!(clock.pace(end), Pace::Sleep(TICK_DURATION));
+    }
+
+    #[test]
+    fn test_stats() {
+        let start = Instant::now();
+        let mut stats = TickStats::new(start);
+        // 10s at full speed then 10s at half speed
+        for i in 1..=200 {
+            stats.record(Duration::from_millis(10), start + TICK_DURATION * i);
+        }
+        let now = start + Duration::from_secs(10);
+        for i in 1..=100 {
+            stats.record(Duration::from_millis(100), now + TICK_DURATION * 2 * i);
+        }
+        let now = start + Duration::from_secs(20);
+        assert_eq!(stats.tps(Duration::from_secs(5), now), 10.0);
+        assert_eq!(stats.tps(Duration::from_secs(60), now), 15.0);
+
+        let report = stats.report(now);
+        assert_eq!(report.tick, 300);
+        assert_eq!(report.mspt_avg, 100.0);
+        assert_eq!(report.mspt_max, 100.0);
+    }
+
+    /// Stops the loop once it counted `stop_at` ticks
+    struct Counter {
+        ticks: u64,
+        stop_at: u64,
+        running: Arc<AtomicBool>,
+    }
+
+    impl Tickable for Counter {
+        fn tick(&mut self, tick: u64) {
+            assert_eq!(self.ticks, tick);
+            self.ticks += 1;
+            if self.ticks == self.stop_at {
+                self.running.store(false, Ordering::Relaxed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tick_loop() {
+        let tick_loop = TickLoop::new();
+        let counter = Arc::new(Mutex::new(Counter {
+            ticks: 0,
+            stop_at: 3,
+            running: tick_loop.running.clone(),
+        }));
+        tick_loop.run(counter.clone()).join().unwrap();
+        assert_eq!(counter.lock().unwrap().ticks, 3);
+        assert!(!tick_loop.is_running());
+        assert_eq!(tick_loop.report().unwrap().tick, 3);
+    }
+}
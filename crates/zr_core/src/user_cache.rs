@@ -0,0 +1,100 @@
+use std::{io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::permission::{read_json, write_json};
+
+pub const USER_CACHE_FILE: &str = "usercache.json";
+
+/// Entry of `usercache.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedUser {
+    pub name: String,
+    pub uuid: Uuid,
+}
+
+/// Players who joined the server, to find the uuid of an offline player by name
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UserCache {
+    /// where `usercache.json` is saved, `None` to keep it in memory
+    directory: Option<PathBuf>,
+    users: Vec<CachedUser>,
+}
+
+impl UserCache {
+    /// Cache kept in memory only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the cache from `directory`, empty when the file is missing
+    pub fn load(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        Ok(Self {
+            users: read_json(&directory.join(USER_CACHE_FILE))?,
+            directory: Some(directory),
+        })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(directory) = &self.directory else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(directory)?;
+        write_json(&directory.join(USER_CACHE_FILE), &self.users)
+    }
+
+    pub fn uuid(&self, name: &str) -> Option<Uuid> {
+        self.users
+            .iter()
+            .find(|user| user.name.eq_ignore_ascii_case(name))
+            .map(|user| user.uuid)
+    }
+
+    /// Remember the player, the old name of a renamed player is forgotten.
+    /// `false` if the player was already known under this name
+    pub fn add(&mut self, name: &str, uuid: Uuid) -> bool {
+        if self
+            .users
+            .iter()
+            .any(|user| user.uuid == uuid && user.name == name)
+        {
+            return false;
+        }
+        self.users
+            .retain(|user| user.uuid != uuid && !user.name.eq_ignore_ascii_case(name));
+        self.users.push(CachedUser {
+            name: name.to_string(),
+            uuid,
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use super::UserCache;
+
+    #[test]
+    fn test_user_cache() {
+        let directory = std::env::temp_dir().join(format!("zr_user_cache_{}", Uuid::new_v4()));
+        let mut cache = UserCache::load(&directory).unwrap();
+        let uuid = Uuid::new_v4();
+        assert!(cache.add("Alice", uuid));
+        assert!(!cache.add("Alice", uuid));
+        assert_eq!(cache.uuid("alice"), Some(uuid));
+        // renamed
+        assert!(cache.add("Alicia", uuid));
+        assert_eq!(cache.uuid("Alice"), None);
+        cache.save().unwrap();
+
+        let cache = UserCache::load(&directory).unwrap();
+        assert_eq!(cache.uuid("Alicia"), Some(uuid));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
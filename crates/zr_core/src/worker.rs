@@ -1,109 +1,51 @@
-use std::{
-    fmt::Debug,
-    sync::{Arc, Mutex},
-    thread::JoinHandle,
-};
-
-// macro_rules! try_work {
-//     (init $worker: expr) => {
-//         match $worker.try_lock() {
-//             Ok(mut worker) => worker.init(),
-//             Err(err) if err == Err(err) => {
-//                 eprintln!("{err:?}");
-//                 panic!("worker crash during init, follow error above")
-//             }
-//         }
-//     };
-//     (handle $worker: expr) => {
-//         match $worker.lock() {
-//             Ok(mut worker) => worker.handle(),
-//             Err(err) => {
-//                 eprintln!("{err:?}");
-//                 panic!("worker crash during handle, follow error above")
-//             }
-//         }
-//     };
-//     (end $worker: expr) => {
-//         match $worker.lock() {
-//             Ok(mut worker) => worker.end(),
-//             Err(err) => {
-//                 eprintln!("{err:?}");
-//                 panic!("worker crash during end, follow error above")
-//             }
-//         }
-//     };
-//     (is_alive $worker: expr) => {
-//         match $worker.lock() {
-//             Ok(worker) => worker.is_alive(),
-//             Err(err) => {
-//                 eprintln!("{err:?}");
-//                 false
-//             }
-//         }
-//     };
-// }
+use std::fmt::Debug;
 
+/// Run a stage of a worker behind a mutex, a poisoned mutex (a previous
+/// stage panicked) is reported as a [`crate::supervisor::Fault`]
 macro_rules! work {
     (init $worker: expr) => {
         match $worker.lock() {
-            Ok(mut worker) => worker.init(),
-            Err(err) => {
-                eprintln!("{err:?}");
-                panic!("worker crash during init, follow error above")
+            Ok(mut worker) => {
+                worker.init();
+                Ok(())
             }
+            Err(_) => Err($crate::supervisor::Fault::Poisoned("init")),
         }
     };
     (handle $worker: expr) => {
         match $worker.lock() {
-            Ok(mut worker) => worker.handle(),
-            Err(err) => {
-                eprintln!("{err:?}");
-                panic!("worker crash during handle, follow error above")
-            }
+            Ok(mut worker) => Ok(worker.handle()),
+            Err(_) => Err($crate::supervisor::Fault::Poisoned("handle")),
         }
     };
     (end $worker: expr) => {
         match $worker.lock() {
-            Ok(mut worker) => worker.end(),
-            Err(err) => {
-                eprintln!("{err:?}");
-                panic!("worker crash during end, follow error above")
+            Ok(mut worker) => {
+                worker.end();
+                Ok(())
             }
+            Err(_) => Err($crate::supervisor::Fault::Poisoned("end")),
         }
     };
     (is_alive $worker: expr) => {
         match $worker.lock() {
-            Ok(worker) => worker.is_alive(),
-            Err(err) => {
-                eprintln!("{err:?}");
-                false
-            }
+            Ok(worker) => Ok(worker.is_alive()),
+            Err(_) => Err($crate::supervisor::Fault::Poisoned("is_alive")),
         }
     };
 }
 
+pub(crate) use work;
+
+/// Long running job, owned by a [`crate::supervisor::Supervisor`] or driven by
+/// [`crate::scheduler::SchedulerHandle::run_worker`]
 pub trait Worker {
-    // TODO : use critical error (error when worker crash)
-    //        if this error occured, all app stop too
-    type Err;
+    /// Returned by `handle`, what happens next depends on the supervision policy
+    type Err: Debug;
 
     fn is_alive(&self) -> bool;
 
     fn init(&mut self);
-    fn handle(&mut self);
+    fn handle(&mut self) -> Result<(), Self::Err>;
     fn end(&mut self);
-
-    fn run(worker: Arc<Mutex<Self>>) -> JoinHandle<()>
-    where
-        Self::Err: Debug + Send,
-        Self: Sync + Send + 'static,
-    {
-        std::thread::spawn(move || {
-            work!(init worker);
-            while work!(is_alive worker) {
-                work!(handle worker);
-            }
-            work!(end worker);
-        })
-    }
 }
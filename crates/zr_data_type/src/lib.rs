@@ -1,2 +1,4 @@
 pub mod string;
-pub mod error;
\ No newline at end of file
+pub mod error;
+pub mod position;
+pub mod vector;
\ No newline at end of file
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use zr_binary::{binary::Binary, error::BinaryError};
+
+/// Block position, encoded on the wire as a packed `i64`
+/// (x: 26 bits, z: 26 bits, y: 12 bits).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Position {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn packed(&self) -> i64 {
+        ((self.x as i64 & 0x3FFFFFF) << 38) | ((self.z as i64 & 0x3FFFFFF) << 12) | (self.y as i64 & 0xFFF)
+    }
+
+    pub fn unpacked(value: i64) -> Self {
+        Self {
+            x: (value >> 38) as i32,
+            y: (value << 52 >> 52) as i32,
+            z: (value << 26 >> 38) as i32,
+        }
+    }
+}
+
+impl Binary for Position {
+    fn binary_len(&self) -> usize {
+        8
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        self.packed().to_binary()
+    }
+
+    fn from_binary(bin: Vec<u8>) -> zr_binary::error::Result<Self> where Self: Sized {
+        if bin.len() < 8 {
+            return Err(BinaryError::LengthError);
+        }
+        Ok(Self::unpacked(i64::from_binary(bin)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::Position;
+
+    #[rstest]
+    #[case(0, 0, 0)]
+    #[case(18357644, 831, -20882616)]
+    #[case(-1, -64, -1)]
+    #[case(33554431, 2047, -33554432)]
+    pub fn test_position(#[case] x: i32, #[case] y: i32, #[case] z: i32) {
+        let position = Position::new(x, y, z);
+        assert_eq!(Position::unpacked(position.packed()), position);
+    }
+
+    #[test]
+    pub fn test_position_wire_format() {
+        // example from the protocol documentation
+        let position = Position::new(18357644, 831, -20882616);
+        assert_eq!(position.packed(), 0x4607_632C_15B4_833F);
+    }
+}
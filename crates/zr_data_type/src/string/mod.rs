@@ -1,8 +1,12 @@
+use std::str::FromStr;
+
 use zr_binary::{binary::Binary, error::BinaryError, varint::VarInt};
 
 use crate::error::MalformedError;
 
-#[derive(Debug, Clone)]
+pub const DEFAULT_NAMESPACE: &str = "minecraft";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Identifier {
     namespace: String,
     value: String
@@ -37,6 +41,30 @@ impl  Identifier {
             Err(MalformedError)
         }
     }
+
+    pub fn minecraft(value: &str) -> Result<Self, MalformedError> {
+        Self::new(DEFAULT_NAMESPACE, value)
+    }
+
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Parse `namespace:value`, or `value` alone which falls back on the `minecraft` namespace.
+impl FromStr for Identifier {
+    type Err = MalformedError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((namespace, value)) => Identifier::new(namespace, value),
+            None => Identifier::minecraft(s),
+        }
+    }
 }
 
 impl  ToString for Identifier {
@@ -69,4 +97,14 @@ mod tests {
             Err(_) => assert!(!expected)  
         }
     }
+
+    #[rstest]
+    #[case("minecraft:stick", Some(("minecraft", "stick")))]
+    #[case("stick", Some(("minecraft", "stick")))]
+    #[case("zirconium:block/test", Some(("zirconium", "block/test")))]
+    #[case("minecraft:st:ck", None)]
+    pub fn test_identifier_from_str(#[case] input: &str, #[case] expected: Option<(&str, &str)>) {
+        let identifier = input.parse::<Identifier>().ok();
+        assert_eq!(identifier.as_ref().map(|id| (id.namespace(), id.value())), expected);
+    }
 }
\ No newline at end of file
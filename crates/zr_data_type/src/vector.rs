@@ -0,0 +1,68 @@
+use std::ops::{Add, Sub};
+
+use serde::{Deserialize, Serialize};
+use zr_binary::binary::{read, Binary};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Vector3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vector3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn map<U>(self, mut f: impl FnMut(T) -> U) -> Vector3<U> {
+        Vector3::new(f(self.x), f(self.y), f(self.z))
+    }
+}
+
+impl Vector3<f64> {
+    pub fn horizontal_distance_squared(&self, other: &Self) -> f64 {
+        (self.x - other.x).powi(2) + (self.z - other.z).powi(2)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Vector3<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Vector3<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Binary> Binary for Vector3<T> {
+    fn binary_len(&self) -> usize {
+        self.x.binary_len() + self.y.binary_len() + self.z.binary_len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        let mut bin = self.x.to_binary();
+        bin.extend(self.y.to_binary());
+        bin.extend(self.z.to_binary());
+        bin
+    }
+
+    fn from_binary(bin: Vec<u8>) -> zr_binary::error::Result<Self>
+    where
+        Self: Sized,
+    {
+        let mut cursor = 0;
+        Ok(Self::new(
+            read(&bin, &mut cursor)?,
+            read(&bin, &mut cursor)?,
+            read(&bin, &mut cursor)?,
+        ))
+    }
+}
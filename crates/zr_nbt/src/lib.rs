@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use zr_binary::{binary::Binary, error::BinaryError};
 
 pub mod either;
+pub mod raw;
+pub mod string;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Nbt<T>(T)
@@ -0,0 +1,131 @@
+use zr_binary::{binary::Binary, error::BinaryError};
+
+const TAG_END: u8 = 0x00;
+const TAG_BYTE_ARRAY: u8 = 0x07;
+const TAG_STRING: u8 = 0x08;
+const TAG_LIST: u8 = 0x09;
+const TAG_COMPOUND: u8 = 0x0A;
+const TAG_INT_ARRAY: u8 = 0x0B;
+const TAG_LONG_ARRAY: u8 = 0x0C;
+/// Nesting allowed before the data is taken as malformed, as the vanilla reader does
+const MAX_DEPTH: usize = 512;
+
+/// Network NBT kept as its bytes, for the data the server only passes along.
+/// The root tag has no name, `TAG_END` alone is an empty NBT.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RawNbt(pub Vec<u8>);
+
+fn get(bin: &[u8], at: usize, len: usize) -> Option<&[u8]> {
+    bin.get(at..at.checked_add(len)?)
+}
+
+fn int(bin: &[u8], at: usize) -> Option<usize> {
+    let bytes = get(bin, at, 4)?;
+    usize::try_from(i32::from_be_bytes(bytes.try_into().ok()?)).ok()
+}
+
+fn short(bin: &[u8], at: usize) -> Option<usize> {
+    let bytes = get(bin, at, 2)?;
+    Some(u16::from_be_bytes(bytes.try_into().ok()?) as usize)
+}
+
+/// Length of the payload of a `tag` starting at `at`
+fn payload_len(tag: u8, bin: &[u8], at: usize, depth: usize) -> Option<usize> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let len = match tag {
+        0x01 => 1,
+        0x02 => 2,
+        0x03 | 0x05 => 4,
+        0x04 | 0x06 => 8,
+        TAG_BYTE_ARRAY => 4 + int(bin, at)?,
+        TAG_STRING => 2 + short(bin, at)?,
+        TAG_INT_ARRAY => 4 + int(bin, at)?.checked_mul(4)?,
+        TAG_LONG_ARRAY => 4 + int(bin, at)?.checked_mul(8)?,
+        TAG_LIST => {
+            let element = *bin.get(at)?;
+            let count = int(bin, at + 1)?;
+            let mut len = 5;
+            for _ in 0..count {
+                len += payload_len(element, bin, at + len, depth + 1)?;
+            }
+            len
+        }
+        TAG_COMPOUND => {
+            let mut len = 0;
+            loop {
+                let tag = *bin.get(at + len)?;
+                len += 1;
+                if tag == TAG_END {
+                    break len;
+                }
+                len += 2 + short(bin, at + len)?;
+                len += payload_len(tag, bin, at + len, depth + 1)?;
+            }
+        }
+        _ => return None,
+    };
+    get(bin, at, len).map(|_| len)
+}
+
+/// Length of the network NBT at the start of `bin`, `None` when it is malformed or cut
+pub fn network_len(bin: &[u8]) -> Option<usize> {
+    match *bin.first()? {
+        TAG_END => Some(1),
+        tag => Some(1 + payload_len(tag, bin, 1, 0)?),
+    }
+}
+
+impl Binary for RawNbt {
+    fn binary_len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        self.0
+    }
+
+    fn from_binary(mut bin: Vec<u8>) -> zr_binary::error::Result<Self>
+    where
+        Self: Sized,
+    {
+        let len = network_len(&bin).ok_or(BinaryError::FormatError)?;
+        bin.truncate(len);
+        Ok(Self(bin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_binary::binary::Binary;
+
+    use super::{network_len, RawNbt};
+
+    #[test]
+    fn test_network_len() {
+        // {name: "Bob", scores: [I; 1, 2], pos: [1.0d, 2.0d], nested: {flag: 1b}}
+        let mut nbt = vec![0x0A];
+        nbt.extend([0x08, 0, 4, b'n', b'a', b'm', b'e', 0, 3, b'B', b'o', b'b']);
+        nbt.extend([0x0B, 0, 6, b's', b'c', b'o', b'r', b'e', b's', 0, 0, 0, 2]);
+        nbt.extend([0, 0, 0, 1, 0, 0, 0, 2]);
+        nbt.extend([0x09, 0, 3, b'p', b'o', b's', 0x06, 0, 0, 0, 2]);
+        nbt.extend(1.0_f64.to_be_bytes());
+        nbt.extend(2.0_f64.to_be_bytes());
+        nbt.extend([0x0A, 0, 6, b'n', b'e', b's', b't', b'e', b'd']);
+        nbt.extend([0x01, 0, 4, b'f', b'l', b'a', b'g', 1, 0x00]);
+        nbt.push(0x00);
+        let len = nbt.len();
+        assert_eq!(network_len(&nbt), Some(len));
+
+        // the data after the nbt is left
+        nbt.extend([0x2A, 0x2A]);
+        let raw = RawNbt::from_binary(nbt.clone()).unwrap();
+        assert_eq!(raw.binary_len(), len);
+        assert_eq!(raw.to_binary(), nbt[..len]);
+
+        assert_eq!(network_len(&[0x00]), Some(1));
+        assert_eq!(network_len(&nbt[..len - 1]), None);
+        assert_eq!(network_len(&[0x0D]), None);
+    }
+}
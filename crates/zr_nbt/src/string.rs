@@ -0,0 +1,44 @@
+use zr_binary::{binary::Binary, error::BinaryError};
+
+const TAG_STRING: u8 = 0x08;
+
+/// Network NBT holding a single string tag, which is the shortest form of a text component.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NbtString(pub String);
+
+impl NbtString {
+    pub fn new(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl From<String> for NbtString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl Binary for NbtString {
+    fn binary_len(&self) -> usize {
+        1 + 2 + self.0.len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        let mut bin = vec![TAG_STRING];
+        bin.extend((self.0.len() as u16).to_binary());
+        bin.extend(self.0.into_bytes());
+        bin
+    }
+
+    fn from_binary(bin: Vec<u8>) -> zr_binary::error::Result<Self>
+    where
+        Self: Sized,
+    {
+        if bin.first() != Some(&TAG_STRING) {
+            return Err(BinaryError::FormatError);
+        }
+        let len = u16::from_binary(bin[1..].to_vec())? as usize;
+        let value = bin.get(3..3 + len).ok_or(BinaryError::LengthError)?;
+        Ok(Self(String::from_utf8_lossy(value).to_string()))
+    }
+}
@@ -110,6 +110,6 @@ pub fn listener(_: TokenStream, input: TokenStream) -> TokenStream {
         }
     }
     .into();
-    println!("{gen}");
+    // println!("{gen}");
     gen
 }
@@ -1,3 +1,4 @@
+use super::proxy::ProxyProtocol;
 use crate::{
     error::{network::NetworkError, packet::PacketError},
     packet::compressed::CompressedPacket,
@@ -5,14 +6,16 @@ use crate::{
 };
 use flate2::Compression;
 use std::{
-    io::{Error, Read, Write},
-    net::TcpStream,
+    io::{Error, ErrorKind, Read, Write},
+    net::{SocketAddr, TcpStream},
+    time::{Duration, Instant},
 };
 use zr_binary::{
     binary::Binary,
     error::BinaryError,
     varint::{self, VarInt},
 };
+use zr_core::console;
 
 const MAX_SIZE: usize = 2_097_149;
 
@@ -21,6 +24,60 @@ pub const CIPHER_ACTIVE: u8 = 0b0000_0010;
 const COMPRESSION_ENABLE: u8 = 0b0000_0100;
 pub const COMPRESSION_ACTIVE: u8 = 0b0000_1000;
 
+/// First byte sent by the clients before 1.7 to ping the server,
+/// read as a packet of this id holding the bytes after it
+pub const LEGACY_PING: u8 = 0xFE;
+/// Enough for the longest legacy ping, sent by 1.6 with the server address
+const LEGACY_PING_SIZE: usize = 1024;
+/// Time given to the rest of a 1.6 legacy ping to arrive
+const LEGACY_PING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Length of the 1.6 legacy ping, `None` while its plugin message header is missing
+fn legacy_ping_len(ping: &[u8]) -> Option<usize> {
+    let short = |at: usize| {
+        ping.get(at..at + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]) as usize)
+    };
+    // `0xFE 0x01 0xFA`, the channel in UTF-16 then the data
+    let data = 5 + 2 * short(3)?;
+    Some(data + 2 + short(data)?)
+}
+
+/// Read a legacy ping from a non blocking `stream`, `0xFE` included.
+/// The pings before 1.6 end with what was sent at once, as vanilla reads them,
+/// the `MC|PingHost` message of 1.6 is read until it arrived whole
+fn read_legacy_ping(stream: &mut impl Read) -> std::io::Result<Vec<u8>> {
+    let deadline = Instant::now() + LEGACY_PING_TIMEOUT;
+    let mut ping = Vec::new();
+    let mut buf = [0_u8; LEGACY_PING_SIZE];
+    loop {
+        let ping_host = ping.get(1..3) == Some(&[0x01, 0xFA][..]);
+        if let Some(len) = legacy_ping_len(&ping).filter(|_| ping_host) {
+            if len > LEGACY_PING_SIZE {
+                return Err(Error::new(ErrorKind::InvalidData, "legacy ping too long"));
+            }
+            if ping.len() >= len {
+                ping.truncate(len);
+                return Ok(ping);
+            }
+        }
+        match stream.read(&mut buf[..LEGACY_PING_SIZE - ping.len()]) {
+            Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+            Ok(read) => ping.extend(&buf[..read]),
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                if !ping.is_empty() && !ping_host {
+                    return Ok(ping);
+                }
+                if Instant::now() >= deadline {
+                    return Err(ErrorKind::TimedOut.into());
+                }
+                std::thread::yield_now();
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
 // TODO : Add cipher & compression
 #[derive(Debug)]
 pub struct Client {
@@ -29,6 +86,10 @@ pub struct Client {
     threshold: usize,
     compression: Compression,
     opt: u8,
+    /// nothing read yet, the connection may be a legacy ping
+    fresh: bool,
+    /// address of the client given by a trusted proxy with the PROXY protocol
+    proxied: Option<SocketAddr>,
 }
 
 impl Client {
@@ -48,9 +109,26 @@ impl Client {
             compression: flate2::Compression::default(),
             aes_key,
             opt,
+            fresh: true,
+            proxied: None,
         })
     }
 
+    /// Read the PROXY protocol header of a stream coming from a trusted proxy,
+    /// before anything else, then the client as `new_with_opt`
+    pub fn new_with_proxy(
+        mut stream: TcpStream,
+        opt: u8,
+        proxy: &ProxyProtocol,
+    ) -> Result<Self, NetworkError> {
+        let proxied = proxy
+            .read_header(&mut stream)
+            .map_err(NetworkError::ProxyError)?;
+        let mut client = Self::new_with_opt(stream, opt).map_err(NetworkError::IOError)?;
+        client.proxied = proxied;
+        Ok(client)
+    }
+
     fn aes_key(&self) -> &[u8; 16] {
         &self.aes_key
     }
@@ -71,11 +149,33 @@ impl Client {
         self.opt & COMPRESSION_ACTIVE != 0
     }
 
+    pub fn peer_addr(&self) -> Result<SocketAddr, Error> {
+        self.stream.peer_addr()
+    }
+
+    /// Address of the client, read from the PROXY protocol header behind a load balancer
+    pub fn address(&self) -> Result<SocketAddr, Error> {
+        match self.proxied {
+            Some(address) => Ok(address),
+            None => self.peer_addr(),
+        }
+    }
+
     pub fn shutdown(&mut self) -> Result<(), std::io::Error> {
         self.stream.shutdown(std::net::Shutdown::Both)
     }
 
     pub fn read_packet(&mut self) -> Result<Packet, NetworkError> {
+        if self.fresh {
+            let mut first = [0_u8; 1];
+            self.stream
+                .peek(&mut first)
+                .map_err(NetworkError::IOError)?;
+            self.fresh = false;
+            if first[0] == LEGACY_PING {
+                return self.read_legacy_ping();
+            }
+        }
         let len = varint::from_reader(&mut self.stream)
             .map_err(NetworkError::IOError)?
             .map_err(|err| NetworkError::PacketError(PacketError::DataError(err)))?;
@@ -100,12 +200,24 @@ impl Client {
             Packet::from_binary(data)
                 .map_err(|err| NetworkError::PacketError(PacketError::DataError(err)))
         };
-        println!("[R]\t{packet:?}");
+        console::log(format!("[R]\t{packet:?}"));
         packet
     }
 
+    fn read_legacy_ping(&mut self) -> Result<Packet, NetworkError> {
+        let ping = read_legacy_ping(&mut self.stream).map_err(NetworkError::IOError)?;
+        let packet = Packet::new(LEGACY_PING as i32, ping[1..].to_vec());
+        console::log(format!("[R]\t{packet:?}"));
+        Ok(packet)
+    }
+
+    /// Write `data` as is, without the packet length, e.g. to answer a legacy ping
+    pub fn write_raw(&mut self, data: &[u8]) -> Result<(), NetworkError> {
+        self.stream.write_all(data).map_err(NetworkError::IOError)
+    }
+
     pub fn write_packet(&mut self, packet: Packet) -> Result<(), NetworkError> {
-        println!("[S]\t{packet:?}");
+        console::log(format!("[S]\t{packet:?}"));
         let packet = if self.is_compression_enable() {
             packet
                 .compress(self.threshold, self.compression)
@@ -149,6 +261,70 @@ impl Client {
             compression: self.compression.clone(),
             threshold: self.threshold,
             opt: self.opt,
+            fresh: self.fresh,
+            proxied: self.proxied,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        io::{ErrorKind, Read},
+    };
+
+    use super::read_legacy_ping;
+
+    /// Gives one chunk per read, an empty chunk as no data yet
+    struct Chunks(VecDeque<Vec<u8>>);
+
+    impl Read for Chunks {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.pop_front() {
+                Some(chunk) if !chunk.is_empty() => {
+                    buf[..chunk.len()].copy_from_slice(&chunk);
+                    Ok(chunk.len())
+                }
+                _ => Err(ErrorKind::WouldBlock.into()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_legacy_ping() {
+        let mut ping = vec![0xFE, 0x01, 0xFA, 0, 11];
+        for unit in "MC|PingHost".encode_utf16() {
+            ping.extend(unit.to_be_bytes());
+        }
+        ping.extend([0, 7 + 18, 74, 0, 9]);
+        for unit in "localhost".encode_utf16() {
+            ping.extend(unit.to_be_bytes());
+        }
+        ping.extend(25565_i32.to_be_bytes());
+
+        // the ping of 1.6 split by the network
+        let chunks = [
+            &ping[..4],
+            &[],
+            &ping[4..10],
+            &ping[10..30],
+            &[],
+            &ping[30..],
+        ];
+        let mut stream = Chunks(chunks.iter().map(|chunk| chunk.to_vec()).collect());
+        assert_eq!(read_legacy_ping(&mut stream).unwrap(), ping);
+
+        // the older versions send the ping at once, what comes later is not read
+        let mut stream = Chunks(VecDeque::from([vec![0xFE, 0x01], vec![], vec![0xFA]]));
+        assert_eq!(read_legacy_ping(&mut stream).unwrap(), [0xFE, 0x01]);
+        let mut stream = Chunks(VecDeque::from([vec![0xFE]]));
+        assert_eq!(read_legacy_ping(&mut stream).unwrap(), [0xFE]);
+
+        let mut stream = Chunks(VecDeque::from([ping[..20].to_vec()]));
+        assert_eq!(
+            read_legacy_ping(&mut stream).unwrap_err().kind(),
+            ErrorKind::TimedOut
+        );
+    }
+}
@@ -6,6 +6,7 @@ use std::{
         Arc, Mutex, TryLockError,
     },
 };
+use zr_core::console;
 
 pub struct ClientManager {
     max_pid: u16,
@@ -85,7 +86,7 @@ impl ClientManager {
                                     }
                                 }
                                 to_remove.push(id);
-                                eprintln!("[{pid:04x}{cid:04x}] {err:?}");
+                                console::error(format!("[{pid:04x}{cid:04x}] {err:?}"));
                             }
                         }
                     }
@@ -161,11 +162,25 @@ impl ClientManager {
         let (pid, cid) = Self::split_id(client_id);
         let mut client = self.remove_client_inner(pid, cid);
         if let Err(err) = client.shutdown() {
-            eprintln!("{err:?}");
+            console::error(format!("{err:?}"));
         }
         if self.clients_partition[&pid].lock().unwrap().is_empty() {
             self.remove_partition(pid);
         }
         self.free_id.insert((pid, cid));
     }
+
+    /// Disconnect every client, the partition threads end with their last client
+    pub fn shutdown(&mut self) {
+        for (_, partition) in self.clients_partition.drain() {
+            if let Ok(mut clients) = partition.lock() {
+                for (_, mut client) in clients.drain() {
+                    if let Err(err) = client.shutdown() {
+                        console::error(format!("{err:?}"));
+                    }
+                }
+            }
+        }
+        self.free_id.clear();
+    }
 }
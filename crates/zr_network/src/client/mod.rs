@@ -1,2 +1,3 @@
 pub mod manager;
 pub mod client;
+pub mod proxy;
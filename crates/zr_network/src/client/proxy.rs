@@ -0,0 +1,212 @@
+use std::{
+    io::Read,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream},
+    time::Duration,
+};
+
+use crate::error::proxy::ProxyError;
+
+/// Start of a version 1 header, in text
+const V1_SIGNATURE: &[u8; 6] = b"PROXY ";
+/// Longest version 1 header, with its CRLF
+const V1_MAX_SIZE: usize = 107;
+/// Start of a version 2 header, in binary
+const V2_SIGNATURE: &[u8; 12] = b"\r\n\r\n\0\r\nQUIT\n";
+/// Version 2, the proxy connects on its own, e.g. for a health check
+const V2_LOCAL: u8 = 0x20;
+/// Version 2, the proxy relays a client
+const V2_PROXY: u8 = 0x21;
+const V2_TCP4: u8 = 0x11;
+const V2_TCP6: u8 = 0x21;
+/// The proxies send the header as soon as they connect
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// PROXY protocol of HAProxy, giving the address of the clients connecting through
+/// a load balancer. Only the trusted proxies are read, the others connect directly
+#[derive(Debug, Clone, Default)]
+pub struct ProxyProtocol {
+    trusted: Vec<IpAddr>,
+}
+
+impl ProxyProtocol {
+    pub fn new(trusted: Vec<IpAddr>) -> Self {
+        Self {
+            trusted: trusted.into_iter().map(|ip| ip.to_canonical()).collect(),
+        }
+    }
+
+    pub fn is_trusted(&self, ip: IpAddr) -> bool {
+        self.trusted.contains(&ip.to_canonical())
+    }
+
+    /// The address of the client behind the proxy, read from the header on a new stream.
+    /// `None` when the stream does not come from a trusted proxy or the proxy does not relay a client
+    pub fn read_header(&self, stream: &mut TcpStream) -> Result<Option<SocketAddr>, ProxyError> {
+        let peer = stream.peer_addr().map_err(ProxyError::IOError)?;
+        if !self.is_trusted(peer.ip()) {
+            return Ok(None);
+        }
+        stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.set_read_timeout(Some(HEADER_TIMEOUT)))
+            .map_err(ProxyError::IOError)?;
+        let source = read_header(stream);
+        stream.set_read_timeout(None).map_err(ProxyError::IOError)?;
+        source
+    }
+}
+
+fn read_exact(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), ProxyError> {
+    reader.read_exact(buf).map_err(ProxyError::IOError)
+}
+
+/// The source address of a version 1 or 2 header, reading nothing after it
+pub fn read_header(reader: &mut impl Read) -> Result<Option<SocketAddr>, ProxyError> {
+    let mut signature = [0_u8; V2_SIGNATURE.len()];
+    read_exact(reader, &mut signature[..V1_SIGNATURE.len()])?;
+    if signature[..V1_SIGNATURE.len()] == V1_SIGNATURE[..] {
+        return read_v1(reader);
+    }
+    read_exact(reader, &mut signature[V1_SIGNATURE.len()..])?;
+    if signature != *V2_SIGNATURE {
+        return Err(ProxyError::MissingHeader);
+    }
+    read_v2(reader)
+}
+
+fn read_v1(reader: &mut impl Read) -> Result<Option<SocketAddr>, ProxyError> {
+    let mut line = V1_SIGNATURE.to_vec();
+    while !line.ends_with(b"\r\n") {
+        if line.len() >= V1_MAX_SIZE {
+            return Err(ProxyError::InvalidHeader);
+        }
+        let mut byte = [0_u8; 1];
+        read_exact(reader, &mut byte)?;
+        line.push(byte[0]);
+    }
+    let line =
+        std::str::from_utf8(&line[..line.len() - 2]).map_err(|_| ProxyError::InvalidHeader)?;
+    parse_v1(line)
+}
+
+/// `PROXY TCP4 source destination source_port destination_port`
+fn parse_v1(line: &str) -> Result<Option<SocketAddr>, ProxyError> {
+    match line.split(' ').collect::<Vec<_>>()[..] {
+        ["PROXY", "UNKNOWN", ..] => Ok(None),
+        ["PROXY", protocol @ ("TCP4" | "TCP6"), source, _, port, _] => {
+            let ip = source
+                .parse::<IpAddr>()
+                .map_err(|_| ProxyError::InvalidHeader)?;
+            if ip.is_ipv4() != (protocol == "TCP4") {
+                return Err(ProxyError::InvalidHeader);
+            }
+            let port = port.parse().map_err(|_| ProxyError::InvalidHeader)?;
+            Ok(Some(SocketAddr::new(ip, port)))
+        }
+        _ => Err(ProxyError::InvalidHeader),
+    }
+}
+
+fn read_v2(reader: &mut impl Read) -> Result<Option<SocketAddr>, ProxyError> {
+    let mut header = [0_u8; 4];
+    read_exact(reader, &mut header)?;
+    let length = u16::from_be_bytes([header[2], header[3]]) as usize;
+    // the addresses then the TLVs, all read so the handshake comes next
+    let mut addresses = vec![0_u8; length];
+    read_exact(reader, &mut addresses)?;
+    parse_v2(header[0], header[1], &addresses)
+}
+
+/// The address at the start of the address block
+fn octets<const N: usize>(addresses: &[u8]) -> Result<[u8; N], ProxyError> {
+    addresses
+        .get(..N)
+        .and_then(|ip| ip.try_into().ok())
+        .ok_or(ProxyError::InvalidHeader)
+}
+
+fn parse_v2(command: u8, family: u8, addresses: &[u8]) -> Result<Option<SocketAddr>, ProxyError> {
+    match command {
+        V2_LOCAL => return Ok(None),
+        V2_PROXY => (),
+        _ => return Err(ProxyError::InvalidHeader),
+    }
+    let port = |at: usize| {
+        addresses
+            .get(at..at + 2)
+            .map(|port| u16::from_be_bytes([port[0], port[1]]))
+            .ok_or(ProxyError::InvalidHeader)
+    };
+    let source = match family {
+        V2_TCP4 => {
+            let ip: [u8; 4] = octets(addresses)?;
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::from(ip)), port(8)?)
+        }
+        V2_TCP6 => {
+            let ip: [u8; 16] = octets(addresses)?;
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::from(ip)), port(32)?)
+        }
+        // UDP or unix sockets, no address a player could come from
+        _ => return Ok(None),
+    };
+    Ok(Some(source))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Cursor,
+        net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    };
+
+    use super::{read_header, ProxyProtocol, V2_SIGNATURE};
+
+    #[test]
+    fn test_v1() {
+        let mut stream =
+            Cursor::new(b"PROXY TCP4 203.0.113.7 10.0.0.1 51234 25565\r\n\x10".to_vec());
+        assert_eq!(
+            read_header(&mut stream).unwrap(),
+            Some(SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)),
+                51234
+            ))
+        );
+        // the handshake is left to read
+        assert_eq!(stream.position(), 45);
+
+        let mut stream = Cursor::new(b"PROXY UNKNOWN\r\n".to_vec());
+        assert_eq!(read_header(&mut stream).unwrap(), None);
+        let mut stream = Cursor::new(b"PROXY TCP6 203.0.113.7 10.0.0.1 51234 25565\r\n".to_vec());
+        assert!(read_header(&mut stream).is_err());
+        let mut stream = Cursor::new(b"\x10\x00\xff\x05\x09localhost".to_vec());
+        assert!(read_header(&mut stream).is_err());
+    }
+
+    #[test]
+    fn test_v2() {
+        let mut data = V2_SIGNATURE.to_vec();
+        data.extend([0x21, 0x21, 0, 39]);
+        data.extend(Ipv6Addr::LOCALHOST.octets());
+        data.extend(Ipv6Addr::UNSPECIFIED.octets());
+        data.extend(51234_u16.to_be_bytes());
+        data.extend(25565_u16.to_be_bytes());
+        // a TLV, skipped
+        data.extend([0x04, 0, 0]);
+        data.push(0x10);
+        let mut stream = Cursor::new(data.clone());
+        assert_eq!(
+            read_header(&mut stream).unwrap(),
+            Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 51234))
+        );
+        assert_eq!(stream.position() as usize, data.len() - 1);
+
+        // a health check
+        data[12] = 0x20;
+        assert_eq!(read_header(&mut Cursor::new(data)).unwrap(), None);
+
+        let proxy = ProxyProtocol::new(vec![IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))]);
+        assert!(proxy.is_trusted("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(!proxy.is_trusted(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2))));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod network;
 pub mod packet;
+pub mod proxy;
 pub mod worker;
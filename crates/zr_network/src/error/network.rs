@@ -1,6 +1,6 @@
 use std::{error::Error, fmt::Display};
 
-use super::{packet::PacketError, worker::WorkerError};
+use super::{packet::PacketError, proxy::ProxyError, worker::WorkerError};
 
 #[derive(Debug)]
 pub enum NetworkError {
@@ -8,6 +8,7 @@ pub enum NetworkError {
     TimedOut,
     ThreadError(WorkerError),
     PacketError(PacketError),
+    ProxyError(ProxyError),
     IOError(std::io::Error),
     Other(Box<dyn Error>),
 }
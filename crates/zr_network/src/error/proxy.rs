@@ -0,0 +1,19 @@
+use std::fmt::Display;
+
+#[derive(Debug)]
+pub enum ProxyError {
+    /// the trusted proxy sent no PROXY protocol header
+    MissingHeader,
+    InvalidHeader,
+    IOError(std::io::Error),
+}
+
+unsafe impl Send for ProxyError {}
+
+impl std::error::Error for ProxyError {}
+
+impl Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
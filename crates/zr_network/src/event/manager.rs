@@ -3,7 +3,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use zr_core::worker::Worker;
+use zr_core::{console, worker::Worker};
 
 use crate::error::worker::WorkerError;
 
@@ -39,7 +39,7 @@ impl EventManager {
             Err(mut err) => {
                 **err.get_mut() = VecDeque::from(vec![event]);
                 self.event_pool.clear_poison();
-                eprintln!("Error occured in EventPool");
+                console::error("Error occured in EventPool");
             }
         }
     }
@@ -50,7 +50,7 @@ impl EventManager {
             Err(mut err) => {
                 **err.get_mut() = VecDeque::new();
                 self.event_pool.clear_poison();
-                eprintln!("Error occured in EventPool");
+                console::error("Error occured in EventPool");
                 None
             }
         }
@@ -59,7 +59,7 @@ impl EventManager {
     fn call(&mut self, event: &Event) {
         for listener in &mut self.listeners {
             if let Err(err) = listener.as_mut().handle_event(event) {
-                eprintln!("Error while listening : {event:?} {err:?}");
+                console::error(format!("Error while listening : {event:?} {err:?}"));
             }
         }
     }
@@ -79,18 +79,19 @@ impl Worker for EventManager {
 
     fn init(&mut self) {
         if self.listeners.is_empty() {
-            eprintln!("EventManager has no listeners");
+            console::error("EventManager has no listeners");
             self.alive = false;
         }
     }
 
-    fn handle(&mut self) {
+    fn handle(&mut self) -> Result<(), Self::Err> {
         if let Some(event) = self.pop_event() {
             self.call(&event);
         }
+        Ok(())
     }
 
     fn end(&mut self) {
-        println!("End")
+        console::log("End")
     }
 }
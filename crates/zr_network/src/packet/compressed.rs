@@ -5,6 +5,7 @@ use flate2::{
     Compress, Compression,
 };
 use zr_binary::{binary::Binary, varint::VarInt};
+use zr_core::console;
 
 use super::packet::Packet;
 
@@ -41,7 +42,7 @@ impl CompressedPacket {
     }
 
     pub fn decompress(self) -> io::Result<Packet> {
-        println!("GET COMPRESSED");
+        console::log("GET COMPRESSED");
         Ok(if self.data_length.0 == 0_i32 {
             Packet::from_binary(self.compressed_packet).unwrap()
         } else {
@@ -27,6 +27,19 @@ impl Debug for Packet {
 }
 
 impl Packet {
+    pub fn new(id: i32, data: Vec<u8>) -> Self {
+        Self {
+            id: id.into(),
+            data,
+        }
+    }
+
+    /// The same packet with the id `id`, e.g. for another protocol version
+    pub fn with_id(mut self, id: i32) -> Self {
+        self.id = id.into();
+        self
+    }
+
     pub fn id(&self) -> i32 {
         self.id.0
     }
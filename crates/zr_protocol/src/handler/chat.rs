@@ -0,0 +1,424 @@
+use std::{
+    collections::VecDeque,
+    fmt::{self, Display},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rsa::{
+    pkcs1v15::{Signature, VerifyingKey},
+    pkcs8::DecodePublicKey,
+    sha2::Sha256,
+    signature::Verifier,
+    RsaPublicKey,
+};
+use uuid::Uuid;
+
+use crate::packet::play::{
+    client::{Acknowledged, MessageSignature},
+    server::ChatSessionData,
+};
+
+/// Messages a client acknowledges in each chat packet
+pub const LAST_SEEN_SIZE: usize = 20;
+pub const MAX_MESSAGE_LENGTH: usize = 256;
+/// Version of the signed message format
+const SIGNATURE_VERSION: i32 = 1;
+
+/// Milliseconds since the epoch, as the chat timestamps
+pub fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as i64)
+}
+
+/// Why a chat packet is refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatError {
+    MissingProfileKey,
+    ExpiredProfileKey,
+    InvalidPublicKey,
+    InvalidSignature,
+    OutOfOrder,
+    IllegalCharacters,
+    /// the acknowledged messages do not match the ones sent to the client
+    ValidationFailure,
+}
+
+impl ChatError {
+    /// The client is disconnected, otherwise only its message is dropped
+    pub fn disconnects(&self) -> bool {
+        !matches!(self, Self::MissingProfileKey | Self::ExpiredProfileKey)
+    }
+}
+
+impl Display for ChatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MissingProfileKey => {
+                "Chat disabled due to missing profile public key. Please try reconnecting."
+            }
+            Self::ExpiredProfileKey => {
+                "Chat disabled due to expired profile public key. Please try reconnecting."
+            }
+            Self::InvalidPublicKey => "Invalid profile public key",
+            Self::InvalidSignature => "Received chat packet with missing or invalid signature.",
+            Self::OutOfOrder => "Out-of-order chat packet received. Did your system time change?",
+            Self::IllegalCharacters => "Illegal characters in chat",
+            Self::ValidationFailure => "Chat message validation failure",
+        })
+    }
+}
+
+/// Data covered by the signature of a chat message
+pub(crate) fn signed_data(
+    sender: Uuid,
+    session_id: Uuid,
+    index: i32,
+    salt: i64,
+    timestamp: i64,
+    message: &str,
+    last_seen: &[MessageSignature],
+) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend(SIGNATURE_VERSION.to_be_bytes());
+    data.extend(sender.as_bytes());
+    data.extend(session_id.as_bytes());
+    data.extend(index.to_be_bytes());
+    data.extend(salt.to_be_bytes());
+    // the signed timestamp is in seconds
+    data.extend((timestamp / 1000).to_be_bytes());
+    data.extend((message.len() as i32).to_be_bytes());
+    data.extend(message.as_bytes());
+    data.extend((last_seen.len() as i32).to_be_bytes());
+    for signature in last_seen {
+        data.extend(signature);
+    }
+    data
+}
+
+/// Session sent by the client, its messages are signed with the key of its profile.
+/// The key is not checked against Mojang's signature as the players are not authenticated
+#[derive(Debug, Clone)]
+pub struct ChatSession {
+    session_id: Uuid,
+    /// milliseconds since the epoch
+    expires_at: i64,
+    key: VerifyingKey<Sha256>,
+    /// DER encoded, sent to the other players with the signature of Mojang
+    public_key: Vec<u8>,
+    key_signature: Vec<u8>,
+}
+
+impl ChatSession {
+    /// `public_key` is DER encoded
+    pub fn new(
+        session_id: Uuid,
+        expires_at: i64,
+        public_key: &[u8],
+        key_signature: &[u8],
+    ) -> Result<Self, ChatError> {
+        let key = RsaPublicKey::from_public_key_der(public_key)
+            .map_err(|_| ChatError::InvalidPublicKey)?;
+        Ok(Self {
+            session_id,
+            expires_at,
+            key: VerifyingKey::new(key),
+            public_key: public_key.to_vec(),
+            key_signature: key_signature.to_vec(),
+        })
+    }
+
+    /// The session as sent in the player list
+    pub fn data(&self) -> ChatSessionData {
+        ChatSessionData {
+            session_id: self.session_id,
+            expires_at: self.expires_at,
+            public_key: self.public_key.clone(),
+            key_signature: self.key_signature.clone(),
+        }
+    }
+
+    pub fn session_id(&self) -> Uuid {
+        self.session_id
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at < now
+    }
+
+    fn verify(&self, data: &[u8], signature: &MessageSignature) -> bool {
+        Signature::try_from(signature.as_slice())
+            .is_ok_and(|signature| self.key.verify(data, &signature).is_ok())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tracked {
+    signature: MessageSignature,
+    /// not acknowledged yet
+    pending: bool,
+}
+
+/// Signatures sent to a client, which acknowledges the ones it has seen in its chat packets
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastSeen {
+    /// the oldest first, at least `LAST_SEEN_SIZE`
+    tracked: VecDeque<Option<Tracked>>,
+}
+
+impl Default for LastSeen {
+    fn default() -> Self {
+        Self {
+            tracked: VecDeque::from(vec![None; LAST_SEEN_SIZE]),
+        }
+    }
+}
+
+impl LastSeen {
+    /// A signed message was sent to the client
+    pub fn add_pending(&mut self, signature: MessageSignature) {
+        self.tracked.push_back(Some(Tracked {
+            signature,
+            pending: true,
+        }));
+    }
+
+    /// The client saw `offset` new messages
+    pub fn apply_offset(&mut self, offset: i32) -> Result<(), ChatError> {
+        let available = self.tracked.len() - LAST_SEEN_SIZE;
+        let offset = usize::try_from(offset)
+            .ok()
+            .filter(|offset| *offset <= available)
+            .ok_or(ChatError::ValidationFailure)?;
+        self.tracked.drain(..offset);
+        Ok(())
+    }
+
+    /// Signatures the client acknowledged, in the order it signed them
+    pub fn apply_update(
+        &mut self,
+        offset: i32,
+        acknowledged: Acknowledged,
+    ) -> Result<Vec<MessageSignature>, ChatError> {
+        self.apply_offset(offset)?;
+        let mut seen = Vec::new();
+        for (index, entry) in self.tracked.iter_mut().take(LAST_SEEN_SIZE).enumerate() {
+            if acknowledged[index / 8] & (1 << (index % 8)) != 0 {
+                let tracked = entry.as_mut().ok_or(ChatError::ValidationFailure)?;
+                tracked.pending = false;
+                seen.push(tracked.signature);
+            } else {
+                // a message cannot be forgotten once acknowledged
+                if entry.is_some_and(|tracked| !tracked.pending) {
+                    return Err(ChatError::ValidationFailure);
+                }
+                *entry = None;
+            }
+        }
+        Ok(seen)
+    }
+}
+
+/// A chat message accepted from a player, `signature` is `None` in insecure mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerMessage {
+    pub sender: Uuid,
+    /// index in the chain of the sender
+    pub index: i32,
+    pub signature: Option<MessageSignature>,
+    pub message: String,
+    pub timestamp: i64,
+    pub salt: i64,
+    pub last_seen: Vec<MessageSignature>,
+}
+
+/// Chat of a player: its session, its message chain and the messages it saw
+#[derive(Debug, Clone, Default)]
+pub struct ChatState {
+    session: Option<ChatSession>,
+    next_index: i32,
+    last_timestamp: i64,
+    last_seen: LastSeen,
+}
+
+impl ChatState {
+    pub fn session(&self) -> Option<&ChatSession> {
+        self.session.as_ref()
+    }
+
+    /// A new session starts a new chain
+    pub fn set_session(&mut self, session: ChatSession) {
+        self.session = Some(session);
+        self.next_index = 0;
+    }
+
+    pub fn last_seen_mut(&mut self) -> &mut LastSeen {
+        &mut self.last_seen
+    }
+
+    /// Update the messages seen by a player running a command
+    pub fn acknowledge(
+        &mut self,
+        offset: i32,
+        acknowledged: Acknowledged,
+    ) -> Result<(), ChatError> {
+        self.last_seen.apply_update(offset, acknowledged).map(drop)
+    }
+
+    /// Check a chat message of `sender`, its signature is only checked when `secure`
+    #[allow(clippy::too_many_arguments)]
+    pub fn accept(
+        &mut self,
+        sender: Uuid,
+        message: String,
+        timestamp: i64,
+        salt: i64,
+        signature: Option<MessageSignature>,
+        offset: i32,
+        acknowledged: Acknowledged,
+        secure: bool,
+    ) -> Result<PlayerMessage, ChatError> {
+        if message.len() > MAX_MESSAGE_LENGTH
+            || message
+                .chars()
+                .any(|c| c == '§' || c < ' ' || c == '\u{7f}')
+        {
+            return Err(ChatError::IllegalCharacters);
+        }
+        let last_seen = self.last_seen.apply_update(offset, acknowledged)?;
+        let mut accepted = PlayerMessage {
+            sender,
+            index: 0,
+            signature: None,
+            message,
+            timestamp,
+            salt,
+            last_seen,
+        };
+        if !secure {
+            return Ok(accepted);
+        }
+        let session = self.session.as_ref().ok_or(ChatError::MissingProfileKey)?;
+        let signature = signature.ok_or(ChatError::InvalidSignature)?;
+        if timestamp < self.last_timestamp {
+            return Err(ChatError::OutOfOrder);
+        }
+        if session.is_expired(now_millis()) {
+            return Err(ChatError::ExpiredProfileKey);
+        }
+        let data = signed_data(
+            sender,
+            session.session_id,
+            self.next_index,
+            salt,
+            timestamp,
+            &accepted.message,
+            &accepted.last_seen,
+        );
+        if !session.verify(&data, &signature) {
+            return Err(ChatError::InvalidSignature);
+        }
+        accepted.index = self.next_index;
+        accepted.signature = Some(signature);
+        self.next_index += 1;
+        self.last_timestamp = timestamp;
+        Ok(accepted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rsa::{
+        pkcs1v15::SigningKey,
+        pkcs8::EncodePublicKey,
+        sha2::Sha256,
+        signature::{SignatureEncoding, Signer},
+        RsaPrivateKey, RsaPublicKey,
+    };
+    use uuid::Uuid;
+
+    use super::{
+        now_millis, signed_data, ChatError, ChatSession, ChatState, LastSeen, LAST_SEEN_SIZE,
+    };
+    use crate::packet::play::client::MessageSignature;
+
+    #[test]
+    fn test_last_seen() {
+        let mut last_seen = LastSeen::default();
+        assert_eq!(last_seen.apply_offset(1), Err(ChatError::ValidationFailure));
+        last_seen.add_pending([1; 256]);
+        last_seen.add_pending([2; 256]);
+        // both messages are now the last two of the window
+        let seen = last_seen.apply_update(2, [0, 0, 0b1100]).unwrap();
+        assert_eq!(seen, vec![[1; 256], [2; 256]]);
+        // an acknowledged message cannot be dropped
+        assert_eq!(
+            last_seen.clone().apply_update(0, [0, 0, 0b1000]),
+            Err(ChatError::ValidationFailure)
+        );
+        // nor can a message never sent be acknowledged
+        assert_eq!(
+            last_seen.apply_update(0, [1, 0, 0b1100]),
+            Err(ChatError::ValidationFailure)
+        );
+        assert_eq!(last_seen.tracked.len(), LAST_SEEN_SIZE);
+    }
+
+    #[test]
+    fn test_signed_chain() {
+        let private = RsaPrivateKey::new(&mut rand::thread_rng(), 2048).unwrap();
+        let public_key = RsaPublicKey::from(&private).to_public_key_der().unwrap();
+        let signer = SigningKey::<Sha256>::new(private);
+        let (sender, session_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let sign = |index, timestamp, message: &str| -> MessageSignature {
+            let data = signed_data(sender, session_id, index, 7, timestamp, message, &[]);
+            signer.sign(&data).to_vec().try_into().unwrap()
+        };
+
+        let mut chat = ChatState::default();
+        let now = now_millis();
+        let accept = |chat: &mut ChatState, message: &str, timestamp, signature| {
+            chat.accept(
+                sender,
+                message.to_string(),
+                timestamp,
+                7,
+                signature,
+                0,
+                [0; 3],
+                true,
+            )
+        };
+        assert_eq!(
+            accept(&mut chat, "hello", now, Some(sign(0, now, "hello"))),
+            Err(ChatError::MissingProfileKey)
+        );
+        let session =
+            ChatSession::new(session_id, now + 60_000, public_key.as_bytes(), &[]).unwrap();
+        chat.set_session(session);
+
+        let first = accept(&mut chat, "hello", now, Some(sign(0, now, "hello"))).unwrap();
+        assert_eq!(first.index, 0);
+        // the second message must be signed with the next index
+        assert_eq!(
+            accept(&mut chat, "again", now, Some(sign(0, now, "again"))),
+            Err(ChatError::InvalidSignature)
+        );
+        let second = accept(&mut chat, "again", now, Some(sign(1, now, "again"))).unwrap();
+        assert_eq!(second.index, 1);
+        assert_eq!(
+            accept(&mut chat, "late", now - 10_000, Some(sign(2, now, "late"))),
+            Err(ChatError::OutOfOrder)
+        );
+        assert_eq!(
+            accept(&mut chat, "§cred", now, None),
+            Err(ChatError::IllegalCharacters)
+        );
+
+        // without secure chat the messages are relayed unsigned
+        let insecure = ChatState::default()
+            .accept(sender, "hi".into(), now, 0, None, 0, [0; 3], false)
+            .unwrap();
+        assert_eq!(insecure.signature, None);
+    }
+}
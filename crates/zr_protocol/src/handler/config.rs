@@ -1,10 +1,12 @@
 use zr_network::packet::{packet::Packet, PacketData};
 
-use crate::packet::config::client;
+use crate::packet::config::{client, server};
 
 use super::{
+    cookie::on_cookie_response,
     handler::{Next, PacketHandler},
-    protocol_handler::Client,
+    keep_alive::TIMED_OUT,
+    protocol_handler::{Client, State},
 };
 
 /// Minimal implementation
@@ -15,9 +17,30 @@ impl PacketHandler for ConfigProtocol {
     fn handle_packet(&mut self, client: &mut Client, packet: Packet) -> Next {
         match packet.id() {
             client::AcknowledgeFinishConfiguration::ID => {
-                println!("OK");
+                // the player joins on the next tick
+                client.change_state(State::Play);
                 Next::Wait
             }
+            client::CookieResponse::ID => match client::CookieResponse::from_packet(packet) {
+                Ok(client::CookieResponse { key, payload, .. }) => {
+                    on_cookie_response(client, key, payload)
+                }
+                Err(_) => Next::Disconnect,
+            },
+            client::KeepAlive::ID => match client::KeepAlive::from_packet(packet) {
+                Ok(client::KeepAlive { keep_alive_id }) => {
+                    match client.on_keep_alive(keep_alive_id) {
+                        Ok(()) => Next::Wait,
+                        Err(_) => Next::Kick(TIMED_OUT.to_string()),
+                    }
+                }
+                Err(_) => Next::Disconnect,
+            },
+            // the registries are sent in full, whatever packs the client knows
+            client::KnownPacks::ID => match client::KnownPacks::from_packet(packet) {
+                Ok(_) => Next::SendPacket(server::FinishConfiguration.to_packet()),
+                Err(_) => Next::Disconnect,
+            },
             _ => Next::Disconnect,
         }
     }
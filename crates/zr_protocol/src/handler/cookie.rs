@@ -0,0 +1,122 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use zr_data_type::string::Identifier;
+
+use crate::packet::login::client::CookiePayload;
+
+use super::{handler::Next, protocol_handler::Client};
+
+/// Largest cookie the clients store, like vanilla
+pub const MAX_COOKIE_SIZE: usize = 5120;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CookieError {
+    /// size of the payload
+    TooLarge(usize),
+    /// the client cannot store or send cookies in its state
+    WrongState,
+    /// the client sends a cookie the server did not ask for
+    Unexpected(Identifier),
+}
+
+impl Display for CookieError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CookieError::TooLarge(size) => write!(
+                f,
+                "Cookie of {size} bytes, larger than {MAX_COOKIE_SIZE} bytes"
+            ),
+            CookieError::WrongState => write!(f, "No cookies in this state"),
+            CookieError::Unexpected(key) => write!(f, "Unexpected cookie {}", key.to_string()),
+        }
+    }
+}
+
+impl std::error::Error for CookieError {}
+
+pub fn check_size(payload: &[u8]) -> Result<(), CookieError> {
+    match payload.len() > MAX_COOKIE_SIZE {
+        true => Err(CookieError::TooLarge(payload.len())),
+        false => Ok(()),
+    }
+}
+
+/// Cookies asked to a client and its answers
+#[derive(Debug, Clone, Default)]
+pub struct Cookies {
+    requested: HashSet<Identifier>,
+    received: HashMap<Identifier, Option<Vec<u8>>>,
+}
+
+impl Cookies {
+    pub fn request(&mut self, key: Identifier) {
+        self.requested.insert(key);
+    }
+
+    /// Answer of the client to a request, `payload` is `None` when it has no such cookie
+    pub fn receive(
+        &mut self,
+        key: Identifier,
+        payload: Option<Vec<u8>>,
+    ) -> Result<(), CookieError> {
+        if !self.requested.remove(&key) {
+            return Err(CookieError::Unexpected(key));
+        }
+        if let Some(payload) = &payload {
+            check_size(payload)?;
+        }
+        self.received.insert(key, payload);
+        Ok(())
+    }
+
+    /// `None` until the client answers, then the cookie if it has one
+    pub fn get(&self, key: &Identifier) -> Option<Option<&[u8]>> {
+        self.received.get(key).map(|payload| payload.as_deref())
+    }
+}
+
+/// Handle a Cookie Response packet, the client is disconnected if it is not expected
+pub(crate) fn on_cookie_response(
+    client: &mut Client,
+    key: Identifier,
+    payload: Option<CookiePayload>,
+) -> Next {
+    match client
+        .cookies
+        .receive(key, payload.map(|payload| payload.data))
+    {
+        Ok(()) => Next::Wait,
+        Err(err) => Next::Kick(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_data_type::string::Identifier;
+
+    use super::{CookieError, Cookies, MAX_COOKIE_SIZE};
+
+    #[test]
+    fn test_cookies() {
+        let key = Identifier::new("example", "session").unwrap();
+        let mut cookies = Cookies::default();
+        assert_eq!(
+            cookies.receive(key.clone(), None),
+            Err(CookieError::Unexpected(key.clone()))
+        );
+
+        cookies.request(key.clone());
+        assert_eq!(cookies.get(&key), None);
+        cookies.receive(key.clone(), Some(vec![1, 2, 3])).unwrap();
+        assert_eq!(cookies.get(&key), Some(Some(&[1_u8, 2, 3][..])));
+
+        cookies.request(key.clone());
+        assert_eq!(
+            cookies.receive(key.clone(), Some(vec![0; MAX_COOKIE_SIZE + 1])),
+            Err(CookieError::TooLarge(MAX_COOKIE_SIZE + 1))
+        );
+    }
+}
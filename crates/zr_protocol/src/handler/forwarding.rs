@@ -0,0 +1,256 @@
+use std::net::IpAddr;
+
+use rsa::sha2::{Digest, Sha256};
+use serde::Deserialize;
+use uuid::Uuid;
+use zr_binary::{binary::read, varint::VarInt};
+use zr_core::settings::{ForwardingMode, ForwardingSettings};
+
+use crate::packet::login::server::Property;
+
+use super::login_plugin::{LoginPluginChannel, LoginProfile, PluginOutcome};
+
+/// Channel of the Velocity login plugin request
+pub const VELOCITY_CHANNEL: &str = "velocity:player_info";
+/// Version of the Velocity forwarding asked to the proxy, without the chat keys
+const VELOCITY_VERSION: u8 = 1;
+const HMAC_SIZE: usize = 32;
+const HMAC_BLOCK_SIZE: usize = 64;
+
+pub const BUNGEE_DISABLED: &str =
+    "If you wish to use IP forwarding, please enable it in your BungeeCord config as well!";
+pub const VELOCITY_REQUIRED: &str = "This server requires you to connect with Velocity.";
+pub const VELOCITY_INVALID: &str = "Unable to verify player details";
+
+/// How the proxy in front of the server gives the players it connects
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Forwarding {
+    /// the players connect to the server directly
+    #[default]
+    None,
+    /// BungeeCord legacy forwarding, in the server address of the handshake
+    Bungee,
+    /// Velocity modern forwarding, signed with the secret shared with the proxy
+    Velocity { secret: Vec<u8> },
+}
+
+impl From<&ForwardingSettings> for Forwarding {
+    fn from(settings: &ForwardingSettings) -> Self {
+        match settings.mode {
+            ForwardingMode::None => Forwarding::None,
+            ForwardingMode::Bungee => Forwarding::Bungee,
+            ForwardingMode::Velocity => Forwarding::Velocity {
+                secret: settings.secret.as_bytes().to_vec(),
+            },
+        }
+    }
+}
+
+/// Login plugin channel asking Velocity for the player
+pub struct VelocityForwarding {
+    secret: Vec<u8>,
+}
+
+impl VelocityForwarding {
+    pub fn new(secret: Vec<u8>) -> Self {
+        Self { secret }
+    }
+}
+
+impl LoginPluginChannel for VelocityForwarding {
+    fn request(&self, _profile: &LoginProfile) -> Option<Vec<u8>> {
+        Some(vec![VELOCITY_VERSION])
+    }
+
+    fn response(&self, profile: &mut LoginProfile, data: Option<&[u8]>) -> PluginOutcome {
+        let Some(data) = data else {
+            return PluginOutcome::Refuse(VELOCITY_REQUIRED.to_string());
+        };
+        let Some(forwarded) = parse_velocity(&self.secret, data) else {
+            return PluginOutcome::Refuse(VELOCITY_INVALID.to_string());
+        };
+        profile.uuid = forwarded.uuid;
+        if let Some(name) = forwarded.name {
+            profile.name = name;
+        }
+        profile.properties = forwarded.properties;
+        profile.address = Some(forwarded.address);
+        PluginOutcome::Continue
+    }
+}
+
+/// Player as the proxy knows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForwardedPlayer {
+    pub address: IpAddr,
+    pub uuid: Uuid,
+    /// only given by Velocity
+    pub name: Option<String>,
+    /// game profile properties, the skin of the player
+    pub properties: Vec<Property>,
+}
+
+#[derive(Deserialize)]
+struct JsonProperty {
+    name: String,
+    value: String,
+    signature: Option<String>,
+}
+
+impl From<JsonProperty> for Property {
+    fn from(property: JsonProperty) -> Self {
+        Property {
+            name: property.name,
+            value: property.value,
+            is_signed: property.signature.is_some(),
+            signature: property.signature,
+        }
+    }
+}
+
+/// The address the player connects to and the player, from the server address of the handshake
+/// `host\0address\0uuid\0properties`
+pub fn parse_bungee(server_address: &str) -> Option<(String, ForwardedPlayer)> {
+    let mut parts = server_address.split('\0');
+    let host = parts.next()?.to_string();
+    let address = parts.next()?.parse().ok()?;
+    let uuid = Uuid::parse_str(parts.next()?).ok()?;
+    let properties = match parts.next() {
+        Some(json) => serde_json::from_str::<Vec<JsonProperty>>(json)
+            .ok()?
+            .into_iter()
+            .map(Property::from)
+            .collect(),
+        None => Vec::new(),
+    };
+    Some((
+        host,
+        ForwardedPlayer {
+            address,
+            uuid,
+            name: None,
+            properties,
+        },
+    ))
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; HMAC_SIZE] {
+    let mut block = [0_u8; HMAC_BLOCK_SIZE];
+    match key.len() > HMAC_BLOCK_SIZE {
+        true => block[..HMAC_SIZE].copy_from_slice(&Sha256::digest(key)),
+        false => block[..key.len()].copy_from_slice(key),
+    }
+    let inner = Sha256::new()
+        .chain_update(block.map(|byte| byte ^ 0x36))
+        .chain_update(data)
+        .finalize();
+    Sha256::new()
+        .chain_update(block.map(|byte| byte ^ 0x5C))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+/// The player from the answer of Velocity, `None` if it is not signed with `secret`
+pub fn parse_velocity(secret: &[u8], data: &[u8]) -> Option<ForwardedPlayer> {
+    let (signature, payload) = data.split_at_checked(HMAC_SIZE)?;
+    let expected = hmac_sha256(secret, payload);
+    // compare every byte so the time does not tell where they differ
+    if signature
+        .iter()
+        .zip(expected)
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        != 0
+    {
+        return None;
+    }
+    let mut cursor = 0;
+    let version = read::<VarInt<i32>>(payload, &mut cursor).ok()?;
+    if version.0 < VELOCITY_VERSION as i32 {
+        return None;
+    }
+    let address = read::<String>(payload, &mut cursor).ok()?.parse().ok()?;
+    let uuid = read::<Uuid>(payload, &mut cursor).ok()?;
+    let name = read::<String>(payload, &mut cursor).ok()?;
+    let count = read::<VarInt<i32>>(payload, &mut cursor).ok()?;
+    let properties = (0..count.0)
+        .map(|_| read::<Property>(payload, &mut cursor).ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some(ForwardedPlayer {
+        address,
+        uuid,
+        name: Some(name),
+        properties,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use uuid::Uuid;
+    use zr_binary::{binary::Binary, varint::VarInt};
+
+    use super::{hmac_sha256, parse_bungee, parse_velocity};
+    use crate::packet::login::server::Property;
+
+    #[test]
+    fn test_hmac() {
+        // RFC 4231, test case 2
+        let hmac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        let hex = hmac
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        assert_eq!(
+            hex,
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_bungee() {
+        let address = [
+            "play.example.com",
+            "203.0.113.7",
+            "069a79f444e94726a5befca90e38aaf5",
+            r#"[{"name":"textures","value":"skin","signature":"signed"}]"#,
+        ]
+        .join("\0");
+        let (host, player) = parse_bungee(&address).unwrap();
+        assert_eq!(host, "play.example.com");
+        assert_eq!(player.address, IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7)));
+        assert_eq!(
+            player.uuid.to_string(),
+            "069a79f4-44e9-4726-a5be-fca90e38aaf5"
+        );
+        assert_eq!(player.properties[0].signature.as_deref(), Some("signed"));
+        assert!(parse_bungee("play.example.com").is_none());
+    }
+
+    #[test]
+    fn test_velocity() {
+        let uuid = Uuid::from_u128(7);
+        let mut payload = VarInt::new(1).to_binary();
+        payload.extend("203.0.113.7".to_string().to_binary());
+        payload.extend(uuid.to_binary());
+        payload.extend("Alice".to_string().to_binary());
+        payload.extend(VarInt::new(1).to_binary());
+        payload.extend(
+            Property {
+                name: "textures".to_string(),
+                value: "skin".to_string(),
+                ..Default::default()
+            }
+            .to_binary(),
+        );
+        let mut data = hmac_sha256(b"secret", &payload).to_vec();
+        data.extend(&payload);
+
+        let player = parse_velocity(b"secret", &data).unwrap();
+        assert_eq!(player.uuid, uuid);
+        assert_eq!(player.name.as_deref(), Some("Alice"));
+        assert_eq!(player.properties.len(), 1);
+        assert!(parse_velocity(b"other secret", &data).is_none());
+    }
+}
@@ -6,6 +6,8 @@ pub enum Next {
     SendPackets(Vec<Packet>),
     UpdateClient(fn(&mut Client)),
     Disconnect,
+    /// Send a disconnect packet with the reason then close the connection
+    Kick(String),
     Wait,
 }
 
@@ -1,31 +1,111 @@
+use crate::handler::forwarding::{parse_bungee, BUNGEE_DISABLED};
+use crate::packet::config::registry::chat_type::decoration::TextComponent;
 use crate::packet::handshake::Handshake;
+use crate::packet::login;
+use crate::version::{outdated_key, ProtocolVersion, SUPPORTED_RANGE};
 
 use super::handler::Next;
 use super::handler::PacketHandler;
 use super::protocol_handler::Client;
 use super::protocol_handler::State;
+use zr_core::console;
 use zr_network::packet::packet::Packet;
 use zr_network::packet::PacketData;
 
-pub struct HandshakeProtocol;
+/// Intents of the handshake
+const STATUS: i32 = 1;
+const LOGIN: i32 = 2;
+const TRANSFER: i32 = 3;
+
+#[derive(Default)]
+pub struct HandshakeProtocol {
+    accept_transfers: bool,
+    /// BungeeCord gives the players in the server address
+    bungee_forwarding: bool,
+}
+
+impl HandshakeProtocol {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept the players transferred from another server, refused by default like vanilla
+    pub fn set_accept_transfers(&mut self, accept_transfers: bool) {
+        self.accept_transfers = accept_transfers;
+    }
+
+    pub fn set_bungee_forwarding(&mut self, bungee_forwarding: bool) {
+        self.bungee_forwarding = bungee_forwarding;
+    }
+
+    /// Refuse a client joining with a protocol version the server does not speak,
+    /// the status is still sent to any version
+    fn refuse_version(&self, client: &mut Client, protocol_version: i32) -> Next {
+        console::log(format!(
+            "[!] Unsupported protocol version {protocol_version}"
+        ));
+        self.refuse(
+            client,
+            TextComponent::translate(
+                outdated_key(protocol_version),
+                vec![TextComponent::text(SUPPORTED_RANGE)],
+            ),
+        )
+    }
+
+    /// Send `reason` with a login disconnect
+    fn refuse(&self, client: &mut Client, reason: TextComponent) -> Next {
+        let packet = login::server::Disconnect {
+            // the reason is a JSON text component
+            reason: serde_json::to_string(&reason).unwrap_or_default(),
+        }
+        .to_packet();
+        if let Err(err) = client.send_packet(packet) {
+            console::error(format!("Cannot send the disconnect reason: {err:?}"));
+        }
+        Next::Disconnect
+    }
+}
 
 impl PacketHandler for HandshakeProtocol {
-    fn handle_packet(&mut self, _: &mut Client, packet: Packet) -> Next {
+    fn handle_packet(&mut self, client: &mut Client, packet: Packet) -> Next {
         if packet.id() != Handshake::ID {
             return Next::Disconnect;
         }
         if let Ok(Handshake {
-            protocol_version: _,
-            server_address: _,
+            protocol_version,
+            server_address,
             server_port: _,
             next_state,
         }) = Handshake::from_packet(packet)
         {
-            // TODO : check protocol version
+            client.protocol_version = protocol_version.0;
+            client.server_address = server_address;
+            if next_state.0 != STATUS && ProtocolVersion::get(protocol_version.0).is_none() {
+                return self.refuse_version(client, protocol_version.0);
+            }
+            if self.bungee_forwarding {
+                match parse_bungee(&client.server_address) {
+                    Some((host, player)) => {
+                        client.server_address = host;
+                        client.forwarded = Some(player);
+                    }
+                    // the proxy pings the server without the player
+                    None if next_state.0 == STATUS => {}
+                    None => return self.refuse(client, TextComponent::text(BUNGEE_DISABLED)),
+                }
+            }
             match next_state.0 {
-                1 => Next::UpdateClient(|client| client.change_state(State::Status)),
-                2 => Next::UpdateClient(|client| client.change_state(State::Login)),
-                3 => Next::Disconnect,
+                STATUS => Next::UpdateClient(|client| client.change_state(State::Status)),
+                LOGIN => Next::UpdateClient(|client| client.change_state(State::Login)),
+                TRANSFER if self.accept_transfers => Next::UpdateClient(|client| {
+                    client.transferred = true;
+                    client.change_state(State::Login)
+                }),
+                TRANSFER => self.refuse(
+                    client,
+                    TextComponent::translate("multiplayer.disconnect.transfers_disabled", vec![]),
+                ),
                 _ => Next::Disconnect,
             }
         } else {
@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+
+use zr_network::error::network::NetworkError;
+
+/// Time between two keep alives sent to a client
+pub const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Time a client has to answer a keep alive
+pub const KEEP_ALIVE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time a connection has to get through the handshake, the status and the login
+pub const LOGIN_TIMEOUT: Duration = Duration::from_secs(30);
+/// Disconnect reason of the clients that timed out
+pub const TIMED_OUT: &str = "Timed out";
+
+/// Keep alives of a client in configuration or play state, and the latency measured with them
+#[derive(Debug, Clone)]
+pub struct KeepAlive {
+    start: Instant,
+    last_sent: Instant,
+    /// id and time of the keep alive the client has not answered yet
+    pending: Option<(i64, Instant)>,
+    latency: Option<Duration>,
+}
+
+impl KeepAlive {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            start: now,
+            last_sent: now,
+            pending: None,
+            latency: None,
+        }
+    }
+
+    /// Id of the keep alive to send at `now`, if any,
+    /// `NetworkError::TimedOut` when the client did not answer the last one in time
+    pub fn tick(&mut self, now: Instant) -> Result<Option<i64>, NetworkError> {
+        if let Some((_, sent)) = self.pending {
+            return match now.duration_since(sent) >= KEEP_ALIVE_TIMEOUT {
+                true => Err(NetworkError::TimedOut),
+                false => Ok(None),
+            };
+        }
+        if now.duration_since(self.last_sent) < KEEP_ALIVE_INTERVAL {
+            return Ok(None);
+        }
+        // like vanilla, the id is a time in milliseconds
+        let id = now.duration_since(self.start).as_millis() as i64;
+        self.last_sent = now;
+        self.pending = Some((id, now));
+        Ok(Some(id))
+    }
+
+    /// Answer of the client to the keep alive `id`,
+    /// `NetworkError::TimedOut` when it does not match the pending one
+    pub fn receive(&mut self, id: i64, now: Instant) -> Result<(), NetworkError> {
+        match self.pending.take() {
+            Some((pending, sent)) if pending == id => {
+                let round_trip = now.duration_since(sent);
+                // smoothed like vanilla so a single slow answer does not stand out
+                self.latency = Some(match self.latency {
+                    Some(latency) => (latency * 3 + round_trip) / 4,
+                    None => round_trip,
+                });
+                Ok(())
+            }
+            _ => Err(NetworkError::TimedOut),
+        }
+    }
+
+    /// Round trip time in milliseconds, 0 before the first answer
+    pub fn latency(&self) -> i32 {
+        self.latency.map_or(0, |latency| {
+            latency.as_millis().min(i32::MAX as u128) as i32
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use zr_network::error::network::NetworkError;
+
+    use super::{KeepAlive, KEEP_ALIVE_INTERVAL, KEEP_ALIVE_TIMEOUT};
+
+    #[test]
+    fn test_keep_alive() {
+        let start = Instant::now();
+        let mut keep_alive = KeepAlive::new(start);
+        assert!(matches!(keep_alive.tick(start), Ok(None)));
+
+        let sent = start + KEEP_ALIVE_INTERVAL;
+        let id = keep_alive.tick(sent).unwrap().unwrap();
+        // nothing else is sent while the client has not answered
+        assert!(matches!(
+            keep_alive.tick(sent + KEEP_ALIVE_INTERVAL),
+            Ok(None)
+        ));
+        keep_alive
+            .receive(id, sent + Duration::from_millis(100))
+            .unwrap();
+        assert_eq!(keep_alive.latency(), 100);
+
+        let sent = sent + KEEP_ALIVE_INTERVAL;
+        let id = keep_alive.tick(sent).unwrap().unwrap();
+        keep_alive
+            .receive(id, sent + Duration::from_millis(20))
+            .unwrap();
+        assert_eq!(keep_alive.latency(), 80);
+
+        // an answer without a pending keep alive
+        assert!(matches!(
+            keep_alive.receive(id, sent),
+            Err(NetworkError::TimedOut)
+        ));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let start = Instant::now();
+        let mut keep_alive = KeepAlive::new(start);
+        let sent = start + KEEP_ALIVE_INTERVAL;
+        keep_alive.tick(sent).unwrap().unwrap();
+        assert!(matches!(
+            keep_alive.tick(sent + KEEP_ALIVE_TIMEOUT),
+            Err(NetworkError::TimedOut)
+        ));
+    }
+}
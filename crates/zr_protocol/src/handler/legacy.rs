@@ -0,0 +1,184 @@
+use super::status::JsonResponse;
+
+/// Protocol version told to the legacy clients, too recent for all of them like vanilla
+const LEGACY_PROTOCOL: i32 = 127;
+/// First byte of the answer, the kick packet of the legacy protocol
+const KICK: u8 = 0xFF;
+/// Plugin message of the 1.6 ping
+const PING_HOST_CHANNEL: &str = "MC|PingHost";
+
+/// Server list ping of the clients before 1.7
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegacyPing {
+    /// Beta 1.8 to 1.3, only `0xFE`
+    Beta,
+    /// 1.4 and 1.5, `0xFE 0x01`
+    V1_4,
+    /// 1.6, with the address the client connects to
+    V1_6 {
+        protocol_version: u8,
+        server_address: String,
+        server_port: i32,
+    },
+}
+
+/// UTF-16 string prefixed with its length, in code units
+fn read_string(data: &[u8], cursor: &mut usize) -> Option<String> {
+    let length = u16::from_be_bytes(data.get(*cursor..*cursor + 2)?.try_into().ok()?) as usize;
+    let units = data
+        .get(*cursor + 2..*cursor + 2 + length * 2)?
+        .chunks_exact(2)
+        .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+        .collect::<Vec<_>>();
+    *cursor += 2 + length * 2;
+    String::from_utf16(&units).ok()
+}
+
+impl LegacyPing {
+    /// The ping from the bytes sent after `0xFE`
+    pub fn parse(data: &[u8]) -> Self {
+        match data {
+            [] => LegacyPing::Beta,
+            [0x01, 0xFA, ..] => Self::parse_ping_host(&data[2..]).unwrap_or(LegacyPing::V1_4),
+            _ => LegacyPing::V1_4,
+        }
+    }
+
+    /// The `MC|PingHost` plugin message of 1.6
+    fn parse_ping_host(data: &[u8]) -> Option<Self> {
+        let mut cursor = 0;
+        if read_string(data, &mut cursor)? != PING_HOST_CHANNEL {
+            return None;
+        }
+        // length of the rest
+        cursor += 2;
+        let protocol_version = *data.get(cursor)?;
+        cursor += 1;
+        let server_address = read_string(data, &mut cursor)?;
+        let server_port = i32::from_be_bytes(data.get(cursor..cursor + 4)?.try_into().ok()?);
+        Some(LegacyPing::V1_6 {
+            protocol_version,
+            server_address,
+            server_port,
+        })
+    }
+
+    pub fn protocol_version(&self) -> i32 {
+        match self {
+            LegacyPing::V1_6 {
+                protocol_version, ..
+            } => *protocol_version as i32,
+            _ => 0,
+        }
+    }
+
+    pub fn server_address(&self) -> &str {
+        match self {
+            LegacyPing::V1_6 { server_address, .. } => server_address,
+            _ => "",
+        }
+    }
+
+    /// Kick packet holding the status, the client reads it then closes the connection
+    pub fn response(&self, status: &JsonResponse) -> Vec<u8> {
+        let players = &status.players;
+        let text = match self {
+            // `§` separates the fields
+            LegacyPing::Beta => format!(
+                "{}§{}§{}",
+                strip_codes(&status.description.text),
+                players.online,
+                players.max
+            ),
+            LegacyPing::V1_4 | LegacyPing::V1_6 { .. } => format!(
+                "§1\0{LEGACY_PROTOCOL}\0{}\0{}\0{}\0{}",
+                status.version.name, status.description.text, players.online, players.max
+            ),
+        };
+        let units = text.encode_utf16().collect::<Vec<_>>();
+        let mut response = vec![KICK];
+        response.extend((units.len() as u16).to_be_bytes());
+        response.extend(units.into_iter().flat_map(u16::to_be_bytes));
+        response
+    }
+}
+
+/// `text` without its `§` formatting codes, each made of the `§` and the character after it
+fn strip_codes(text: &str) -> String {
+    let mut chars = text.chars();
+    let mut stripped = String::with_capacity(text.len());
+    while let Some(c) = chars.next() {
+        match c {
+            '§' => {
+                chars.next();
+            }
+            c => stripped.push(c),
+        }
+    }
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LegacyPing;
+    use crate::handler::status::{Description, JsonResponse, Players, Version};
+
+    fn utf16(text: &str) -> Vec<u8> {
+        let units = text.encode_utf16().collect::<Vec<_>>();
+        let mut data = (units.len() as u16).to_be_bytes().to_vec();
+        data.extend(units.into_iter().flat_map(u16::to_be_bytes));
+        data
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(LegacyPing::parse(&[]), LegacyPing::Beta);
+        assert_eq!(LegacyPing::parse(&[0x01]), LegacyPing::V1_4);
+
+        let mut data = vec![0x01, 0xFA];
+        data.extend(utf16("MC|PingHost"));
+        let host = utf16("localhost");
+        data.extend((host.len() as u16 + 5).to_be_bytes());
+        data.push(78);
+        data.extend(host);
+        data.extend(25565_i32.to_be_bytes());
+        assert_eq!(
+            LegacyPing::parse(&data),
+            LegacyPing::V1_6 {
+                protocol_version: 78,
+                server_address: "localhost".to_string(),
+                server_port: 25565,
+            }
+        );
+        // cut before the port
+        assert_eq!(LegacyPing::parse(&data[..data.len() - 2]), LegacyPing::V1_4);
+    }
+
+    #[test]
+    fn test_response() {
+        let status = JsonResponse {
+            version: Version {
+                name: "1.21".to_string(),
+                protocol: 767,
+            },
+            players: Players {
+                max: 20,
+                online: 3,
+                sample: vec![],
+            },
+            description: Description {
+                text: "§6A server".to_string(),
+            },
+            ..Default::default()
+        };
+        let mut expected = vec![0xFF];
+        expected.extend(utf16(
+            &["§1", "127", "1.21", "§6A server", "3", "20"].join("\0"),
+        ));
+        assert_eq!(LegacyPing::V1_4.response(&status), expected);
+
+        let mut expected = vec![0xFF];
+        expected.extend(utf16("A server§3§20"));
+        assert_eq!(LegacyPing::Beta.response(&status), expected);
+    }
+}
@@ -1,15 +1,31 @@
 use super::{
+    cookie::on_cookie_response,
+    forwarding::{ForwardedPlayer, Forwarding, VelocityForwarding, VELOCITY_CHANNEL},
     handler::{Next, PacketHandler},
+    login_plugin::{LoginChannels, LoginPluginChannel, LoginProfile, LoginStep},
     protocol_handler::{Client, State},
 };
 use crate::packet::{
-    config::{self, registry::Registry},
+    config::{
+        self,
+        registry::{chat_type::ChatFormatter, Registry},
+    },
     login::{client, server},
 };
 use rsa::{pkcs8::EncodePublicKey, RsaPrivateKey, RsaPublicKey};
+use std::{
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::Instant,
+};
 use zr_binary::varint::VarInt;
-use zr_core::entity::player::Player;
-use zr_nbt::Nbt;
+use zr_core::{
+    access::{self, AccessLists, Denial},
+    console,
+    entity::player::Player,
+    permission::Permissions,
+};
+use zr_data_type::string::Identifier;
 use zr_network::{error::network::NetworkError, packet::PacketData};
 
 const RSA_KEY_BITS: usize = 1024;
@@ -17,23 +33,61 @@ const RSA_KEY_BITS: usize = 1024;
 pub struct LoginProtocol {
     key_pair: (RsaPrivateKey, RsaPublicKey),
     verify_token: Vec<u8>,
+    access: Arc<RwLock<AccessLists>>,
+    /// the operators join even when not whitelisted
+    permissions: Arc<RwLock<Permissions>>,
+    /// login plugin channels the players go through before `LoginSuccess`
+    channels: LoginChannels,
+    /// sent in the registries, their ids are the ones of the chat messages
+    chat_types: Arc<RwLock<ChatFormatter>>,
 }
 
 impl LoginProtocol {
-    pub fn new() -> rsa::Result<Self> {
-        Self::new_with_key_bits(RSA_KEY_BITS)
+    pub fn new(
+        access: Arc<RwLock<AccessLists>>,
+        permissions: Arc<RwLock<Permissions>>,
+        chat_types: Arc<RwLock<ChatFormatter>>,
+    ) -> rsa::Result<Self> {
+        Self::new_with_key_bits(RSA_KEY_BITS, access, permissions, chat_types)
     }
 
-    pub fn new_with_key_bits(key_bits: usize) -> rsa::Result<Self> {
+    pub fn new_with_key_bits(
+        key_bits: usize,
+        access: Arc<RwLock<AccessLists>>,
+        permissions: Arc<RwLock<Permissions>>,
+        chat_types: Arc<RwLock<ChatFormatter>>,
+    ) -> rsa::Result<Self> {
         let mut rng = rand::thread_rng();
         let private = RsaPrivateKey::new(&mut rng, key_bits)?;
         let public = RsaPublicKey::from(private.clone());
         Ok(Self {
             key_pair: (private, public),
             verify_token: b"verify_token".to_vec(),
+            access,
+            permissions,
+            channels: LoginChannels::new(),
+            chat_types,
         })
     }
 
+    /// Take the players from Velocity, BungeeCord gives them in the handshake
+    pub fn set_forwarding(&mut self, forwarding: Forwarding) {
+        let channel = Identifier::from_str(VELOCITY_CHANNEL).unwrap();
+        match forwarding {
+            Forwarding::Velocity { secret } => self
+                .channels
+                .add_first(channel, Box::new(VelocityForwarding::new(secret))),
+            _ => {
+                self.channels.remove(&channel);
+            }
+        }
+    }
+
+    /// Exchange login plugin messages on `channel` with the players logging in, after the other channels
+    pub fn add_channel(&mut self, channel: Identifier, handler: Box<dyn LoginPluginChannel>) {
+        self.channels.add(channel, handler);
+    }
+
     pub fn public_key_der(&self) -> Result<Vec<u8>, rsa::pkcs8::spki::Error> {
         self.key_pair.1.to_public_key_der().map(|d| d.to_vec())
     }
@@ -52,19 +106,61 @@ impl LoginProtocol {
         }
     }
 
+    /// Whether the player may join, checked before `LoginSuccess`
+    fn check_access(&self, client: &Client, player: &Player) -> Result<(), Denial> {
+        let name = player.name();
+        let access = self.access.read().unwrap_or_else(|err| err.into_inner());
+        match access.check(&name, Some(player.uuid()), client.address(), access::now()) {
+            Err(Denial::NotWhitelisted)
+                if self
+                    .permissions
+                    .read()
+                    .is_ok_and(|permissions| permissions.op_level(&name) > 0) =>
+            {
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn deny(&self, client: &mut Client, denial: Denial) -> Next {
+        self.refuse(client, &denial.to_string())
+    }
+
+    fn refuse(&self, client: &mut Client, reason: &str) -> Next {
+        console::log(format!("[!] Login refused: {}", reason.replace('\n', " ")));
+        let packet = server::Disconnect {
+            // the reason is a JSON text component
+            reason: serde_json::Value::from(reason).to_string(),
+        }
+        .to_packet();
+        if let Err(err) = client.send_packet(packet) {
+            console::error(format!("Cannot send the disconnect reason: {err:?}"));
+        }
+        Next::Disconnect
+    }
+
     fn on_login_acknowledge(&self, client: &mut Client) -> Next {
         client.change_state(State::Config);
-        Next::SendPackets(vec![
-            config::server::KnownPacks { known_pack: vec![] }.to_packet(),
-            config::server::RegistryData {
-                // TODO : remove hard json file
-                registry_codec: serde_json::from_str::<Nbt<Registry>>(include_str!(
-                    "/home/zirkonya/Desktop/workspace/rust/minecraft-server/1.20.6.json"
-                ))
-                .unwrap(),
-            }
-            .to_packet(),
-        ])
+        // TODO : remove hard json file
+        let registry = serde_json::from_str::<Registry>(include_str!(
+            "/home/zirkonya/Desktop/workspace/rust/minecraft-server/1.20.6.json"
+        ))
+        .unwrap();
+        let chat_types = self
+            .chat_types
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .registry_data();
+        let mut packets = vec![config::server::KnownPacks { known_pack: vec![] }.to_packet()];
+        packets.extend(
+            registry
+                .registry_data()
+                .into_iter()
+                .map(|data| data.to_packet()),
+        );
+        packets.push(chat_types.to_packet());
+        Next::SendPackets(packets)
     }
 
     fn on_encryption_response(
@@ -86,15 +182,110 @@ impl LoginProtocol {
             server::LoginSuccess {
                 uuid,
                 username,
-                properties: vec![],
+                properties: client.properties.clone(),
                 strict_error_handling: false,
             }
             .to_packet(),
         ))
     }
 
+    /// Let the player of `profile` in unless banned or not whitelisted
+    fn login(&self, client: &mut Client, profile: LoginProfile) -> Next {
+        let LoginProfile {
+            uuid,
+            name,
+            properties,
+            address,
+        } = profile;
+        if let Some(address) = address.filter(|address| client.address() != Some(*address)) {
+            // given by a proxy on its login plugin channel
+            client.forwarded = Some(ForwardedPlayer {
+                address,
+                uuid,
+                name: Some(name.clone()),
+                properties: properties.clone(),
+            });
+        }
+        client.properties = properties;
+        let player = Player::new(uuid, name);
+        if let Err(denial) = self.check_access(client, &player) {
+            return self.deny(client, denial);
+        }
+        client.player = Some(player.clone());
+        self.login_success(client, player)
+    }
+
+    /// Let the player in, disconnect the client if it cannot be written to
+    fn login_success(&self, client: &mut Client, player: Player) -> Next {
+        match self.on_encryption_response(client, player) {
+            Ok(next) => next,
+            Err(err) => {
+                console::error(format!("Cannot finish the login: {err:?}"));
+                Next::Disconnect
+            }
+        }
+    }
+
+    fn step(&self, client: &mut Client, step: LoginStep) -> Next {
+        match step {
+            LoginStep::Request {
+                message_id,
+                channel,
+                data,
+            } => Next::SendPacket(
+                server::LoginPluginRequest {
+                    message_id: VarInt::new(message_id),
+                    channel,
+                    data,
+                }
+                .to_packet(),
+            ),
+            LoginStep::Done(profile) => self.login(client, profile),
+            LoginStep::Refuse(reason) => self.refuse(client, &reason),
+        }
+    }
+
+    fn start_login(&self, client: &mut Client, login_start: client::LoginStart) -> Next {
+        let client::LoginStart { name, player_uuid } = login_start;
+        let mut profile = LoginProfile {
+            uuid: player_uuid,
+            name,
+            properties: Vec::new(),
+            address: client.address(),
+        };
+        if let Some(forwarded) = &client.forwarded {
+            profile.uuid = forwarded.uuid;
+            profile.properties = forwarded.properties.clone();
+        }
+        let step = self
+            .channels
+            .start(&mut client.login_plugins, profile, Instant::now());
+        self.step(client, step)
+    }
+
+    fn on_login_plugin_response(
+        &self,
+        client: &mut Client,
+        response: client::LoginPluginResponse,
+    ) -> Next {
+        let data = response.data.filter(|_| response.successful);
+        let step = self.channels.receive(
+            &mut client.login_plugins,
+            response.message_id.0,
+            data.as_deref(),
+            Instant::now(),
+        );
+        self.step(client, step)
+    }
+
+    /// Go on with the login of a client which did not answer a login plugin request in time
+    pub(crate) fn time_out_plugins(&self, client: &mut Client, now: Instant) -> Option<Next> {
+        let step = self.channels.time_out(&mut client.login_plugins, now)?;
+        Some(self.step(client, step))
+    }
+
     fn wrong_packet_id(&self) -> Next {
-        eprintln!("unknown packet id");
+        console::error("unknown packet id");
         Next::SendPacket(
             server::Disconnect {
                 reason: "No hablo español".to_string(),
@@ -112,20 +303,30 @@ impl PacketHandler for LoginProtocol {
         packet: zr_network::packet::packet::Packet,
     ) -> Next {
         match packet.id() {
-            client::LoginStart::ID =>
-            /*self.on_login_start()*/
-            {
-                let login_start = client::LoginStart::from_packet(packet).unwrap();
-                client.player = Some(Player::new(login_start.player_uuid, login_start.name));
-                self.on_encryption_response(client, client.player().clone().unwrap())
-                    .unwrap()
-            }
+            client::LoginStart::ID => match client::LoginStart::from_packet(packet) {
+                Ok(login_start) => self.start_login(client, login_start),
+                Err(_) => Next::Disconnect,
+            },
             client::EncryptionResponse::ID => {
-                let player = client.player().clone().unwrap();
-                self.on_encryption_response(client, player).unwrap()
+                let Some(player) = client.player().clone() else {
+                    console::error("Encryption response before the login");
+                    return Next::Disconnect;
+                };
+                self.login_success(client, player)
             }
             client::LoginAcknowledge::ID => self.on_login_acknowledge(client),
-            client::LoginPluginResponse::ID => Next::Wait,
+            client::LoginPluginResponse::ID => {
+                match client::LoginPluginResponse::from_packet(packet) {
+                    Ok(response) => self.on_login_plugin_response(client, response),
+                    Err(_) => Next::Disconnect,
+                }
+            }
+            client::CookieResponse::ID => match client::CookieResponse::from_packet(packet) {
+                Ok(client::CookieResponse { key, payload, .. }) => {
+                    on_cookie_response(client, key, payload)
+                }
+                Err(_) => Next::Disconnect,
+            },
             _ => self.wrong_packet_id(),
         }
     }
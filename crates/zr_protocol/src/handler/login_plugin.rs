@@ -0,0 +1,300 @@
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+use zr_data_type::string::Identifier;
+
+use crate::packet::login::server::Property;
+
+/// Time a client has to answer a request, then the channel is taken as not understood
+pub const PLUGIN_TIMEOUT: Duration = Duration::from_secs(10);
+pub const UNEXPECTED_RESPONSE: &str = "Unexpected login plugin response";
+
+/// The player logging in, the channels may change it before `LoginSuccess`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginProfile {
+    pub uuid: Uuid,
+    pub name: String,
+    /// game profile properties, the skin of the player
+    pub properties: Vec<Property>,
+    /// checked against the ban list
+    pub address: Option<IpAddr>,
+}
+
+/// What the login does after an answer of the client
+#[derive(Debug, Clone, PartialEq)]
+pub enum PluginOutcome {
+    /// go on with the next channel, then log the player in
+    Continue,
+    /// send another request on the same channel
+    Request(Vec<u8>),
+    /// disconnect the player with the reason
+    Refuse(String),
+}
+
+/// Channel of the login plugin messages, e.g. a proxy forwarding or a mod handshake
+pub trait LoginPluginChannel: Send + Sync {
+    /// Data of the first request, `None` to skip the channel for this player
+    fn request(&self, profile: &LoginProfile) -> Option<Vec<u8>>;
+
+    /// Answer of the client, `None` when it does not understand the channel or does not answer in time
+    fn response(&self, profile: &mut LoginProfile, data: Option<&[u8]>) -> PluginOutcome;
+}
+
+/// Next step of the login of a player
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoginStep {
+    /// send a Login Plugin Request
+    Request {
+        message_id: i32,
+        channel: Identifier,
+        data: Vec<u8>,
+    },
+    /// every channel is done, log the player in
+    Done(LoginProfile),
+    Refuse(String),
+}
+
+/// Request waiting for the answer of the client
+#[derive(Debug, Clone)]
+struct Pending {
+    message_id: i32,
+    /// index of the channel
+    channel: usize,
+    sent_at: Instant,
+}
+
+/// Login plugin messages exchanged with a client
+#[derive(Debug, Clone, Default)]
+pub struct LoginExchange {
+    profile: Option<LoginProfile>,
+    pending: Option<Pending>,
+    /// unique on the connection
+    next_message_id: i32,
+}
+
+impl LoginExchange {
+    /// Whether the login waits for the answer of the client
+    pub fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+/// Login plugin channels the players go through one after the other, before `LoginSuccess`
+#[derive(Default)]
+pub struct LoginChannels {
+    channels: Vec<(Identifier, Box<dyn LoginPluginChannel>)>,
+}
+
+impl LoginChannels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `handler` after the other channels, replacing the one of `channel` if any
+    pub fn add(&mut self, channel: Identifier, handler: Box<dyn LoginPluginChannel>) {
+        self.remove(&channel);
+        self.channels.push((channel, handler));
+    }
+
+    /// Add `handler` before the other channels, e.g. to know the player forwarded by a proxy first
+    pub fn add_first(&mut self, channel: Identifier, handler: Box<dyn LoginPluginChannel>) {
+        self.remove(&channel);
+        self.channels.insert(0, (channel, handler));
+    }
+
+    pub fn remove(&mut self, channel: &Identifier) -> Option<Box<dyn LoginPluginChannel>> {
+        let index = self
+            .channels
+            .iter()
+            .position(|(known, _)| known == channel)?;
+        Some(self.channels.remove(index).1)
+    }
+
+    /// Request of the first channel from `from` handling the player, `Done` when there is none
+    fn next(&self, exchange: &mut LoginExchange, from: usize, now: Instant) -> LoginStep {
+        let Some(profile) = exchange.profile.take() else {
+            return LoginStep::Refuse(UNEXPECTED_RESPONSE.to_string());
+        };
+        for (index, (channel, handler)) in self.channels.iter().enumerate().skip(from) {
+            if let Some(data) = handler.request(&profile) {
+                exchange.profile = Some(profile);
+                return Self::request(exchange, index, channel, data, now);
+            }
+        }
+        LoginStep::Done(profile)
+    }
+
+    fn request(
+        exchange: &mut LoginExchange,
+        index: usize,
+        channel: &Identifier,
+        data: Vec<u8>,
+        now: Instant,
+    ) -> LoginStep {
+        let message_id = exchange.next_message_id;
+        exchange.next_message_id += 1;
+        exchange.pending = Some(Pending {
+            message_id,
+            channel: index,
+            sent_at: now,
+        });
+        LoginStep::Request {
+            message_id,
+            channel: channel.clone(),
+            data,
+        }
+    }
+
+    /// Start the login of `profile` with the first channel
+    pub fn start(
+        &self,
+        exchange: &mut LoginExchange,
+        profile: LoginProfile,
+        now: Instant,
+    ) -> LoginStep {
+        exchange.profile = Some(profile);
+        exchange.pending = None;
+        self.next(exchange, 0, now)
+    }
+
+    /// Answer of the client to the pending request, `data` is `None` when it does not understand it
+    pub fn receive(
+        &self,
+        exchange: &mut LoginExchange,
+        message_id: i32,
+        data: Option<&[u8]>,
+        now: Instant,
+    ) -> LoginStep {
+        let Some(pending) = exchange
+            .pending
+            .take_if(|pending| pending.message_id == message_id)
+        else {
+            return LoginStep::Refuse(UNEXPECTED_RESPONSE.to_string());
+        };
+        let (Some(profile), Some((channel, handler))) = (
+            exchange.profile.as_mut(),
+            self.channels.get(pending.channel),
+        ) else {
+            return LoginStep::Refuse(UNEXPECTED_RESPONSE.to_string());
+        };
+        match handler.response(profile, data) {
+            PluginOutcome::Continue => self.next(exchange, pending.channel + 1, now),
+            PluginOutcome::Request(data) => {
+                Self::request(exchange, pending.channel, channel, data, now)
+            }
+            PluginOutcome::Refuse(reason) => LoginStep::Refuse(reason),
+        }
+    }
+
+    /// The pending request taken as not understood when the client does not answer in time
+    pub fn time_out(&self, exchange: &mut LoginExchange, now: Instant) -> Option<LoginStep> {
+        let pending = exchange.pending.as_ref()?;
+        if now.duration_since(pending.sent_at) < PLUGIN_TIMEOUT {
+            return None;
+        }
+        Some(self.receive(exchange, pending.message_id, None, now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use uuid::Uuid;
+    use zr_data_type::string::Identifier;
+
+    use super::{
+        LoginChannels, LoginExchange, LoginPluginChannel, LoginProfile, LoginStep, PluginOutcome,
+        PLUGIN_TIMEOUT,
+    };
+
+    /// Asks twice then renames the player after the answer
+    struct Rename;
+
+    impl LoginPluginChannel for Rename {
+        fn request(&self, _profile: &LoginProfile) -> Option<Vec<u8>> {
+            Some(vec![0])
+        }
+
+        fn response(&self, profile: &mut LoginProfile, data: Option<&[u8]>) -> PluginOutcome {
+            match data {
+                Some([0]) => PluginOutcome::Request(vec![1]),
+                Some(name) => {
+                    profile.name = String::from_utf8_lossy(name).to_string();
+                    PluginOutcome::Continue
+                }
+                None => PluginOutcome::Refuse("Mod required".to_string()),
+            }
+        }
+    }
+
+    fn profile() -> LoginProfile {
+        LoginProfile {
+            uuid: Uuid::from_u128(1),
+            name: "Alice".to_string(),
+            properties: vec![],
+            address: None,
+        }
+    }
+
+    fn message_id(step: &LoginStep) -> i32 {
+        match step {
+            LoginStep::Request { message_id, .. } => *message_id,
+            step => panic!("not a request {step:?}"),
+        }
+    }
+
+    #[test]
+    fn test_exchange() {
+        let now = Instant::now();
+        let channels = LoginChannels::new();
+        let mut exchange = LoginExchange::default();
+        assert_eq!(
+            channels.start(&mut exchange, profile(), now),
+            LoginStep::Done(profile())
+        );
+
+        let mut channels = LoginChannels::new();
+        channels.add(
+            Identifier::new("example", "rename").unwrap(),
+            Box::new(Rename),
+        );
+        let first = message_id(&channels.start(&mut exchange, profile(), now));
+        let second = message_id(&channels.receive(&mut exchange, first, Some(&[0]), now));
+        assert_ne!(first, second);
+        assert_eq!(
+            channels.receive(&mut exchange, first, Some(b"Bob"), now),
+            LoginStep::Refuse(super::UNEXPECTED_RESPONSE.to_string())
+        );
+
+        let second = message_id(&channels.start(&mut exchange, profile(), now));
+        let third = message_id(&channels.receive(&mut exchange, second, Some(&[0]), now));
+        let LoginStep::Done(renamed) = channels.receive(&mut exchange, third, Some(b"Bob"), now)
+        else {
+            panic!("not done");
+        };
+        assert_eq!(renamed.name, "Bob");
+    }
+
+    #[test]
+    fn test_time_out() {
+        let now = Instant::now();
+        let mut channels = LoginChannels::new();
+        channels.add(
+            Identifier::new("example", "rename").unwrap(),
+            Box::new(Rename),
+        );
+        let mut exchange = LoginExchange::default();
+        channels.start(&mut exchange, profile(), now);
+        assert!(exchange.is_pending());
+        assert_eq!(channels.time_out(&mut exchange, now), None);
+        assert_eq!(
+            channels.time_out(&mut exchange, now + PLUGIN_TIMEOUT),
+            Some(LoginStep::Refuse("Mod required".to_string()))
+        );
+        assert!(!exchange.is_pending());
+    }
+}
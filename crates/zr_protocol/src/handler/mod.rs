@@ -1,8 +1,15 @@
+pub mod chat;
 pub mod config;
+pub mod cookie;
+pub mod forwarding;
 pub mod handler;
 pub mod handshake;
+pub mod keep_alive;
+pub mod legacy;
 pub mod login;
+pub mod login_plugin;
 pub mod play;
 pub mod protocol_handler;
+pub mod server;
 pub mod status;
-
+pub mod tab_list;
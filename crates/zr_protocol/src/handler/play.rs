@@ -0,0 +1,619 @@
+use std::sync::{mpsc::Sender, Arc, Mutex, RwLock};
+
+use rsa::sha2::{Digest, Sha256};
+use zr_binary::varint::VarInt;
+use zr_core::{
+    command::{CommandSource, Dispatcher},
+    entity::{player::GameMode, tracker::EntityUpdate, Entity, EntityType},
+    permission::Permissions,
+    world::Weather,
+};
+use zr_nbt::{string::NbtString, Nbt};
+use zr_network::packet::{packet::Packet, PacketData};
+
+use crate::packet::{
+    config::registry::chat_type::decoration::TextComponent,
+    play::{
+        client::{self, MessageSignature},
+        server,
+    },
+};
+
+use super::{
+    chat::{ChatError, ChatSession, PlayerMessage},
+    cookie::on_cookie_response,
+    handler::{Next, PacketHandler},
+    keep_alive::TIMED_OUT,
+    protocol_handler::Client,
+    server::Action,
+};
+
+/// The only dimension of the server
+const OVERWORLD: &str = "minecraft:overworld";
+/// Id of the overworld in the dimension type registry, the first one by name
+const OVERWORLD_TYPE: i32 = 0;
+
+/// Events of the Game Event packet
+pub mod game_event {
+    pub const END_RAINING: u8 = 1;
+    pub const BEGIN_RAINING: u8 = 2;
+    pub const CHANGE_GAME_MODE: u8 = 3;
+    pub const RAIN_LEVEL_CHANGE: u8 = 7;
+    pub const THUNDER_LEVEL_CHANGE: u8 = 8;
+}
+
+/// Entity statuses of the Entity Event packet
+pub mod entity_status {
+    /// statuses 24 to 28 give the operator level 0 to 4 of the player
+    pub const OP_LEVEL_0: u8 = 24;
+}
+
+/// A player running a command, its feedback is sent back as system messages
+struct PlayerSource<'a> {
+    name: String,
+    permissions: &'a RwLock<Permissions>,
+    messages: Mutex<Vec<String>>,
+}
+
+impl<'a> PlayerSource<'a> {
+    fn new(client: &Client, permissions: &'a RwLock<Permissions>) -> Self {
+        Self {
+            name: client.player_name().unwrap_or_default(),
+            permissions,
+            messages: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn into_packets(self) -> Vec<Packet> {
+        self.messages
+            .into_inner()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|message| system_chat_packet(TextComponent::text(&message)))
+            .collect()
+    }
+}
+
+impl CommandSource for PlayerSource<'_> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn send_message(&self, message: &str) {
+        if let Ok(mut messages) = self.messages.lock() {
+            messages.push(message.to_string());
+        }
+    }
+
+    fn op_level(&self) -> u8 {
+        self.permissions
+            .read()
+            .map_or(0, |permissions| permissions.op_level(&self.name))
+    }
+
+    fn permission(&self, node: &str) -> Option<bool> {
+        self.permissions
+            .read()
+            .ok()
+            .and_then(|permissions| permissions.permission(&self.name, node))
+    }
+}
+
+/// Commands, their completion and the chat, the rest of the play state is handled elsewhere
+pub struct PlayProtocol {
+    commands: Arc<Mutex<Dispatcher>>,
+    permissions: Arc<RwLock<Permissions>>,
+    /// the accepted messages are broadcast by the protocol handler
+    actions: Sender<Action>,
+    /// the chat messages must be signed by the players
+    secure_chat: bool,
+}
+
+impl PlayProtocol {
+    pub fn new(
+        commands: Arc<Mutex<Dispatcher>>,
+        permissions: Arc<RwLock<Permissions>>,
+        actions: Sender<Action>,
+    ) -> Self {
+        Self {
+            commands,
+            permissions,
+            actions,
+            secure_chat: false,
+        }
+    }
+
+    pub fn is_secure_chat(&self) -> bool {
+        self.secure_chat
+    }
+
+    pub fn set_secure_chat(&mut self, secure_chat: bool) {
+        self.secure_chat = secure_chat;
+    }
+
+    pub fn commands(&self) -> Arc<Mutex<Dispatcher>> {
+        self.commands.clone()
+    }
+
+    /// The command tree the client can use, sent on join and when its permissions change
+    pub fn commands_packet(&self, client: &Client) -> Option<Packet> {
+        let commands = self.commands.lock().ok()?;
+        let (nodes, root_index) = commands.serialize(&PlayerSource::new(client, &self.permissions));
+        Some(
+            server::Commands {
+                nodes,
+                root_index: VarInt::new(root_index),
+            }
+            .to_packet(),
+        )
+    }
+
+    fn on_chat_command(&self, client: &Client, command: &str) -> Next {
+        let source = PlayerSource::new(client, &self.permissions);
+        match self.commands.lock() {
+            Ok(commands) => {
+                if let Err(err) = commands.execute(&source, command) {
+                    source.send_message(&err.to_string());
+                }
+            }
+            Err(_) => return Next::Wait,
+        }
+        Next::SendPackets(source.into_packets())
+    }
+
+    /// Drop the message with a notice, or kick the player when the error breaks its chain
+    fn refuse_chat(&self, client: &Client, err: ChatError) -> Next {
+        if !err.disconnects() {
+            return Next::SendPacket(system_chat_packet(TextComponent::text(&err.to_string())));
+        }
+        if let Some(player) = client.player_name() {
+            // the protocol handler is gone once the server stops
+            let _ = self.actions.send(Action::Kick {
+                player,
+                reason: err.to_string(),
+            });
+        }
+        Next::Wait
+    }
+
+    fn on_chat_message(&self, client: &mut Client, packet: client::ChatMessage) -> Next {
+        let Some((sender, name)) = client
+            .player()
+            .as_ref()
+            .map(|player| (player.uuid(), player.name()))
+        else {
+            return Next::Disconnect;
+        };
+        let accepted = client.chat.accept(
+            sender,
+            packet.message,
+            packet.timestamp,
+            packet.salt,
+            packet.signature,
+            packet.message_count.0,
+            packet.acknowledged,
+            self.secure_chat,
+        );
+        match accepted {
+            Ok(message) => {
+                let _ = self.actions.send(Action::Chat {
+                    sender: name,
+                    message: Box::new(message),
+                });
+                Next::Wait
+            }
+            Err(err) => self.refuse_chat(client, err),
+        }
+    }
+
+    fn on_player_session(&self, client: &mut Client, packet: client::PlayerSession) -> Next {
+        match ChatSession::new(
+            packet.session_id,
+            packet.expires_at,
+            &packet.public_key,
+            &packet.key_signature,
+        ) {
+            Ok(session) => {
+                client.chat.set_session(session);
+                Next::Wait
+            }
+            Err(err) => self.refuse_chat(client, err),
+        }
+    }
+
+    fn on_suggestions_request(&self, client: &Client, transaction_id: i32, text: &str) -> Next {
+        let (prefix, command) = match text.strip_prefix('/') {
+            Some(command) => (1, command),
+            None => (0, text),
+        };
+        let Ok(commands) = self.commands.lock() else {
+            return Next::Wait;
+        };
+        let suggestions = commands.suggest(&PlayerSource::new(client, &self.permissions), command);
+        let start = prefix + suggestions.start;
+        Next::SendPacket(
+            server::CommandSuggestionsResponse {
+                id: VarInt::new(transaction_id),
+                start: VarInt::new(start as i32),
+                length: VarInt::new((text.len() - start) as i32),
+                matches: suggestions
+                    .matches
+                    .into_iter()
+                    .map(|text| server::CommandSuggestion {
+                        text,
+                        has_tooltip: false,
+                        tooltip: None,
+                    })
+                    .collect(),
+            }
+            .to_packet(),
+        )
+    }
+}
+
+impl PacketHandler for PlayProtocol {
+    fn handle_packet(&mut self, client: &mut Client, packet: Packet) -> Next {
+        match packet.id() {
+            client::AcknowledgeMessage::ID => match client::AcknowledgeMessage::from_packet(packet)
+            {
+                Ok(client::AcknowledgeMessage { message_count }) => {
+                    match client.chat.last_seen_mut().apply_offset(message_count.0) {
+                        Ok(()) => Next::Wait,
+                        Err(err) => self.refuse_chat(client, err),
+                    }
+                }
+                Err(_) => Next::Disconnect,
+            },
+            client::ChatCommand::ID => match client::ChatCommand::from_packet(packet) {
+                Ok(client::ChatCommand { command }) => self.on_chat_command(client, &command),
+                Err(_) => Next::Disconnect,
+            },
+            // the arguments are not signed messages for the vanilla commands of this server
+            client::SignedChatCommand::ID => match client::SignedChatCommand::from_packet(packet) {
+                Ok(client::SignedChatCommand {
+                    command,
+                    message_count,
+                    acknowledged,
+                    ..
+                }) => match client.chat.acknowledge(message_count.0, acknowledged) {
+                    Ok(()) => self.on_chat_command(client, &command),
+                    Err(err) => self.refuse_chat(client, err),
+                },
+                Err(_) => Next::Disconnect,
+            },
+            client::ChatMessage::ID => match client::ChatMessage::from_packet(packet) {
+                Ok(packet) => self.on_chat_message(client, packet),
+                Err(_) => Next::Disconnect,
+            },
+            client::PlayerSession::ID => match client::PlayerSession::from_packet(packet) {
+                Ok(packet) => self.on_player_session(client, packet),
+                Err(_) => Next::Disconnect,
+            },
+            client::CookieResponse::ID => match client::CookieResponse::from_packet(packet) {
+                Ok(client::CookieResponse { key, payload, .. }) => {
+                    on_cookie_response(client, key, payload)
+                }
+                Err(_) => Next::Disconnect,
+            },
+            client::KeepAlive::ID => match client::KeepAlive::from_packet(packet) {
+                Ok(client::KeepAlive { keep_alive_id }) => {
+                    match client.on_keep_alive(keep_alive_id) {
+                        Ok(()) => Next::Wait,
+                        Err(_) => Next::Kick(TIMED_OUT.to_string()),
+                    }
+                }
+                Err(_) => Next::Disconnect,
+            },
+            client::CommandSuggestionsRequest::ID => {
+                match client::CommandSuggestionsRequest::from_packet(packet) {
+                    Ok(client::CommandSuggestionsRequest {
+                        transaction_id,
+                        text,
+                    }) => self.on_suggestions_request(client, transaction_id.0, &text),
+                    Err(_) => Next::Disconnect,
+                }
+            }
+            _ => Next::Wait,
+        }
+    }
+}
+
+/// Ids of the packets moving the player
+pub const MOVEMENTS: [i32; 4] = [
+    client::SetPlayerPosition::ID,
+    client::SetPlayerPositionAndRotation::ID,
+    client::SetPlayerRotation::ID,
+    client::SetPlayerOnGround::ID,
+];
+
+/// Move the entity of the player as its client tells, there is no entity before the join
+pub fn on_movement(entity: Option<&mut Entity>, packet: Packet) -> Next {
+    let Some(entity) = entity else {
+        return Next::Wait;
+    };
+    let (position, rotation, on_ground) = match packet.id() {
+        client::SetPlayerPosition::ID => match client::SetPlayerPosition::from_packet(packet) {
+            Ok(packet) => (Some(packet.coordinate), None, packet.on_ground),
+            Err(_) => return Next::Disconnect,
+        },
+        client::SetPlayerPositionAndRotation::ID => {
+            match client::SetPlayerPositionAndRotation::from_packet(packet) {
+                Ok(packet) => (
+                    Some(packet.coordinate),
+                    Some((packet.yaw, packet.pitch)),
+                    packet.on_ground,
+                ),
+                Err(_) => return Next::Disconnect,
+            }
+        }
+        client::SetPlayerRotation::ID => match client::SetPlayerRotation::from_packet(packet) {
+            Ok(packet) => (None, Some((packet.yaw, packet.pitch)), packet.on_ground),
+            Err(_) => return Next::Disconnect,
+        },
+        client::SetPlayerOnGround::ID => match client::SetPlayerOnGround::from_packet(packet) {
+            Ok(packet) => (None, None, packet.on_ground),
+            Err(_) => return Next::Disconnect,
+        },
+        _ => return Next::Wait,
+    };
+    // the viewers get a delta, not a teleport
+    if let Some(position) = position {
+        entity.set_position(position);
+    }
+    if let Some((yaw, pitch)) = rotation {
+        entity.set_rotation(yaw, pitch);
+        entity.set_head_yaw(yaw);
+    }
+    entity.set_on_ground(on_ground);
+    Next::Wait
+}
+
+/// Packets setting the weather of a player
+pub fn weather_packets(weather: Weather) -> Vec<Packet> {
+    let level = |on: bool| if on { 1.0 } else { 0.0 };
+    let event = match weather.is_raining() {
+        true => game_event::BEGIN_RAINING,
+        false => game_event::END_RAINING,
+    };
+    [
+        (event, 0.0),
+        (game_event::RAIN_LEVEL_CHANGE, level(weather.is_raining())),
+        (
+            game_event::THUNDER_LEVEL_CHANGE,
+            level(weather.is_thundering()),
+        ),
+    ]
+    .into_iter()
+    .map(|(event, value)| server::GameEvent { event, value }.to_packet())
+    .collect()
+}
+
+/// Message of the server, already decorated
+pub fn system_chat_packet(content: TextComponent) -> Packet {
+    server::SystemChatMessage {
+        content: Nbt::new(content),
+        overlay: false,
+    }
+    .to_packet()
+}
+
+/// Chat message of a player as seen by a recipient, signed in secure mode.
+/// The client decorates it with the chat type of id `chat_type`
+pub fn chat_packet(sender_name: &str, message: &PlayerMessage, chat_type: i32) -> Packet {
+    let chat_type = VarInt::new(chat_type + 1);
+    let sender_name = NbtString::new(sender_name);
+    match message.signature {
+        Some(signature) => server::PlayerChatMessage {
+            sender: message.sender,
+            index: VarInt::new(message.index),
+            has_signature: true,
+            signature: Some(signature),
+            message: message.message.clone(),
+            timestamp: message.timestamp,
+            salt: message.salt,
+            previous_messages: message
+                .last_seen
+                .iter()
+                .map(|signature| server::PreviousMessage {
+                    message_id: VarInt::new(0),
+                    signature: Some(*signature),
+                })
+                .collect(),
+            has_unsigned_content: false,
+            unsigned_content: None,
+            filter_type: VarInt::new(0),
+            chat_type,
+            sender_name,
+            has_target_name: false,
+            target_name: None,
+        }
+        .to_packet(),
+        None => server::DisguisedChatMessage {
+            message: NbtString::new(&message.message),
+            chat_type,
+            sender_name,
+            has_target_name: false,
+            target_name: None,
+        }
+        .to_packet(),
+    }
+}
+
+/// Remove a signed message from the chat of a player
+pub fn delete_message_packet(signature: MessageSignature) -> Packet {
+    server::DeleteMessage {
+        message_id: VarInt::new(0),
+        signature: Some(signature),
+    }
+    .to_packet()
+}
+
+/// Operator level of the player, the client unlocks some features with it
+pub fn op_level_packet(entity_id: i32, level: u8) -> Packet {
+    server::EntityEvent {
+        entity_id,
+        entity_statue: entity_status::OP_LEVEL_0 + level,
+    }
+    .to_packet()
+}
+
+/// First packet of the play state, the player joins the overworld as the entity `entity_id`
+pub fn login_packet(
+    entity_id: i32,
+    game_mode: GameMode,
+    seed: i64,
+    max_players: usize,
+    view_distance: u8,
+    secure_chat: bool,
+) -> Packet {
+    server::Login {
+        entity_id,
+        is_hardcore: false,
+        dimension_names: vec![OVERWORLD.to_string()],
+        max_players: VarInt::new(max_players as i32),
+        view_distance: VarInt::new(view_distance as i32),
+        simulation_distance: VarInt::new(view_distance as i32),
+        reduced_debug_info: false,
+        enable_respawn_screen: true,
+        do_limited_crafting: false,
+        dimension_type: VarInt::new(OVERWORLD_TYPE),
+        dimension_name: OVERWORLD.to_string(),
+        hashed_seed: hashed_seed(seed),
+        game_mode: game_mode.id(),
+        // none
+        previous_game_mode: -1,
+        is_debug: false,
+        is_flat: false,
+        has_death_location: false,
+        death_dimension_name: None,
+        death_location: None,
+        portal_cooldown: VarInt::new(0),
+        enforces_secure_chat: secure_chat,
+    }
+    .to_packet()
+}
+
+/// First 8 bytes of the SHA-256 of the seed, the client uses it for the biome noise
+fn hashed_seed(seed: i64) -> i64 {
+    let hash = Sha256::digest(seed.to_le_bytes());
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    i64::from_le_bytes(bytes)
+}
+
+/// Packets telling a player about an entity update
+pub fn entity_packets(update: EntityUpdate) -> Vec<Packet> {
+    match update {
+        EntityUpdate::Spawn {
+            id,
+            entity_type: EntityType::ExperienceOrb,
+            position,
+            data,
+            ..
+        } => vec![server::SpawnExperienceOrb {
+            entity_id: VarInt::new(id),
+            coordinate: position,
+            count: data as i16,
+        }
+        .to_packet()],
+        EntityUpdate::Spawn {
+            id,
+            uuid,
+            entity_type,
+            position,
+            yaw,
+            pitch,
+            head_yaw,
+            data,
+            velocity,
+            metadata,
+        } => {
+            let mut packets = vec![server::SpawnEntity {
+                entity_id: VarInt::new(id),
+                entity_uuid: uuid,
+                entity_type: VarInt::from(entity_type.id()),
+                coordinate: position,
+                pitch,
+                yaw,
+                head_yaw,
+                data: VarInt::new(data),
+                velocity,
+            }
+            .to_packet()];
+            if !metadata.is_empty() {
+                packets.push(
+                    server::SetEntityMetadata {
+                        entity_id: VarInt::new(id),
+                        metadata,
+                    }
+                    .to_packet(),
+                );
+            }
+            packets
+        }
+        EntityUpdate::Remove(ids) => vec![server::RemoveEntities {
+            entity_ids: ids.into_iter().map(VarInt::new).collect(),
+        }
+        .to_packet()],
+        EntityUpdate::Move {
+            id,
+            delta,
+            on_ground,
+        } => vec![server::UpdateEntityPosition {
+            entity_id: VarInt::new(id),
+            delta,
+            on_ground,
+        }
+        .to_packet()],
+        EntityUpdate::MoveRotate {
+            id,
+            delta,
+            yaw,
+            pitch,
+            on_ground,
+        } => vec![server::UpdateEntityPositionAndRotation {
+            entity_id: VarInt::new(id),
+            delta,
+            yaw,
+            pitch,
+            on_ground,
+        }
+        .to_packet()],
+        EntityUpdate::Rotate {
+            id,
+            yaw,
+            pitch,
+            on_ground,
+        } => vec![server::UpdateEntityRotation {
+            entity_id: VarInt::new(id),
+            yaw,
+            pitch,
+            on_ground,
+        }
+        .to_packet()],
+        EntityUpdate::Teleport {
+            id,
+            position,
+            yaw,
+            pitch,
+            on_ground,
+        } => vec![server::TeleportEntity {
+            entity_id: VarInt::new(id),
+            coordinate: position,
+            yaw,
+            pitch,
+            on_ground,
+        }
+        .to_packet()],
+        EntityUpdate::HeadRotation { id, head_yaw } => vec![server::SetHeadRotation {
+            entity_id: VarInt::new(id),
+            head_yaw,
+        }
+        .to_packet()],
+        EntityUpdate::Metadata { id, metadata } => vec![server::SetEntityMetadata {
+            entity_id: VarInt::new(id),
+            metadata,
+        }
+        .to_packet()],
+    }
+}
@@ -1,19 +1,92 @@
 use std::{
     collections::HashMap,
-    sync::{mpsc::Receiver, Arc, RwLock},
+    net::IpAddr,
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex, RwLock,
+    },
+    time::Instant,
+};
+use uuid::Uuid;
+use zr_binary::varint::VarInt;
+use zr_core::{
+    access::AccessLists,
+    command::Dispatcher,
+    console,
+    entity::player::{GameMode, Player},
+    entity::{
+        store::EntityStore,
+        tracker::{EntityTracker, EntityUpdate},
+        EntityType,
+    },
+    handler::Handler,
+    item::inventory::{Inventory, HOTBAR_SIZE},
+    permission::Permissions,
+    save::{PlayerData, WorldData, WorldSave},
+    scheduler::{Scheduler, SchedulerHandle},
+    tick::{Tickable, TICK_DURATION},
+    user_cache::UserCache,
+    world::Weather,
+};
+use zr_data_type::{string::Identifier, vector::Vector3};
+use zr_nbt::Nbt;
+use zr_network::{
+    client::client::LEGACY_PING,
+    error::network::NetworkError,
+    packet::{packet::Packet, PacketData},
 };
-use zr_core::{entity::player::Player, handler::Handler};
-use zr_network::{error::network::NetworkError, packet::packet::Packet};
+
+use crate::packet::{
+    config::{
+        self,
+        registry::chat_type::{self, decoration::TextComponent, ChatFormatter},
+    },
+    login::{self, server::Property},
+    play,
+    play::client::MessageSignature,
+};
+use crate::version::{ProtocolVersion, PROTOCOL_VERSION, VERSION_NAME};
 
 use super::{
+    chat::{ChatSession, ChatState},
     config::ConfigProtocol,
+    cookie::{check_size, CookieError, Cookies},
+    forwarding::{ForwardedPlayer, Forwarding},
     handler::{Next, PacketHandler},
     handshake::HandshakeProtocol,
+    keep_alive::{KeepAlive, LOGIN_TIMEOUT, TIMED_OUT},
+    legacy::LegacyPing,
     login::LoginProtocol,
-    status::{Description, JsonResponse, Players, StatusProtocol, Version},
+    login_plugin::{LoginExchange, LoginPluginChannel},
+    play::{
+        chat_packet, delete_message_packet, entity_packets, game_event, login_packet, on_movement,
+        op_level_packet, system_chat_packet, weather_packets, PlayProtocol, MOVEMENTS,
+    },
+    server::{Action, OnlinePlayer, ServerHandle, ServerState},
+    status::{
+        Description, JsonResponse, Players, StatusContext, StatusHook, StatusProtocol, Version,
+    },
+    tab_list::{TabEntry, TabList},
+};
+
+/// Entity view distance, in chunks
+const VIEW_DISTANCE: u8 = 10;
+/// Where the players without a saved position join
+const SPAWN_POSITION: Vector3<f64> = Vector3 {
+    x: 0.5,
+    y: 64.0,
+    z: 0.5,
 };
+/// Threads running the async scheduled tasks
+const ASYNC_THREADS: usize = 4;
+/// Ticks between two time updates sent to the players
+const TIME_UPDATE_INTERVAL: i64 = 20;
+/// Window id of the player inventory
+const PLAYER_WINDOW: u8 = 0;
+/// Window slot of the first inventory slot above the hotbar
+const HOTBAR_WINDOW_SLOT: usize = 36;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
     Handshake,
     Status,
@@ -26,35 +99,263 @@ pub enum State {
 pub struct Client {
     client: zr_network::client::client::Client,
     pub(crate) player: Option<Player>,
+    /// entity of the player once in play state
+    pub(crate) entity_id: Option<i32>,
+    pub(crate) game_mode: GameMode,
+    pub(crate) inventory: Inventory,
+    /// the saved data of the player was applied once in play state
+    pub(crate) loaded: bool,
+    pub(crate) chat: ChatState,
+    /// game profile properties given at login, the skin of the player
+    pub(crate) properties: Vec<Property>,
+    /// given in the handshake
+    pub(crate) protocol_version: i32,
+    pub(crate) server_address: String,
+    /// connected with a transfer from another server
+    pub(crate) transferred: bool,
+    pub(crate) cookies: Cookies,
+    /// the player as the proxy in front of the server knows it
+    pub(crate) forwarded: Option<ForwardedPlayer>,
+    pub(crate) login_plugins: LoginExchange,
     state: State,
+    connected_at: Instant,
+    keep_alive: KeepAlive,
+    /// packets sent at the end of the tick
+    outgoing: Vec<Packet>,
 }
 
 impl Client {
     fn new(client: zr_network::client::client::Client) -> Self {
+        let now = Instant::now();
         Client {
             client,
             player: None,
+            entity_id: None,
+            game_mode: GameMode::default(),
+            inventory: Inventory::default(),
+            loaded: false,
+            chat: ChatState::default(),
+            properties: Vec::new(),
+            protocol_version: 0,
+            server_address: String::new(),
+            transferred: false,
+            cookies: Cookies::default(),
+            forwarded: None,
+            login_plugins: LoginExchange::default(),
             state: State::Handshake,
+            connected_at: now,
+            keep_alive: KeepAlive::new(now),
+            outgoing: Vec::new(),
         }
     }
 
     pub fn send_packet(&mut self, packet: Packet) -> Result<(), NetworkError> {
+        let packet = match ProtocolVersion::get(self.protocol_version) {
+            Some(version) => version.clientbound(self.state, packet),
+            None => packet,
+        };
         self.client.write_packet(packet)
     }
 
+    /// The packet sent by the client with the id known by the server
+    fn map_incoming(&self, packet: Packet) -> Packet {
+        match ProtocolVersion::get(self.protocol_version) {
+            Some(version) => version.serverbound(self.state, packet),
+            None => packet,
+        }
+    }
+
+    /// Send the packet with the other packets of the tick
+    pub fn queue_packet(&mut self, packet: Packet) {
+        self.outgoing.push(packet);
+    }
+
+    pub fn flush(&mut self) -> Result<(), NetworkError> {
+        for packet in std::mem::take(&mut self.outgoing) {
+            self.send_packet(packet)?;
+        }
+        Ok(())
+    }
+
+    /// Queue the content of the inventory slot `index`
+    fn queue_slot(&mut self, index: usize) {
+        // the hotbar is at the end of the player window
+        let slot = match index < HOTBAR_SIZE {
+            true => HOTBAR_WINDOW_SLOT + index,
+            false => index,
+        };
+        let slot_data = self.inventory.slot(index).cloned().unwrap_or_default();
+        self.queue_packet(
+            play::server::SetContainerSlot {
+                windows_id: PLAYER_WINDOW,
+                state_id: VarInt::new(0),
+                slot: slot as i16,
+                slot_data,
+            }
+            .to_packet(),
+        );
+    }
+
+    /// Send the pending packets then a disconnect packet with `reason` and close the connection
+    pub fn disconnect(&mut self, reason: &str) -> Result<(), NetworkError> {
+        // a JSON text component at login, an NBT one afterwards
+        let packet = match self.state {
+            State::Login => Some(
+                login::server::Disconnect {
+                    reason: serde_json::Value::from(reason).to_string(),
+                }
+                .to_packet(),
+            ),
+            State::Config => Some(
+                config::server::Disconnect {
+                    reason: Nbt::new(TextComponent::text(reason)),
+                }
+                .to_packet(),
+            ),
+            State::Play => Some(
+                play::server::Disconnect {
+                    reason: Nbt::new(TextComponent::text(reason)),
+                }
+                .to_packet(),
+            ),
+            // no disconnect packet before login
+            State::Handshake | State::Status => None,
+        };
+        self.flush()?;
+        if let Some(packet) = packet {
+            self.send_packet(packet)?;
+        }
+        self.client.shutdown().map_err(NetworkError::IOError)
+    }
+
     pub fn change_state(&mut self, state: State) {
-        println!("[!] {state:?}");
+        if let State::Config = state {
+            self.keep_alive = KeepAlive::new(Instant::now());
+        }
         self.state = state;
     }
 
+    /// Queue a keep alive when one is due, `NetworkError::TimedOut` when the connection
+    /// is stuck before the configuration or does not answer the keep alives
+    pub fn tick(&mut self, now: Instant) -> Result<(), NetworkError> {
+        let keep_alive_id = match self.state {
+            State::Handshake | State::Status | State::Login => {
+                return match now.duration_since(self.connected_at) >= LOGIN_TIMEOUT {
+                    true => Err(NetworkError::TimedOut),
+                    false => Ok(()),
+                };
+            }
+            State::Config | State::Play => self.keep_alive.tick(now)?,
+        };
+        let packet = match (keep_alive_id, &self.state) {
+            (Some(keep_alive_id), State::Config) => {
+                config::server::KeepAlive { keep_alive_id }.to_packet()
+            }
+            (Some(keep_alive_id), _) => play::server::KeepAlive { keep_alive_id }.to_packet(),
+            (None, _) => return Ok(()),
+        };
+        self.queue_packet(packet);
+        Ok(())
+    }
+
+    /// Answer of the client to a keep alive
+    pub fn on_keep_alive(&mut self, keep_alive_id: i64) -> Result<(), NetworkError> {
+        self.keep_alive.receive(keep_alive_id, Instant::now())
+    }
+
+    /// Round trip time measured with the keep alives, in milliseconds
+    pub fn latency(&self) -> i32 {
+        self.keep_alive.latency()
+    }
+
     pub fn player(&self) -> &Option<Player> {
         &self.player
     }
 
+    pub fn entity_id(&self) -> Option<i32> {
+        self.entity_id
+    }
+
+    /// Address the client connects from, the one given by the proxy if any
+    pub fn address(&self) -> Option<IpAddr> {
+        match &self.forwarded {
+            Some(forwarded) => Some(forwarded.address),
+            None => self.client.address().ok().map(|address| address.ip()),
+        }
+    }
+
+    /// Protocol version of the client, given in the handshake
+    pub fn protocol_version(&self) -> i32 {
+        self.protocol_version
+    }
+
+    /// Address the client used to connect to the server, given in the handshake
+    pub fn server_address(&self) -> &str {
+        &self.server_address
+    }
+
+    /// Whether the client comes from another server which transferred it
+    pub fn is_transferred(&self) -> bool {
+        self.transferred
+    }
+
+    /// Move the client to the server at `host`, `false` before the configuration
+    pub fn transfer(&mut self, host: &str, port: u16) -> bool {
+        let (host, port) = (host.to_string(), VarInt::new(port as i32));
+        let packet = match self.state {
+            State::Config => config::server::Transfer { host, port }.to_packet(),
+            State::Play => play::server::Transfer { host, port }.to_packet(),
+            _ => return false,
+        };
+        self.queue_packet(packet);
+        true
+    }
+
+    /// Store a cookie on the client, kept when it is transferred
+    pub fn store_cookie(&mut self, key: Identifier, payload: Vec<u8>) -> Result<(), CookieError> {
+        check_size(&payload)?;
+        let packet = match self.state {
+            State::Config => config::server::StoreCookie { key, payload }.to_packet(),
+            State::Play => play::server::StoreCookie {
+                key: key.to_string(),
+                payload,
+            }
+            .to_packet(),
+            _ => return Err(CookieError::WrongState),
+        };
+        self.queue_packet(packet);
+        Ok(())
+    }
+
+    /// Ask the client for a cookie, read it with `cookie` once it answers
+    pub fn request_cookie(&mut self, key: Identifier) -> Result<(), CookieError> {
+        let packet = match self.state {
+            State::Login => login::server::CookieRequest { key: key.clone() }.to_packet(),
+            State::Config => config::server::CookieRequest { key: key.clone() }.to_packet(),
+            State::Play => play::server::CookieRequest {
+                key: key.to_string(),
+            }
+            .to_packet(),
+            _ => return Err(CookieError::WrongState),
+        };
+        self.cookies.request(key);
+        self.queue_packet(packet);
+        Ok(())
+    }
+
+    /// `None` until the client answers the request, then the cookie if it has one
+    pub fn cookie(&self, key: &Identifier) -> Option<Option<&[u8]>> {
+        self.cookies.get(key)
+    }
+
     pub fn player_name(&self) -> Option<String> {
         self.player.as_ref().map(|player| player.name())
     }
 
+    pub fn is_playing(&self) -> bool {
+        matches!(self.state, State::Play)
+    }
+
     pub fn active_compression(&mut self) {
         self.client.active_compression();
     }
@@ -65,50 +366,106 @@ pub struct Protocols {
     status: StatusProtocol,
     login: LoginProtocol,
     config: ConfigProtocol,
-    _play: (),
+    play: PlayProtocol,
 }
 
-impl Default for Protocols {
-    fn default() -> Self {
+impl Protocols {
+    fn new(
+        state: Arc<RwLock<ServerState>>,
+        permissions: Arc<RwLock<Permissions>>,
+        access: Arc<RwLock<AccessLists>>,
+        chat_types: Arc<RwLock<ChatFormatter>>,
+        actions: Sender<Action>,
+    ) -> Self {
         let json_response = JsonResponse {
             version: Version {
-                name: "1.21".to_string(),
-                protocol: 767,
-            },
-            players: Players {
-                max: 666,
-                online: 0,
-                sample: Vec::with_capacity(0),
+                name: VERSION_NAME.to_string(),
+                protocol: PROTOCOL_VERSION as u32,
             },
+            // filled on each request
+            players: Players::default(),
             description: Description {
                 text: "§fMon serveur en §6§lRust".to_string(),
             },
             favicon: None,
-            enforces_secure_chat: true,
+            // the players are not authenticated, see `with_secure_chat`
+            enforces_secure_chat: false,
         };
         Self {
-            handshake: HandshakeProtocol,
-            status: StatusProtocol::new(Arc::new(RwLock::new(json_response))),
-            login: LoginProtocol::new().unwrap(),
+            handshake: HandshakeProtocol::new(),
+            status: StatusProtocol::new(Arc::new(RwLock::new(json_response)), state),
+            login: LoginProtocol::new(access, permissions.clone(), chat_types).unwrap(),
             config: ConfigProtocol,
-            _play: (),
+            play: PlayProtocol::new(
+                Arc::new(Mutex::new(Dispatcher::new())),
+                permissions,
+                actions,
+            ),
         }
     }
 }
 
 pub struct ProtocolHandler {
-    // packet receiver
-    receiver: Receiver<(u32, Packet)>,
+    // packet receiver, taken by the handler thread
+    receiver: Option<Receiver<(u32, Packet)>>,
     clients: HashMap<u32, Client>,
     protocols: Protocols,
+    entities: EntityStore,
+    tracker: EntityTracker,
+    scheduler: Scheduler,
+    state: Arc<RwLock<ServerState>>,
+    permissions: Arc<RwLock<Permissions>>,
+    access: Arc<RwLock<AccessLists>>,
+    users: Arc<RwLock<UserCache>>,
+    chat_types: Arc<RwLock<ChatFormatter>>,
+    tab_list: Arc<RwLock<TabList>>,
+    /// where the world and the players are saved, `None` to keep them in memory
+    save: Option<WorldSave>,
+    /// players of the clients in the player list
+    listed: HashMap<u32, Uuid>,
+    server: ServerHandle,
+    actions: Receiver<Action>,
+    stopped: bool,
 }
 
 impl ProtocolHandler {
     pub fn new(receiver: Receiver<(u32, Packet)>) -> Self {
+        let state = Arc::new(RwLock::new(ServerState::default()));
+        let permissions = Arc::new(RwLock::new(Permissions::new()));
+        let access = Arc::new(RwLock::new(AccessLists::new()));
+        let users = Arc::new(RwLock::new(UserCache::new()));
+        let chat_types = Arc::new(RwLock::new(ChatFormatter::new()));
+        let (sender, actions) = mpsc::channel();
         Self {
-            protocols: Protocols::default(),
-            receiver,
+            protocols: Protocols::new(
+                state.clone(),
+                permissions.clone(),
+                access.clone(),
+                chat_types.clone(),
+                sender.clone(),
+            ),
+            receiver: Some(receiver),
             clients: HashMap::new(),
+            entities: EntityStore::new(),
+            tracker: EntityTracker::new(VIEW_DISTANCE),
+            scheduler: Scheduler::new(ASYNC_THREADS),
+            server: ServerHandle::new(
+                state.clone(),
+                permissions.clone(),
+                access.clone(),
+                users.clone(),
+                sender,
+            ),
+            state,
+            permissions,
+            access,
+            users,
+            chat_types,
+            tab_list: Arc::new(RwLock::new(TabList::new())),
+            save: None,
+            listed: HashMap::new(),
+            actions,
+            stopped: false,
         }
     }
 
@@ -116,47 +473,781 @@ impl ProtocolHandler {
         self.clients.insert(id, Client::new(client));
     }
 
+    pub fn entities(&mut self) -> &mut EntityStore {
+        &mut self.entities
+    }
+
+    pub fn tracker(&mut self) -> &mut EntityTracker {
+        &mut self.tracker
+    }
+
+    pub fn scheduler(&self) -> SchedulerHandle {
+        self.scheduler.handle()
+    }
+
+    /// Use `permissions`, usually loaded from the server directory
+    pub fn with_permissions(self, permissions: Permissions) -> Self {
+        *self
+            .permissions
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = permissions;
+        self
+    }
+
+    /// Operator levels and permission nodes, shared with the commands and the plugins
+    pub fn permissions(&self) -> Arc<RwLock<Permissions>> {
+        self.permissions.clone()
+    }
+
+    /// Use the ban lists and the whitelist `access`, usually loaded from the server directory
+    pub fn with_access(self, access: AccessLists) -> Self {
+        *self.access.write().unwrap_or_else(|err| err.into_inner()) = access;
+        self
+    }
+
+    /// Ban lists and whitelist checked when a player logs in, reload them to apply changes made to the files
+    pub fn access(&self) -> Arc<RwLock<AccessLists>> {
+        self.access.clone()
+    }
+
+    /// Start from the seed, the time, the weather and the difficulty of `world`
+    pub fn with_world(self, world: WorldData) -> Self {
+        let mut state = self.state.write().unwrap_or_else(|err| err.into_inner());
+        state.seed = world.seed;
+        state.game_time = world.game_time;
+        state.day_time = world.day_time;
+        state.weather = world.weather;
+        state.weather_time = world.weather_time;
+        state.difficulty = world.difficulty;
+        drop(state);
+        self
+    }
+
+    /// Save the world and the players in `save` when the server stops,
+    /// the players get their data back when they join
+    pub fn with_save(mut self, save: WorldSave) -> Self {
+        self.save = Some(save);
+        self
+    }
+
+    /// Use the players known from `users`, usually loaded from the server directory
+    pub fn with_user_cache(self, users: UserCache) -> Self {
+        *self.users.write().unwrap_or_else(|err| err.into_inner()) = users;
+        self
+    }
+
+    /// Require the players to sign their chat messages, the status tells the clients about it
+    pub fn with_secure_chat(mut self, secure_chat: bool) -> Self {
+        self.protocols.play.set_secure_chat(secure_chat);
+        if let Ok(mut json_response) = self.protocols.status.json_response().write() {
+            json_response.enforces_secure_chat = secure_chat;
+        }
+        self
+    }
+
+    /// Chat types decorating the messages on the server, the data packs register theirs there
+    pub fn chat_types(&self) -> Arc<RwLock<ChatFormatter>> {
+        self.chat_types.clone()
+    }
+
+    /// Take the players, their address and their skin from the proxy in front of the server
+    pub fn with_forwarding(mut self, forwarding: Forwarding) -> Self {
+        self.protocols
+            .handshake
+            .set_bungee_forwarding(forwarding == Forwarding::Bungee);
+        self.protocols.login.set_forwarding(forwarding);
+        self
+    }
+
+    /// Exchange login plugin messages on `channel` with the players logging in,
+    /// e.g. for a mod handshake
+    pub fn add_login_channel(&mut self, channel: Identifier, handler: Box<dyn LoginPluginChannel>) {
+        self.protocols.login.add_channel(channel, handler);
+    }
+
+    /// Accept the players transferred from another server
+    pub fn with_transfers(mut self, accept_transfers: bool) -> Self {
+        self.protocols
+            .handshake
+            .set_accept_transfers(accept_transfers);
+        self
+    }
+
+    /// The client, e.g. to transfer it or to store cookies on it
+    pub fn client_mut(&mut self, client_id: u32) -> Option<&mut Client> {
+        self.clients.get_mut(&client_id)
+    }
+
+    /// Show `favicon`, a PNG data URI, in the server list
+    pub fn with_favicon(self, favicon: Option<String>) -> Self {
+        if let Ok(mut json_response) = self.protocols.status.json_response().write() {
+            json_response.favicon = favicon;
+        }
+        self
+    }
+
+    /// Name at most `sample_size` random players in the server list
+    pub fn with_sample_size(mut self, sample_size: usize) -> Self {
+        self.protocols.status.set_sample_size(sample_size);
+        self
+    }
+
+    /// Status sent to the server list, change its description there
+    pub fn status(&self) -> Arc<RwLock<JsonResponse>> {
+        self.protocols.status.json_response()
+    }
+
+    /// Customise the status sent to each client, e.g. by protocol version or address
+    pub fn add_status_hook(&mut self, hook: StatusHook) {
+        self.protocols.status.add_hook(hook);
+    }
+
+    /// Use `header` and `footer` in the player list
+    pub fn with_tab_list(self, header: TextComponent, footer: TextComponent) -> Self {
+        let mut tab_list = self.tab_list.write().unwrap_or_else(|err| err.into_inner());
+        tab_list.set_header(header);
+        tab_list.set_footer(footer);
+        drop(tab_list);
+        self
+    }
+
+    /// Player list, the plugins change the display names, the listed flags, the header and the footer there
+    pub fn tab_list(&self) -> Arc<RwLock<TabList>> {
+        self.tab_list.clone()
+    }
+
+    /// Send a message decorated on the server with the chat type `name` to all the players,
+    /// `false` if the chat type is unknown
+    pub fn send_chat(
+        &mut self,
+        name: &str,
+        sender: &str,
+        target: Option<&str>,
+        content: &str,
+    ) -> bool {
+        let chat_types = self
+            .chat_types
+            .read()
+            .unwrap_or_else(|err| err.into_inner());
+        let Some(component) = chat_types.decorate(name, sender, target, content) else {
+            return false;
+        };
+        console::log(chat_types.to_plain(&component));
+        drop(chat_types);
+        self.queue_all(|| vec![system_chat_packet(component.clone())]);
+        true
+    }
+
+    /// Remove a signed chat message from the chat of all the players
+    pub fn delete_message(&mut self, signature: MessageSignature) {
+        self.queue_all(|| vec![delete_message_packet(signature)]);
+    }
+
+    /// Queue the operator level and the command tree of the client,
+    /// to send when they change
+    pub fn send_permissions(&mut self, client_id: u32) {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        if let (Some(entity_id), Some(name)) = (client.entity_id, client.player_name()) {
+            let level = self
+                .permissions
+                .read()
+                .map_or(0, |permissions| permissions.op_level(&name));
+            client.queue_packet(op_level_packet(entity_id, level));
+        }
+        self.send_commands(client_id);
+    }
+
+    /// Server access for the built-in commands
+    pub fn server(&self) -> ServerHandle {
+        self.server.clone()
+    }
+
+    /// Commands run by the players and the console
+    pub fn commands(&self) -> Arc<Mutex<Dispatcher>> {
+        self.protocols.play.commands()
+    }
+
+    /// Queue the command tree the client is allowed to use
+    pub fn send_commands(&mut self, client_id: u32) {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        if let Some(packet) = self.protocols.play.commands_packet(client) {
+            client.queue_packet(packet);
+        }
+    }
+
+    fn world_data(&self) -> WorldData {
+        let state = self.state.read().unwrap_or_else(|err| err.into_inner());
+        WorldData {
+            seed: state.seed,
+            game_time: state.game_time,
+            day_time: state.day_time,
+            weather: state.weather,
+            weather_time: state.weather_time,
+            difficulty: state.difficulty,
+        }
+    }
+
+    fn player_data(&self, client: &Client) -> PlayerData {
+        let entity = client.entity_id.and_then(|id| self.entities.get(id));
+        PlayerData {
+            game_mode: client.game_mode,
+            position: entity.map(|entity| entity.position()),
+            rotation: entity.map_or((0.0, 0.0), |entity| (entity.yaw(), entity.pitch())),
+            inventory: client.inventory.slots().to_vec(),
+        }
+    }
+
+    /// Save the world and the players in play state
+    pub fn save(&self) {
+        for client in self.clients.values() {
+            self.save_player(client);
+        }
+        self.save_world();
+    }
+
+    fn save_world(&self) {
+        let Some(save) = &self.save else {
+            return;
+        };
+        if let Err(err) = save.save_world(&self.world_data()) {
+            console::error(format!("Cannot save the world: {err}"));
+        }
+    }
+
+    /// Save the player of the client once it joined, from its entity
+    fn save_player(&self, client: &Client) {
+        let (Some(save), Some(player)) = (&self.save, &client.player) else {
+            return;
+        };
+        if !client.loaded {
+            return;
+        }
+        if let Err(err) = save.save_player(player.uuid(), &self.player_data(client)) {
+            console::error(format!("Cannot save the player {}: {err}", player.name()));
+        }
+    }
+
+    /// Let the clients entering the play state join
+    fn load_players(&mut self) {
+        let joined: Vec<u32> = self
+            .clients
+            .iter()
+            .filter(|(_, client)| client.is_playing() && !client.loaded)
+            .map(|(client_id, _)| *client_id)
+            .collect();
+        for client_id in joined {
+            self.join(client_id);
+        }
+    }
+
+    /// Remember the player of the client, spawn its entity where it left, give it its saved data
+    /// back, its operator level and its command tree
+    fn join(&mut self, client_id: u32) {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return;
+        };
+        client.loaded = true;
+        let Some(player) = client.player.clone() else {
+            return;
+        };
+        let mut users = self.users.write().unwrap_or_else(|err| err.into_inner());
+        if users.add(&player.name(), player.uuid()) {
+            if let Err(err) = users.save() {
+                console::error(format!("Cannot save the user cache: {err}"));
+            }
+        }
+        drop(users);
+        let data = match self
+            .save
+            .as_ref()
+            .map(|save| save.load_player(player.uuid()))
+        {
+            Some(Ok(data)) => data,
+            Some(Err(err)) => {
+                console::error(format!("Cannot load the player {}: {err}", player.name()));
+                None
+            }
+            None => None,
+        };
+        let position = data
+            .as_ref()
+            .and_then(|data| data.position)
+            .unwrap_or(SPAWN_POSITION);
+        let (yaw, pitch) = data.as_ref().map_or((0.0, 0.0), |data| data.rotation);
+        let entity_id = self
+            .entities
+            .spawn_with_uuid(EntityType::Player, player.uuid(), position);
+        if let Some(entity) = self.entities.get_mut(entity_id) {
+            entity.set_rotation(yaw, pitch);
+            entity.set_head_yaw(yaw);
+        }
+        self.tracker.add_viewer(entity_id);
+        client.entity_id = Some(entity_id);
+        if let Some(data) = data {
+            client.game_mode = data.game_mode;
+            for (index, stack) in data.inventory.into_iter().enumerate() {
+                if !stack.is_empty() {
+                    client.inventory.set_slot(index, stack);
+                    client.queue_slot(index);
+                }
+            }
+        }
+        let state = self.state.read().unwrap_or_else(|err| err.into_inner());
+        let login = login_packet(
+            entity_id,
+            client.game_mode,
+            state.seed,
+            state.max_players,
+            VIEW_DISTANCE,
+            self.protocols.play.is_secure_chat(),
+        );
+        drop(state);
+        // before anything queued since the end of the configuration
+        client.outgoing.insert(0, login);
+        client.queue_packet(
+            play::server::SynchronizePlayerPosition {
+                coordinate: position,
+                yaw,
+                pitch,
+                flags: 0,
+                teleport_id: VarInt::new(0),
+            }
+            .to_packet(),
+        );
+        self.send_permissions(client_id);
+    }
+
+    /// Save the player of the client, remove the client and the entity of the player,
+    /// `None` if it already left
+    fn remove_client(&mut self, client_id: u32) -> Option<Client> {
+        let client = self.clients.remove(&client_id)?;
+        self.save_player(&client);
+        if let Some(entity_id) = client.entity_id {
+            self.tracker.remove_viewer(entity_id);
+            self.entities.despawn(entity_id);
+        }
+        Some(client)
+    }
+
+    /// Save the world and the players, disconnect the clients with `message`
+    /// and stop the scheduled tasks, the handler thread ends after it
+    pub fn shutdown(&mut self, message: &str) {
+        self.save_world();
+        let client_ids: Vec<u32> = self.clients.keys().copied().collect();
+        for client_id in client_ids {
+            let Some(mut client) = self.remove_client(client_id) else {
+                continue;
+            };
+            if let Err(err) = client.disconnect(message) {
+                console::error(format!("Error on : [{client_id:08x}] {err:?}"));
+            }
+        }
+        self.scheduler.shutdown();
+        self.stopped = true;
+    }
+
+    /// Queue the entity tracker updates for the players viewing them
+    fn queue_entity_updates(&mut self, updates: Vec<(i32, EntityUpdate)>) {
+        for (viewer, update) in updates {
+            let Some(client) = self
+                .clients
+                .values_mut()
+                .find(|client| client.entity_id == Some(viewer))
+            else {
+                continue;
+            };
+            for packet in entity_packets(update) {
+                client.queue_packet(packet);
+            }
+        }
+    }
+
+    fn client_by_name(&mut self, name: &str) -> Option<(u32, &mut Client)> {
+        self.clients
+            .iter_mut()
+            .find(|(_, client)| {
+                client.is_playing() && client.player_name().as_deref() == Some(name)
+            })
+            .map(|(id, client)| (*id, client))
+    }
+
+    fn queue_all(&mut self, packets: impl Fn() -> Vec<Packet>) {
+        for client in self
+            .clients
+            .values_mut()
+            .filter(|client| client.is_playing())
+        {
+            for packet in packets() {
+                client.queue_packet(packet);
+            }
+        }
+    }
+
+    fn time_packet(&self) -> Packet {
+        let state = self.state.read().unwrap_or_else(|err| err.into_inner());
+        play::server::UpdateTime {
+            world_age: state.game_time,
+            time_of_day: state.day_time,
+        }
+        .to_packet()
+    }
+
+    /// Advance the time and the weather, refresh the players seen by the commands
+    fn tick_world(&mut self) {
+        let mut state = self.state.write().unwrap_or_else(|err| err.into_inner());
+        state.game_time += 1;
+        state.day_time += 1;
+        let mut cleared = false;
+        if state.weather != Weather::Clear {
+            state.weather_time -= 1;
+            if state.weather_time <= 0 {
+                state.weather = Weather::Clear;
+                cleared = true;
+            }
+        }
+        state.players = self
+            .clients
+            .values()
+            .filter(|client| client.is_playing())
+            .filter_map(|client| {
+                let player = client.player.as_ref()?;
+                let entity = client.entity_id.and_then(|id| self.entities.get(id));
+                Some(OnlinePlayer {
+                    name: player.name(),
+                    uuid: player.uuid(),
+                    position: entity.map(|entity| entity.position()).unwrap_or_default(),
+                    rotation: entity.map_or((0.0, 0.0), |entity| (entity.yaw(), entity.pitch())),
+                    latency: client.latency(),
+                })
+            })
+            .collect();
+        let update_time = state.game_time % TIME_UPDATE_INTERVAL == 0;
+        drop(state);
+        if cleared {
+            self.queue_all(|| weather_packets(Weather::Clear));
+        }
+        if update_time {
+            let packet = self.time_packet();
+            self.queue_all(|| vec![packet.clone()]);
+        }
+    }
+
+    /// Apply the changes asked by the commands
+    fn apply_actions(&mut self) {
+        while let Ok(action) = self.actions.try_recv() {
+            match action {
+                Action::Message { player, message } => {
+                    let packet = || vec![system_chat_packet(TextComponent::text(&message))];
+                    match player {
+                        Some(player) => {
+                            if let Some((_, client)) = self.client_by_name(&player) {
+                                packet()
+                                    .into_iter()
+                                    .for_each(|packet| client.queue_packet(packet));
+                            }
+                        }
+                        None => self.queue_all(packet),
+                    }
+                }
+                Action::Kick { player, reason } => {
+                    let Some((client_id, _)) = self.client_by_name(&player) else {
+                        continue;
+                    };
+                    if let Some(mut client) = self.remove_client(client_id) {
+                        if let Err(err) = client.disconnect(&reason) {
+                            console::error(format!("Error on : [{client_id:08x}] {err:?}"));
+                        }
+                    }
+                }
+                Action::Teleport {
+                    player,
+                    position,
+                    rotation: (yaw, pitch),
+                } => {
+                    let Some((_, client)) = self.client_by_name(&player) else {
+                        continue;
+                    };
+                    client.queue_packet(
+                        play::server::SynchronizePlayerPosition {
+                            coordinate: position,
+                            yaw,
+                            pitch,
+                            flags: 0,
+                            teleport_id: VarInt::new(0),
+                        }
+                        .to_packet(),
+                    );
+                    if let Some(entity) = client.entity_id.and_then(|id| self.entities.get_mut(id))
+                    {
+                        entity.teleport(position);
+                        entity.set_rotation(yaw, pitch);
+                    }
+                }
+                Action::GameMode { player, game_mode } => {
+                    if let Some((_, client)) = self.client_by_name(&player) {
+                        client.game_mode = game_mode;
+                        client.queue_packet(
+                            play::server::GameEvent {
+                                event: game_event::CHANGE_GAME_MODE,
+                                value: game_mode.id() as f32,
+                            }
+                            .to_packet(),
+                        );
+                    }
+                }
+                Action::Give { player, stack } => {
+                    let Some((_, client)) = self.client_by_name(&player) else {
+                        continue;
+                    };
+                    let (changed, _) = client.inventory.add(stack);
+                    for index in changed {
+                        client.queue_slot(index);
+                    }
+                }
+                Action::Time => {
+                    let packet = self.time_packet();
+                    self.queue_all(|| vec![packet.clone()]);
+                }
+                Action::Weather => {
+                    let weather = self
+                        .state
+                        .read()
+                        .map_or(Weather::Clear, |state| state.weather);
+                    self.queue_all(|| weather_packets(weather));
+                }
+                Action::Permissions { player } => {
+                    if let Some((client_id, _)) = self.client_by_name(&player) {
+                        self.send_permissions(client_id);
+                    }
+                }
+                Action::Chat { sender, message } => {
+                    let chat_types = self
+                        .chat_types
+                        .read()
+                        .unwrap_or_else(|err| err.into_inner());
+                    if let Some(component) =
+                        chat_types.decorate(chat_type::CHAT, &sender, None, &message.message)
+                    {
+                        console::log(chat_types.to_plain(&component));
+                    }
+                    let chat_type = chat_types.id(chat_type::CHAT).unwrap_or_default();
+                    drop(chat_types);
+                    for client in self
+                        .clients
+                        .values_mut()
+                        .filter(|client| client.is_playing())
+                    {
+                        client.queue_packet(chat_packet(&sender, &message, chat_type));
+                        if let Some(signature) = message.signature {
+                            client.chat.last_seen_mut().add_pending(signature);
+                        }
+                    }
+                }
+                Action::Difficulty => {
+                    let difficulty = self
+                        .state
+                        .read()
+                        .map_or(Default::default(), |state| state.difficulty);
+                    self.queue_all(|| {
+                        vec![play::server::ChangeDifficulty {
+                            difficulty: difficulty.id(),
+                            difficulty_locked: false,
+                        }
+                        .to_packet()]
+                    });
+                }
+            }
+        }
+    }
+
+    /// Send the keep alives and disconnect the clients that timed out
+    fn keep_alive(&mut self) {
+        let now = Instant::now();
+        let timed_out: Vec<u32> = self
+            .clients
+            .iter_mut()
+            .filter_map(|(client_id, client)| match client.tick(now) {
+                Ok(()) => None,
+                Err(err) => {
+                    console::error(format!("Error on : [{client_id:08x}] {err:?}"));
+                    Some(*client_id)
+                }
+            })
+            .collect();
+        for client_id in timed_out {
+            let Some(mut client) = self.remove_client(client_id) else {
+                continue;
+            };
+            if let Err(err) = client.disconnect(TIMED_OUT) {
+                console::error(format!("Error on : [{client_id:08x}] {err:?}"));
+            }
+        }
+    }
+
+    /// Go on with the logins waiting for login plugin answers which did not come in time
+    fn time_out_login_plugins(&mut self) {
+        let now = Instant::now();
+        let mut steps = Vec::new();
+        for (client_id, client) in self.clients.iter_mut().filter(|(_, client)| {
+            matches!(client.state, State::Login) && client.login_plugins.is_pending()
+        }) {
+            if let Some(next) = self.protocols.login.time_out_plugins(client, now) {
+                steps.push((*client_id, next));
+            }
+        }
+        for (client_id, next) in steps {
+            if let Err(err) = self.apply_next(client_id, next) {
+                console::error(format!("Error on : [{client_id:08x}] {err:?}"));
+            }
+        }
+    }
+
+    /// Add the players joining to the player list, remove the ones leaving, refresh the chat
+    /// sessions, the game modes and the latencies, then send the changes
+    fn update_tab_list(&mut self) {
+        let mut tab_list = self.tab_list.write().unwrap_or_else(|err| err.into_inner());
+        self.listed.retain(|client_id, uuid| {
+            let playing = self
+                .clients
+                .get(client_id)
+                .is_some_and(|client| client.is_playing());
+            if !playing {
+                tab_list.remove(*uuid);
+            }
+            playing
+        });
+        let mut joined = Vec::new();
+        for (client_id, client) in self
+            .clients
+            .iter()
+            .filter(|(_, client)| client.is_playing())
+        {
+            let Some(player) = client.player.as_ref() else {
+                continue;
+            };
+            if self.listed.insert(*client_id, player.uuid()).is_none() {
+                let entry = TabEntry::new(player.uuid(), player.name(), client.properties.clone());
+                tab_list.add(entry);
+                joined.push(*client_id);
+            }
+            // the others verify the signed messages of the player with its session
+            tab_list.set_chat_session(player.uuid(), client.chat.session().map(ChatSession::data));
+            tab_list.set_game_mode(player.uuid(), client.game_mode);
+            tab_list.set_latency(player.uuid(), client.latency());
+        }
+        let changes = tab_list.take_changes();
+        // the players joining get the whole list instead of the changes
+        let list = match joined.is_empty() {
+            true => Vec::new(),
+            false => tab_list.packets(),
+        };
+        drop(tab_list);
+        for (client_id, client) in self
+            .clients
+            .iter_mut()
+            .filter(|(_, client)| client.is_playing())
+        {
+            let packets = match joined.contains(client_id) {
+                true => &list,
+                false => &changes,
+            };
+            for packet in packets {
+                client.queue_packet(packet.clone());
+            }
+        }
+    }
+
+    /// Answer the server list ping of a client before 1.7 with the status, then close the connection
+    fn on_legacy_ping(&mut self, client_id: u32, packet: Packet) -> Result<(), NetworkError> {
+        let Some(mut client) = self.remove_client(client_id) else {
+            return Ok(());
+        };
+        let ping = LegacyPing::parse(packet.raw_data());
+        let context = StatusContext {
+            protocol_version: ping.protocol_version(),
+            address: client.address(),
+            server_address: ping.server_address().to_string(),
+        };
+        let response = ping.response(&self.protocols.status.response(&context));
+        client.client.write_raw(&response)?;
+        client.client.shutdown().map_err(NetworkError::IOError)
+    }
+
     fn handle_packet(&mut self, client_id: u32, packet: Packet) -> Result<(), NetworkError> {
-        let client = self.clients.get_mut(&client_id).unwrap();
+        // the client left while its packets were waiting, e.g. it timed out
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return Ok(());
+        };
+        if matches!(client.state, State::Handshake) && packet.id() == LEGACY_PING as i32 {
+            return self.on_legacy_ping(client_id, packet);
+        }
+        let packet = client.map_incoming(packet);
         let next = match client.state {
             State::Handshake => self.protocols.handshake.handle_packet(client, packet),
             State::Status => self.protocols.status.handle_packet(client, packet),
             State::Config => self.protocols.config.handle_packet(client, packet),
             State::Login => self.protocols.login.handle_packet(client, packet),
-            State::Play => {
-                todo!()
+            State::Play if MOVEMENTS.contains(&packet.id()) => {
+                let entity = client.entity_id.and_then(|id| self.entities.get_mut(id));
+                on_movement(entity, packet)
             }
+            State::Play => self.protocols.play.handle_packet(client, packet),
+        };
+        self.apply_next(client_id, next)
+    }
+
+    fn apply_next(&mut self, client_id: u32, next: Next) -> Result<(), NetworkError> {
+        let Some(client) = self.clients.get_mut(&client_id) else {
+            return Ok(());
         };
         match next {
             Next::Disconnect => {
-                self.clients
-                    .remove(&client_id)
-                    .unwrap()
-                    .client
-                    .shutdown()
-                    .map_err(|err| NetworkError::IOError(err))?;
+                let result = client.client.shutdown().map_err(NetworkError::IOError);
+                self.remove_client(client_id);
+                result?;
             }
             Next::Wait => {}
-            Next::SendPacket(packet) => self
-                .clients
-                .get_mut(&client_id)
-                .unwrap()
-                .client
-                .write_packet(packet)?,
+            Next::Kick(reason) => {
+                let result = client.disconnect(&reason);
+                self.remove_client(client_id);
+                result?;
+            }
+            Next::SendPacket(packet) => client.send_packet(packet)?,
             Next::SendPackets(packets) => {
                 for packet in packets {
-                    self.clients
-                        .get_mut(&client_id)
-                        .unwrap()
-                        .send_packet(packet)?;
+                    client.send_packet(packet)?;
                 }
             }
-            Next::UpdateClient(update) => update(self.clients.get_mut(&client_id).unwrap()),
+            Next::UpdateClient(update) => update(client),
         }
         Ok(())
     }
 }
 
+impl Tickable for ProtocolHandler {
+    fn tick(&mut self, _tick: u64) {
+        self.scheduler.tick();
+        self.tick_world();
+        self.load_players();
+        self.apply_actions();
+        self.entities.tick();
+        let updates = self.tracker.tick(&mut self.entities);
+        self.queue_entity_updates(updates);
+        self.keep_alive();
+        self.time_out_login_plugins();
+        self.update_tab_list();
+        for (client_id, client) in self.clients.iter_mut() {
+            if let Err(err) = client.flush() {
+                console::error(format!("Error on : [{client_id:08x}] {err:?}"));
+            }
+        }
+    }
+}
+
 impl Handler for ProtocolHandler {
     type Return = ();
 
@@ -166,21 +1257,239 @@ impl Handler for ProtocolHandler {
     where
         Self: Sized,
     {
-        std::thread::spawn(move || loop {
-            match handler.try_lock() {
-                Ok(mut handler) => match handler.receiver.try_recv() {
+        // waiting for the packets without holding the handler lets the ticks run
+        let receiver = handler
+            .lock()
+            .ok()
+            .and_then(|mut handler| handler.receiver.take());
+        std::thread::spawn(move || {
+            let Some(receiver) = receiver else {
+                return;
+            };
+            loop {
+                // wakes up at least once a tick to see if the server stopped
+                let received = receiver.recv_timeout(TICK_DURATION);
+                let Ok(mut handler) = handler.lock() else {
+                    break;
+                };
+                if handler.stopped {
+                    break;
+                }
+                match received {
                     Ok((client_id, packet)) => {
                         if let Err(err) = handler.handle_packet(client_id, packet) {
-                            eprintln!("Error on : [{client_id:08x}] {err:?}");
+                            console::error(format!("Error on : [{client_id:08x}] {err:?}"));
                         }
                     }
-                    Err(_) => continue,
-                },
-                Err(err) => match err {
-                    std::sync::TryLockError::Poisoned(_) => break,
-                    std::sync::TryLockError::WouldBlock => continue,
-                },
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs, io,
+        net::{TcpListener, TcpStream},
+        sync::{mpsc, Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use uuid::Uuid;
+    use zr_binary::varint::VarInt;
+    use zr_core::{entity::EntityType, handler::Handler, save::WorldSave, tick::Tickable};
+    use zr_data_type::vector::Vector3;
+    use zr_network::packet::{packet::Packet, PacketData};
+
+    use super::{ProtocolHandler, State, SPAWN_POSITION};
+    use crate::{
+        handler::{handler::Next, keep_alive::TIMED_OUT, play::op_level_packet},
+        packet::{config, handshake::Handshake, login, play},
+        version::PROTOCOL_VERSION,
+    };
+
+    /// Connect a client to `handler` as `client_id`, what the server sends it is discarded
+    fn connect(handler: &mut ProtocolHandler, client_id: u32) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (server_side, _) = listener.accept().unwrap();
+        let client = zr_network::client::client::Client::new(server_side).unwrap();
+        handler.add_client(client_id, client);
+        std::thread::spawn(move || io::copy(&mut stream, &mut io::sink()));
+    }
+
+    fn login_handshake() -> Packet {
+        Handshake {
+            protocol_version: VarInt::new(PROTOCOL_VERSION),
+            server_address: "localhost".to_string(),
+            server_port: 25565,
+            next_state: VarInt::new(2),
+        }
+        .to_packet()
+    }
+
+    /// Go through the handshake, the login and the configuration as the player `name`
+    fn join(handler: &mut ProtocolHandler, client_id: u32, name: &str, uuid: Uuid) {
+        connect(handler, client_id);
+        let packets = [
+            login_handshake(),
+            login::client::LoginStart {
+                name: name.to_string(),
+                player_uuid: uuid,
+            }
+            .to_packet(),
+            login::client::LoginAcknowledge.to_packet(),
+            config::client::KnownPacks {
+                known_packs: vec![],
+            }
+            .to_packet(),
+            config::client::AcknowledgeFinishConfiguration.to_packet(),
+        ];
+        for packet in packets {
+            handler.handle_packet(client_id, packet).unwrap();
+        }
+        assert!(handler.clients[&client_id].is_playing());
+    }
+
+    #[test]
+    fn test_player_entity() {
+        let (_sender, receiver) = mpsc::channel();
+        let mut handler = ProtocolHandler::new(receiver);
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        join(&mut handler, 1, "Alice", alice);
+        handler.load_players();
+        // the player joins as its entity
+        let client = &handler.clients[&1];
+        let entity_id = client.entity_id.unwrap();
+        assert_eq!(client.outgoing[0].id(), play::server::Login::ID);
+        let entity = handler.entities.get(entity_id).unwrap();
+        assert_eq!(entity.entity_type(), EntityType::Player);
+        assert_eq!(entity.uuid(), alice);
+
+        // the players see each other
+        join(&mut handler, 2, "Bob", bob);
+        handler.tick(0);
+        let bob_id = handler.clients[&2].entity_id.unwrap();
+        assert!(handler.tracker.is_visible(entity_id, bob_id));
+        assert!(handler.tracker.is_visible(bob_id, entity_id));
+
+        // the entity leaves with the player
+        handler.apply_next(2, Next::Disconnect).unwrap();
+        assert!(handler.entities.get(bob_id).is_none());
+        assert!(!handler.tracker.is_visible(bob_id, entity_id));
+        handler.tick(1);
+        assert!(!handler.tracker.is_visible(entity_id, bob_id));
+        handler
+            .apply_next(1, Next::Kick(TIMED_OUT.to_string()))
+            .unwrap();
+        assert!(handler.entities.is_empty());
+    }
+
+    #[test]
+    fn test_op_level_on_join() {
+        let (_sender, receiver) = mpsc::channel();
+        let mut handler = ProtocolHandler::new(receiver);
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        handler.permissions().write().unwrap().op("Alice", alice, 3);
+        join(&mut handler, 1, "Alice", alice);
+        join(&mut handler, 2, "Bob", bob);
+        handler.load_players();
+        for (client_id, level) in [(1, 3), (2, 0)] {
+            let client = &handler.clients[&client_id];
+            let packet = op_level_packet(client.entity_id.unwrap(), level);
+            assert!(client.outgoing.contains(&packet));
+        }
+    }
+
+    #[test]
+    fn test_saved_position() {
+        let directory = std::env::temp_dir().join(format!("zr_join_{}", Uuid::new_v4()));
+        let (_sender, receiver) = mpsc::channel();
+        let mut handler = ProtocolHandler::new(receiver).with_save(WorldSave::new(&directory));
+        let uuid = Uuid::new_v4();
+        join(&mut handler, 1, "Alice", uuid);
+        handler.tick(0);
+        let entity_id = handler.clients[&1].entity_id.unwrap();
+        assert_eq!(
+            handler.entities.get(entity_id).unwrap().position(),
+            SPAWN_POSITION
+        );
+
+        let position = Vector3::new(10.5, 70.0, -3.5);
+        let packet = play::client::SetPlayerPositionAndRotation {
+            coordinate: position,
+            yaw: 90.0,
+            pitch: 10.0,
+            on_ground: true,
+        };
+        handler.handle_packet(1, packet.to_packet()).unwrap();
+        handler.tick(1);
+        handler.apply_next(1, Next::Disconnect).unwrap();
+        let data = WorldSave::new(&directory)
+            .load_player(uuid)
+            .unwrap()
+            .unwrap();
+        assert_eq!(data.position, Some(position));
+        assert_eq!(data.rotation, (90.0, 10.0));
+
+        // back where it left
+        join(&mut handler, 2, "Alice", uuid);
+        handler.tick(2);
+        let entity_id = handler.clients[&2].entity_id.unwrap();
+        let entity = handler.entities.get(entity_id).unwrap();
+        assert_eq!(entity.position(), position);
+        assert_eq!((entity.yaw(), entity.pitch()), (90.0, 10.0));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn test_early_encryption_response() {
+        let (_sender, receiver) = mpsc::channel();
+        let mut handler = ProtocolHandler::new(receiver);
+        connect(&mut handler, 1);
+        handler.handle_packet(1, login_handshake()).unwrap();
+        // no player logged in yet
+        let packet = login::client::EncryptionResponse {
+            shared_secret: vec![],
+            verify_token: vec![],
+        };
+        handler.handle_packet(1, packet.to_packet()).unwrap();
+        assert!(handler.clients.is_empty());
+    }
+
+    #[test]
+    fn test_handle() {
+        let (sender, receiver) = mpsc::channel();
+        let handler = Arc::new(Mutex::new(ProtocolHandler::new(receiver)));
+        let thread = Handler::handle(handler.clone());
+        connect(&mut handler.lock().unwrap(), 1);
+        sender.send((1, login_handshake())).unwrap();
+        // the handler thread waits for the packets without holding the handler
+        let start = Instant::now();
+        while handler.lock().unwrap().clients[&1].state != State::Login {
+            assert!(start.elapsed() < Duration::from_secs(1));
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        handler.lock().unwrap().shutdown("Bye");
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_removed_client() {
+        let (_sender, receiver) = mpsc::channel();
+        let mut handler = ProtocolHandler::new(receiver);
+        // the packets of a client removed by the keep alive timeout are still queued
+        let client_id = 42;
+        assert!(handler
+            .handle_packet(client_id, Packet::new(0x00, vec![]))
+            .is_ok());
+        assert!(handler.apply_next(client_id, Next::Wait).is_ok());
+        assert!(handler
+            .apply_next(client_id, Next::Kick(TIMED_OUT.to_string()))
+            .is_ok());
+        assert!(handler.clients.is_empty());
+    }
+}
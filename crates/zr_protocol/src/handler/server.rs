@@ -0,0 +1,390 @@
+use std::{
+    sync::{mpsc::Sender, Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    time::Instant,
+};
+
+use uuid::Uuid;
+use zr_core::{
+    access::{self, AccessLists, PlayerBan},
+    command::vanilla::Server,
+    console,
+    entity::player::GameMode,
+    item::ItemStack,
+    permission::{Permissions, DEFAULT_OP_LEVEL},
+    supervisor::SupervisorHandle,
+    tick::{TickReport, TickStats},
+    user_cache::UserCache,
+    world::{Difficulty, Weather},
+};
+use zr_data_type::vector::Vector3;
+
+use super::chat::PlayerMessage;
+
+/// Players allowed at the same time
+pub const MAX_PLAYERS: usize = 20;
+
+/// A player in play state, as seen by the commands
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnlinePlayer {
+    pub name: String,
+    pub uuid: Uuid,
+    pub position: Vector3<f64>,
+    /// yaw and pitch
+    pub rotation: (f32, f32),
+    /// round trip time, in milliseconds
+    pub latency: i32,
+}
+
+/// Server data shared between the protocol handler and the commands
+#[derive(Debug, Clone)]
+pub struct ServerState {
+    /// refreshed by the protocol handler every tick
+    pub players: Vec<OnlinePlayer>,
+    pub max_players: usize,
+    pub game_time: i64,
+    pub day_time: i64,
+    pub weather: Weather,
+    /// ticks before the weather clears
+    pub weather_time: i32,
+    pub difficulty: Difficulty,
+    pub seed: i64,
+}
+
+impl Default for ServerState {
+    fn default() -> Self {
+        Self {
+            players: Vec::new(),
+            max_players: MAX_PLAYERS,
+            game_time: 0,
+            day_time: 0,
+            weather: Weather::Clear,
+            weather_time: 0,
+            difficulty: Difficulty::Normal,
+            seed: rand::random(),
+        }
+    }
+}
+
+/// Change on the connected clients, applied by the protocol handler on its next tick
+#[derive(Debug, Clone)]
+pub enum Action {
+    /// `None` for all the players
+    Message {
+        player: Option<String>,
+        message: String,
+    },
+    Kick {
+        player: String,
+        reason: String,
+    },
+    Teleport {
+        player: String,
+        position: Vector3<f64>,
+        rotation: (f32, f32),
+    },
+    GameMode {
+        player: String,
+        game_mode: GameMode,
+    },
+    Give {
+        player: String,
+        stack: ItemStack,
+    },
+    /// send the time to all the players
+    Time,
+    /// send the weather to all the players
+    Weather,
+    /// send the difficulty to all the players
+    Difficulty,
+    /// send the operator level and the command tree to the player
+    Permissions {
+        player: String,
+    },
+    /// relay a chat message to all the players
+    Chat {
+        sender: String,
+        message: Box<PlayerMessage>,
+    },
+}
+
+/// Access to the server for the commands, the changes are sent to the protocol handler
+#[derive(Clone)]
+pub struct ServerHandle {
+    state: Arc<RwLock<ServerState>>,
+    permissions: Arc<RwLock<Permissions>>,
+    access: Arc<RwLock<AccessLists>>,
+    users: Arc<RwLock<UserCache>>,
+    actions: Sender<Action>,
+    shutdown: Option<SupervisorHandle>,
+    ticks: Option<Arc<Mutex<TickStats>>>,
+}
+
+impl ServerHandle {
+    pub(crate) fn new(
+        state: Arc<RwLock<ServerState>>,
+        permissions: Arc<RwLock<Permissions>>,
+        access: Arc<RwLock<AccessLists>>,
+        users: Arc<RwLock<UserCache>>,
+        actions: Sender<Action>,
+    ) -> Self {
+        Self {
+            state,
+            permissions,
+            access,
+            users,
+            actions,
+            shutdown: None,
+            ticks: None,
+        }
+    }
+
+    /// `/stop` shuts the supervised workers down
+    pub fn with_shutdown(mut self, shutdown: SupervisorHandle) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// `/tps` reports the statistics of the tick loop
+    pub fn with_tick_stats(mut self, ticks: Arc<Mutex<TickStats>>) -> Self {
+        self.ticks = Some(ticks);
+        self
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, ServerState> {
+        self.state.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, ServerState> {
+        self.state.write().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Change and save the permissions, the player gets its new command tree
+    fn update_permissions(
+        &self,
+        player: &str,
+        update: impl FnOnce(&mut Permissions) -> bool,
+    ) -> bool {
+        let mut permissions = self
+            .permissions
+            .write()
+            .unwrap_or_else(|err| err.into_inner());
+        if !update(&mut permissions) {
+            return false;
+        }
+        if let Err(err) = permissions.save() {
+            console::error(format!("Cannot save the permissions: {err}"));
+        }
+        drop(permissions);
+        self.send(Action::Permissions {
+            player: player.to_string(),
+        });
+        true
+    }
+
+    /// Change the access lists and save them when `update` changed something
+    fn update_access(&self, update: impl FnOnce(&mut AccessLists) -> bool) -> bool {
+        let mut access = self.access.write().unwrap_or_else(|err| err.into_inner());
+        if !update(&mut access) {
+            return false;
+        }
+        if let Err(err) = access.save() {
+            console::error(format!("Cannot save the access lists: {err}"));
+        }
+        true
+    }
+
+    fn read_access(&self) -> RwLockReadGuard<'_, AccessLists> {
+        self.access.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn send(&self, action: Action) {
+        // the protocol handler is gone once the server stops
+        let _ = self.actions.send(action);
+    }
+}
+
+impl Server for ServerHandle {
+    fn stop(&self) {
+        if let Some(shutdown) = &self.shutdown {
+            shutdown.shutdown();
+        }
+    }
+
+    fn players(&self) -> Vec<String> {
+        self.read()
+            .players
+            .iter()
+            .map(|player| player.name.clone())
+            .collect()
+    }
+
+    fn max_players(&self) -> usize {
+        self.read().max_players
+    }
+
+    fn position(&self, player: &str) -> Option<(Vector3<f64>, (f32, f32))> {
+        self.read()
+            .players
+            .iter()
+            .find(|online| online.name == player)
+            .map(|online| (online.position, online.rotation))
+    }
+
+    fn send_message(&self, player: &str, message: &str) {
+        self.send(Action::Message {
+            player: Some(player.to_string()),
+            message: message.to_string(),
+        });
+    }
+
+    fn broadcast(&self, message: &str) {
+        self.send(Action::Message {
+            player: None,
+            message: message.to_string(),
+        });
+    }
+
+    fn kick(&self, player: &str, reason: &str) {
+        self.send(Action::Kick {
+            player: player.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    fn uuid(&self, player: &str) -> Option<Uuid> {
+        let online = self
+            .read()
+            .players
+            .iter()
+            .find(|online| online.name == player)
+            .map(|online| online.uuid);
+        online.or_else(|| {
+            self.users
+                .read()
+                .unwrap_or_else(|err| err.into_inner())
+                .uuid(player)
+        })
+    }
+
+    fn ban(&self, player: &str, uuid: Uuid, source: &str, reason: &str) -> bool {
+        let ban = PlayerBan {
+            uuid,
+            name: player.to_string(),
+            created: access::now(),
+            source: source.to_string(),
+            expires: None,
+            reason: reason.to_string(),
+        };
+        self.update_access(|access| access.ban(ban))
+    }
+
+    fn pardon(&self, player: &str) -> bool {
+        self.update_access(|access| access.pardon(player))
+    }
+
+    fn op(&self, player: &str, uuid: Uuid) -> bool {
+        self.update_permissions(player, |permissions| {
+            permissions.op(player, uuid, DEFAULT_OP_LEVEL)
+        })
+    }
+
+    fn deop(&self, player: &str) -> bool {
+        self.update_permissions(player, |permissions| permissions.deop(player))
+    }
+
+    fn whitelist(&self) -> Vec<String> {
+        self.read_access()
+            .whitelist()
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect()
+    }
+
+    fn is_whitelist_enabled(&self) -> bool {
+        self.read_access().is_whitelist_enabled()
+    }
+
+    fn set_whitelist_enabled(&self, enabled: bool) {
+        self.update_access(|access| {
+            access.set_whitelist_enabled(enabled);
+            true
+        });
+    }
+
+    fn whitelist_add(&self, player: &str) -> bool {
+        let uuid = self.uuid(player);
+        self.update_access(|access| access.whitelist_add(player, uuid))
+    }
+
+    fn whitelist_remove(&self, player: &str) -> bool {
+        self.update_access(|access| access.whitelist_remove(player))
+    }
+
+    fn reload_whitelist(&self) {
+        let mut access = self.access.write().unwrap_or_else(|err| err.into_inner());
+        if let Err(err) = access.reload_whitelist() {
+            console::error(format!("Cannot reload the whitelist: {err}"));
+        }
+    }
+
+    fn teleport(&self, player: &str, position: Vector3<f64>, rotation: (f32, f32)) {
+        self.send(Action::Teleport {
+            player: player.to_string(),
+            position,
+            rotation,
+        });
+    }
+
+    fn set_game_mode(&self, player: &str, game_mode: GameMode) {
+        self.send(Action::GameMode {
+            player: player.to_string(),
+            game_mode,
+        });
+    }
+
+    fn give(&self, player: &str, stack: ItemStack) {
+        self.send(Action::Give {
+            player: player.to_string(),
+            stack,
+        });
+    }
+
+    fn game_time(&self) -> i64 {
+        self.read().game_time
+    }
+
+    fn day_time(&self) -> i64 {
+        self.read().day_time
+    }
+
+    fn set_day_time(&self, time: i64) {
+        self.write().day_time = time;
+        self.send(Action::Time);
+    }
+
+    fn set_weather(&self, weather: Weather, duration: i32) {
+        let mut state = self.write();
+        state.weather = weather;
+        state.weather_time = duration;
+        drop(state);
+        self.send(Action::Weather);
+    }
+
+    fn seed(&self) -> i64 {
+        self.read().seed
+    }
+
+    fn difficulty(&self) -> Difficulty {
+        self.read().difficulty
+    }
+
+    fn set_difficulty(&self, difficulty: Difficulty) {
+        self.write().difficulty = difficulty;
+        self.send(Action::Difficulty);
+    }
+
+    fn tick_report(&self) -> Option<TickReport> {
+        let ticks = self.ticks.as_ref()?.lock().ok()?;
+        Some(ticks.report(Instant::now()))
+    }
+}
@@ -1,23 +1,40 @@
 use std::{
-    ops::Deref,
+    fmt::Display,
+    fs,
+    io::ErrorKind,
+    net::IpAddr,
+    path::Path,
     sync::{Arc, RwLock},
 };
 
+use base64ct::{Base64, Encoding};
+use rand::seq::SliceRandom;
 use serde::Serialize;
 // ---
 use uuid::Uuid;
+use zr_core::{console, settings::DEFAULT_SAMPLE_SIZE};
 use zr_network::packet::{packet::Packet, PacketData};
 
-use crate::packet::status::{
-    client::{Ping, StatusRequest},
-    server::{Pong, StatusResponse},
+use crate::{
+    packet::status::{
+        client::{Ping, StatusRequest},
+        server::{Pong, StatusResponse},
+    },
+    version::ProtocolVersion,
 };
 
 use super::{
     handler::{Next, PacketHandler},
     protocol_handler::Client,
+    server::ServerState,
 };
 
+/// Icon of the server list, read from the server directory
+pub const SERVER_ICON: &str = "server-icon.png";
+/// Width and height of the icon
+const ICON_SIZE: u32 = 64;
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonResponse {
@@ -47,7 +64,7 @@ pub struct Players {
 #[serde(rename_all = "camelCase")]
 pub struct Sample {
     pub name: String,
-    pub id: u128,
+    pub id: Uuid,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize)]
@@ -57,27 +74,152 @@ pub struct Description {
 }
 // ---
 
+/// Why the server icon cannot be used
+#[derive(Debug)]
+pub enum IconError {
+    IOError(std::io::Error),
+    NotPng,
+    /// width and height of the image
+    WrongSize(u32, u32),
+}
+
+impl Display for IconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IconError::IOError(err) => write!(f, "{err}"),
+            IconError::NotPng => write!(f, "the icon is not a PNG image"),
+            IconError::WrongSize(width, height) => write!(
+                f,
+                "the icon must be {ICON_SIZE}x{ICON_SIZE} pixels, not {width}x{height}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IconError {}
+
+/// The PNG image as a data URI, checking it is a 64x64 PNG
+pub fn icon_uri(png: &[u8]) -> Result<String, IconError> {
+    // the IHDR chunk comes first, with the width and the height
+    if png.len() < 24 || png[..8] != PNG_SIGNATURE || &png[12..16] != b"IHDR" {
+        return Err(IconError::NotPng);
+    }
+    let width = u32::from_be_bytes([png[16], png[17], png[18], png[19]]);
+    let height = u32::from_be_bytes([png[20], png[21], png[22], png[23]]);
+    if (width, height) != (ICON_SIZE, ICON_SIZE) {
+        return Err(IconError::WrongSize(width, height));
+    }
+    Ok(format!(
+        "data:image/png;base64,{}",
+        Base64::encode_string(png)
+    ))
+}
+
+/// The `server-icon.png` of `directory` as a data URI, `None` if there is none
+pub fn load_icon(directory: impl AsRef<Path>) -> Result<Option<String>, IconError> {
+    match fs::read(directory.as_ref().join(SERVER_ICON)) {
+        Ok(png) => icon_uri(&png).map(Some),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(IconError::IOError(err)),
+    }
+}
+
+/// Connection asking for the status
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusContext {
+    /// protocol version of the client
+    pub protocol_version: i32,
+    /// address the client connects from
+    pub address: Option<IpAddr>,
+    /// address the client used to connect to the server
+    pub server_address: String,
+}
+
+/// Changes the status sent to a client, e.g. its description
+pub type StatusHook = Box<dyn Fn(&StatusContext, &mut JsonResponse) + Send + Sync>;
+
 pub struct StatusProtocol {
+    /// version, description, favicon and chat settings
     json_response: Arc<RwLock<JsonResponse>>,
+    /// players online
+    state: Arc<RwLock<ServerState>>,
+    sample_size: usize,
+    hooks: Vec<StatusHook>,
 }
 
 impl StatusProtocol {
-    pub fn new(json_response: Arc<RwLock<JsonResponse>>) -> Self {
-        Self { json_response }
+    pub fn new(json_response: Arc<RwLock<JsonResponse>>, state: Arc<RwLock<ServerState>>) -> Self {
+        Self {
+            json_response,
+            state,
+            sample_size: DEFAULT_SAMPLE_SIZE,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Sent to the clients asking for the status, the players are filled on each request
+    pub fn json_response(&self) -> Arc<RwLock<JsonResponse>> {
+        self.json_response.clone()
+    }
+
+    /// Name at most `sample_size` random players in the server list
+    pub fn set_sample_size(&mut self, sample_size: usize) {
+        self.sample_size = sample_size;
+    }
+
+    /// Run `hook` on each status sent, after the players are filled
+    pub fn add_hook(&mut self, hook: StatusHook) {
+        self.hooks.push(hook);
+    }
+
+    /// The status for the client in `context`
+    pub fn response(&self, context: &StatusContext) -> JsonResponse {
+        let mut json_response = self
+            .json_response
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        let state = self.state.read().unwrap_or_else(|err| err.into_inner());
+        json_response.players = Players {
+            max: state.max_players as u32,
+            online: state.players.len() as u32,
+            sample: state
+                .players
+                .choose_multiple(&mut rand::thread_rng(), self.sample_size)
+                .map(|player| Sample {
+                    name: player.name.clone(),
+                    id: player.uuid,
+                })
+                .collect(),
+        };
+        drop(state);
+        // the clients of the other supported versions can join too
+        if let Some(version) = ProtocolVersion::get(context.protocol_version) {
+            json_response.version = Version {
+                name: version.name.to_string(),
+                protocol: version.protocol as u32,
+            };
+        }
+        for hook in &self.hooks {
+            hook(context, &mut json_response);
+        }
+        json_response
     }
 
     fn on_ping_request(&self, payload: i64) -> Next {
         Next::SendPacket(Pong { payload }.to_packet())
     }
 
-    fn on_status_request(&self) -> Next {
-        match self.json_response.read() {
-            Ok(json_response) => {
-                let json_response = serde_json::to_string(json_response.deref()).unwrap();
-                Next::SendPacket(StatusResponse { json_response }.to_packet())
-            }
+    fn on_status_request(&self, client: &Client) -> Next {
+        let context = StatusContext {
+            protocol_version: client.protocol_version(),
+            address: client.address(),
+            server_address: client.server_address().to_string(),
+        };
+        match serde_json::to_string(&self.response(&context)) {
+            Ok(json_response) => Next::SendPacket(StatusResponse { json_response }.to_packet()),
             Err(err) => {
-                eprintln!("{err:?}");
+                console::error(format!("{err:?}"));
                 Next::Disconnect
             }
         }
@@ -85,14 +227,83 @@ impl StatusProtocol {
 }
 
 impl PacketHandler for StatusProtocol {
-    fn handle_packet(&mut self, _: &mut Client, packet: Packet) -> Next {
+    fn handle_packet(&mut self, client: &mut Client, packet: Packet) -> Next {
         match packet.id() {
             Ping::ID => {
                 let Ping { payload } = Ping::from_packet(packet).unwrap();
                 self.on_ping_request(payload)
             }
-            StatusRequest::ID => self.on_status_request(),
+            StatusRequest::ID => self.on_status_request(client),
             _ => Next::Disconnect,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use uuid::Uuid;
+
+    use super::{icon_uri, IconError, JsonResponse, StatusContext, StatusProtocol, PNG_SIGNATURE};
+    use crate::handler::server::{OnlinePlayer, ServerState};
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(13u32.to_be_bytes());
+        png.extend(b"IHDR");
+        png.extend(width.to_be_bytes());
+        png.extend(height.to_be_bytes());
+        png
+    }
+
+    #[test]
+    fn test_icon() {
+        assert!(icon_uri(&png(64, 64))
+            .unwrap()
+            .starts_with("data:image/png;base64,iVBORw0KGgo"));
+        assert!(matches!(
+            icon_uri(&png(128, 64)),
+            Err(IconError::WrongSize(128, 64))
+        ));
+        assert!(matches!(icon_uri(b"GIF89a"), Err(IconError::NotPng)));
+    }
+
+    #[test]
+    fn test_response() {
+        let player = |name: &str, uuid| OnlinePlayer {
+            name: name.to_string(),
+            uuid: Uuid::from_u128(uuid),
+            position: Default::default(),
+            rotation: (0.0, 0.0),
+            latency: 0,
+        };
+        let state = ServerState {
+            players: vec![player("Alice", 1), player("Bob", 2), player("Carol", 3)],
+            max_players: 10,
+            ..Default::default()
+        };
+        let mut status = StatusProtocol::new(
+            Arc::new(RwLock::new(JsonResponse::default())),
+            Arc::new(RwLock::new(state)),
+        );
+        status.set_sample_size(2);
+        status.add_hook(Box::new(|context, json_response| {
+            json_response.description.text = format!("protocol {}", context.protocol_version);
+        }));
+        let context = StatusContext {
+            protocol_version: 767,
+            address: None,
+            server_address: "localhost".to_string(),
+        };
+        let response = status.response(&context);
+        assert_eq!((response.players.online, response.players.max), (3, 10));
+        assert_eq!(response.players.sample.len(), 2);
+        assert_eq!(response.description.text, "protocol 767");
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json["players"]["sample"][0]["id"]
+            .as_str()
+            .is_some_and(|id| id.starts_with("00000000-0000-0000-0000-00000000000")));
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+use zr_binary::varint::VarInt;
+use zr_core::entity::player::GameMode;
+use zr_nbt::Nbt;
+use zr_network::packet::{packet::Packet, PacketData};
+
+use crate::packet::{
+    config::registry::chat_type::decoration::TextComponent,
+    login::server::Property,
+    play::server::{
+        AddPlayer, ChatSessionData, PlayerActions, PlayerInfoRemove, PlayerInfoUpdate,
+        SetTabListHeaderAndFooter,
+    },
+};
+
+/// Actions of the Player Info Update packet
+pub mod action {
+    pub const ADD_PLAYER: u8 = 0x01;
+    pub const INITIALIZE_CHAT: u8 = 0x02;
+    pub const UPDATE_GAME_MODE: u8 = 0x04;
+    pub const UPDATE_LISTED: u8 = 0x08;
+    pub const UPDATE_LATENCY: u8 = 0x10;
+    pub const UPDATE_DISPLAY_NAME: u8 = 0x20;
+    /// Everything a client needs to show a new entry
+    pub const ALL: u8 = ADD_PLAYER
+        | INITIALIZE_CHAT
+        | UPDATE_GAME_MODE
+        | UPDATE_LISTED
+        | UPDATE_LATENCY
+        | UPDATE_DISPLAY_NAME;
+}
+
+/// Player shown in the player list
+#[derive(Debug, Clone, PartialEq)]
+pub struct TabEntry {
+    pub uuid: Uuid,
+    pub name: String,
+    /// game profile properties, the skin of the player
+    pub properties: Vec<Property>,
+    /// session to verify the signed messages of the player, `None` if it sent none
+    pub chat_session: Option<ChatSessionData>,
+    pub game_mode: GameMode,
+    /// in milliseconds
+    pub latency: i32,
+    /// shown instead of the name
+    pub display_name: Option<TextComponent>,
+    /// hidden from the list when `false`
+    pub listed: bool,
+}
+
+impl TabEntry {
+    pub fn new(uuid: Uuid, name: String, properties: Vec<Property>) -> Self {
+        Self {
+            uuid,
+            name,
+            properties,
+            chat_session: None,
+            game_mode: GameMode::default(),
+            latency: 0,
+            display_name: None,
+            listed: true,
+        }
+    }
+
+    /// Fields of the entry for the `actions` bit set
+    fn actions(&self, actions: u8) -> PlayerActions {
+        let has = |action: u8| actions & action != 0;
+        let chat_session = has(action::INITIALIZE_CHAT);
+        let display_name = has(action::UPDATE_DISPLAY_NAME);
+        PlayerActions {
+            uuid: self.uuid,
+            add_player: has(action::ADD_PLAYER).then(|| AddPlayer {
+                name: self.name.clone(),
+                properties: self.properties.clone(),
+            }),
+            has_chat_session: chat_session.then_some(self.chat_session.is_some()),
+            chat_session: self.chat_session.clone().filter(|_| chat_session),
+            game_mode: has(action::UPDATE_GAME_MODE)
+                .then(|| VarInt::new(self.game_mode.id() as i32)),
+            listed: has(action::UPDATE_LISTED).then_some(self.listed),
+            latency: has(action::UPDATE_LATENCY).then(|| VarInt::new(self.latency)),
+            has_display_name: display_name.then_some(self.display_name.is_some()),
+            display_name: self
+                .display_name
+                .clone()
+                .filter(|_| display_name)
+                .map(Nbt::new),
+        }
+    }
+}
+
+/// Players shown in the player list with its header and footer,
+/// the changes are sent to the players on the next tick
+#[derive(Debug, Clone)]
+pub struct TabList {
+    /// in the order they joined
+    entries: Vec<TabEntry>,
+    header: TextComponent,
+    footer: TextComponent,
+    /// actions to send for the entries changed since the last tick
+    changes: HashMap<Uuid, u8>,
+    removed: Vec<Uuid>,
+    header_changed: bool,
+}
+
+impl Default for TabList {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            header: TextComponent::text(""),
+            footer: TextComponent::text(""),
+            changes: HashMap::new(),
+            removed: Vec::new(),
+            header_changed: false,
+        }
+    }
+}
+
+impl TabList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[TabEntry] {
+        &self.entries
+    }
+
+    pub fn entry(&self, uuid: Uuid) -> Option<&TabEntry> {
+        self.entries.iter().find(|entry| entry.uuid == uuid)
+    }
+
+    /// Add an entry, or replace the one with the same uuid
+    pub fn add(&mut self, entry: TabEntry) {
+        let uuid = entry.uuid;
+        match self.entries.iter_mut().find(|known| known.uuid == uuid) {
+            Some(known) => {
+                // the clients only read the profile of the entries they do not know
+                self.removed.push(uuid);
+                *known = entry;
+            }
+            None => self.entries.push(entry),
+        }
+        self.changes.insert(uuid, action::ALL);
+    }
+
+    pub fn remove(&mut self, uuid: Uuid) -> Option<TabEntry> {
+        let index = self.entries.iter().position(|entry| entry.uuid == uuid)?;
+        self.changes.remove(&uuid);
+        self.removed.push(uuid);
+        Some(self.entries.remove(index))
+    }
+
+    /// Apply `update` to the entry and record `action` when it changes it
+    fn update(&mut self, uuid: Uuid, action: u8, update: impl FnOnce(&mut TabEntry)) -> bool {
+        let Some(entry) = self.entries.iter_mut().find(|entry| entry.uuid == uuid) else {
+            return false;
+        };
+        let before = entry.clone();
+        update(entry);
+        if *entry != before {
+            *self.changes.entry(uuid).or_default() |= action;
+        }
+        true
+    }
+
+    /// `false` if the player is not in the list
+    pub fn set_chat_session(&mut self, uuid: Uuid, session: Option<ChatSessionData>) -> bool {
+        self.update(uuid, action::INITIALIZE_CHAT, |entry| {
+            entry.chat_session = session
+        })
+    }
+
+    /// `false` if the player is not in the list
+    pub fn set_game_mode(&mut self, uuid: Uuid, game_mode: GameMode) -> bool {
+        self.update(uuid, action::UPDATE_GAME_MODE, |entry| {
+            entry.game_mode = game_mode
+        })
+    }
+
+    /// `false` if the player is not in the list
+    pub fn set_latency(&mut self, uuid: Uuid, latency: i32) -> bool {
+        self.update(uuid, action::UPDATE_LATENCY, |entry| {
+            entry.latency = latency
+        })
+    }
+
+    /// `false` if the player is not in the list
+    pub fn set_display_name(&mut self, uuid: Uuid, display_name: Option<TextComponent>) -> bool {
+        self.update(uuid, action::UPDATE_DISPLAY_NAME, |entry| {
+            entry.display_name = display_name
+        })
+    }
+
+    /// `false` if the player is not in the list
+    pub fn set_listed(&mut self, uuid: Uuid, listed: bool) -> bool {
+        self.update(uuid, action::UPDATE_LISTED, |entry| entry.listed = listed)
+    }
+
+    pub fn header(&self) -> &TextComponent {
+        &self.header
+    }
+
+    pub fn footer(&self) -> &TextComponent {
+        &self.footer
+    }
+
+    pub fn set_header(&mut self, header: TextComponent) {
+        self.header = header;
+        self.header_changed = true;
+    }
+
+    pub fn set_footer(&mut self, footer: TextComponent) {
+        self.footer = footer;
+        self.header_changed = true;
+    }
+
+    fn header_packet(&self) -> Packet {
+        SetTabListHeaderAndFooter {
+            header: Nbt::new(self.header.clone()),
+            footer: Nbt::new(self.footer.clone()),
+        }
+        .to_packet()
+    }
+
+    /// The whole list, for a player joining
+    pub fn packets(&self) -> Vec<Packet> {
+        let mut packets = Vec::new();
+        if !self.entries.is_empty() {
+            packets.push(
+                PlayerInfoUpdate {
+                    actions: action::ALL,
+                    players: self
+                        .entries
+                        .iter()
+                        .map(|entry| entry.actions(action::ALL))
+                        .collect(),
+                }
+                .to_packet(),
+            );
+        }
+        packets.push(self.header_packet());
+        packets
+    }
+
+    /// The changes since the last call, for the players already knowing the list
+    pub fn take_changes(&mut self) -> Vec<Packet> {
+        let mut packets = Vec::new();
+        if !self.removed.is_empty() {
+            packets.push(
+                PlayerInfoRemove {
+                    players: std::mem::take(&mut self.removed),
+                }
+                .to_packet(),
+            );
+        }
+        // the players of a packet all have the same actions
+        let mut updates: HashMap<u8, Vec<PlayerActions>> = HashMap::new();
+        for entry in &self.entries {
+            if let Some(actions) = self.changes.get(&entry.uuid) {
+                updates
+                    .entry(*actions)
+                    .or_default()
+                    .push(entry.actions(*actions));
+            }
+        }
+        self.changes.clear();
+        packets.extend(
+            updates
+                .into_iter()
+                .map(|(actions, players)| PlayerInfoUpdate { actions, players }.to_packet()),
+        );
+        if std::mem::take(&mut self.header_changed) {
+            packets.push(self.header_packet());
+        }
+        packets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+    use zr_core::entity::player::GameMode;
+
+    use zr_network::packet::{packet::Packet, PacketData};
+
+    use super::{action, TabEntry, TabList};
+    use crate::packet::{
+        config::registry::chat_type::decoration::TextComponent,
+        play::server::{ChatSessionData, PlayerInfoRemove, PlayerInfoUpdate},
+    };
+
+    /// The update of `actions` for the entries of `players`
+    fn update(tab_list: &TabList, actions: u8, players: &[Uuid]) -> Packet {
+        PlayerInfoUpdate {
+            actions,
+            players: players
+                .iter()
+                .map(|uuid| tab_list.entry(*uuid).unwrap().actions(actions))
+                .collect(),
+        }
+        .to_packet()
+    }
+
+    #[test]
+    fn test_changes() {
+        let mut tab_list = TabList::new();
+        let alice = Uuid::from_u128(1);
+        let bob = Uuid::from_u128(2);
+        tab_list.add(TabEntry::new(alice, "Alice".to_string(), vec![]));
+        assert_eq!(
+            tab_list.take_changes(),
+            [update(&tab_list, action::ALL, &[alice])]
+        );
+        assert!(tab_list.take_changes().is_empty());
+
+        assert!(tab_list.set_latency(alice, 0));
+        assert!(tab_list.take_changes().is_empty());
+        assert!(!tab_list.set_latency(bob, 50));
+
+        tab_list.add(TabEntry::new(bob, "Bob".to_string(), vec![]));
+        tab_list.set_game_mode(alice, GameMode::Creative);
+        tab_list.set_header(TextComponent::text("Welcome"));
+        let changes = tab_list.take_changes();
+        // Bob added and Alice updated in any order, then the header
+        assert_eq!(changes.len(), 3);
+        assert!(changes.contains(&update(&tab_list, action::ALL, &[bob])));
+        assert!(changes.contains(&update(&tab_list, action::UPDATE_GAME_MODE, &[alice])));
+        assert_eq!(changes[2], tab_list.header_packet());
+
+        tab_list.set_latency(alice, 20);
+        tab_list.set_latency(bob, 30);
+        let changes = tab_list.take_changes();
+        // the entries with the same actions share a packet, in the order they joined
+        assert_eq!(
+            changes,
+            [update(&tab_list, action::UPDATE_LATENCY, &[alice, bob])]
+        );
+
+        tab_list.set_listed(bob, false);
+        tab_list.remove(bob);
+        // only the removal of Bob
+        assert_eq!(
+            tab_list.take_changes(),
+            [PlayerInfoRemove { players: vec![bob] }.to_packet()]
+        );
+        assert_eq!(tab_list.entries().len(), 1);
+        let entry = tab_list.entry(alice).unwrap();
+        assert_eq!(entry.game_mode, GameMode::Creative);
+        assert_eq!(entry.latency, 20);
+    }
+
+    #[test]
+    fn test_chat_session() {
+        let mut tab_list = TabList::new();
+        let alice = Uuid::from_u128(1);
+        tab_list.add(TabEntry::new(alice, "Alice".to_string(), vec![]));
+        tab_list.take_changes();
+
+        let session = ChatSessionData {
+            session_id: Uuid::from_u128(2),
+            expires_at: 3,
+            public_key: vec![4, 5],
+            key_signature: vec![6],
+        };
+        assert!(tab_list.set_chat_session(alice, Some(session.clone())));
+        let changes = tab_list.take_changes();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].id(), PlayerInfoUpdate::ID);
+        let mut data = vec![action::INITIALIZE_CHAT, 1];
+        data.extend(alice.as_bytes());
+        data.push(1);
+        data.extend(session.session_id.as_bytes());
+        data.extend(3i64.to_be_bytes());
+        data.extend([2, 4, 5, 1, 6]);
+        assert_eq!(changes[0].raw_data(), &data);
+
+        // the same session is not sent again
+        assert!(tab_list.set_chat_session(alice, Some(session)));
+        assert!(tab_list.take_changes().is_empty());
+        assert!(tab_list.entry(alice).unwrap().chat_session.is_some());
+    }
+}
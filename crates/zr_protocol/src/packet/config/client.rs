@@ -1,16 +1,33 @@
 use zr_binary::varint::VarInt;
 use zr_binary_macros::Binary;
+use zr_data_type::string::Identifier;
 use zr_network_macros::Packet;
 
 use super::server::Pack;
+use crate::packet::login::client::CookiePayload;
+
+#[derive(Binary, Packet)]
+#[id = 0x01]
+pub struct CookieResponse {
+    pub(crate) key: Identifier,
+    pub(crate) has_payload: bool,
+    #[some_if = "self.has_payload"]
+    pub(crate) payload: Option<CookiePayload>,
+}
 
 #[derive(Binary, Packet)]
 #[id = 0x03]
 pub struct AcknowledgeFinishConfiguration;
 
+#[derive(Binary, Packet)]
+#[id = 0x04]
+pub struct KeepAlive {
+    pub(crate) keep_alive_id: i64,
+}
+
 #[derive(Binary, Packet)]
 #[id = 0x07]
 pub struct KnownPacks {
     #[prefixed_length = "VarInt<i32>"]
-    known_packs: Vec<Pack>,
+    pub(crate) known_packs: Vec<Pack>,
 }
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ContentField {
     Text {
@@ -8,6 +8,7 @@ pub enum ContentField {
     },
     Translatable {
         translate: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
         with: Option<Vec<Box<TextComponent>>>,
     },
     KeyBind {
@@ -18,30 +19,35 @@ pub enum ContentField {
     },
     Selector {
         selector: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
         separator: Option<Box<TextComponent>>,
     },
     Nbt {
         nbt: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
         interpret: Option<i8>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         block: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         entity: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         storage: Option<String>,
     },
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Score {
     name: String,
     objectives: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ClickEvent {
     action: String,
     value: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum HoverEvent {
     ShowText {
@@ -49,32 +55,45 @@ pub enum HoverEvent {
     },
     ShowItem {
         id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
         count: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         tag: Option<String>,
     },
     ShowEntity {
         #[serde(rename = "type")]
         type_field: String,
         id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
     },
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Style {
+    #[serde(skip_serializing_if = "Option::is_none")]
     color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     bold: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     italic: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     underlined: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     strikethrough: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     obfuscated: Option<i8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     font: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     insertion: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     click_event: Option<ClickEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     hover_event: Option<HoverEvent>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TextComponent {
     #[serde(flatten)]
     contents: Option<ContentField>,
@@ -82,9 +101,200 @@ pub struct TextComponent {
     style: Option<Style>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Decoration {
     translation_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     style: Option<TextComponent>,
     parameters: Vec<String>,
 }
+
+impl Style {
+    pub fn color(color: &str) -> Self {
+        Self {
+            color: Some(color.to_string()),
+            bold: None,
+            italic: None,
+            underlined: None,
+            strikethrough: None,
+            obfuscated: None,
+            font: None,
+            insertion: None,
+            click_event: None,
+            hover_event: None,
+        }
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = Some(1);
+        self
+    }
+}
+
+impl TextComponent {
+    pub fn text(text: &str) -> Self {
+        Self {
+            contents: Some(ContentField::Text {
+                text: text.to_string(),
+            }),
+            style: None,
+        }
+    }
+
+    pub fn translate(key: &str, with: Vec<TextComponent>) -> Self {
+        Self {
+            contents: Some(ContentField::Translatable {
+                translate: key.to_string(),
+                with: Some(with.into_iter().map(Box::new).collect()),
+            }),
+            style: None,
+        }
+    }
+
+    pub fn with_style(mut self, style: Option<Style>) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn style(&self) -> Option<&Style> {
+        self.style.as_ref()
+    }
+
+    /// Text shown by the client, `translation` gives the format of a translation key
+    /// where each `%s` is replaced by the next argument and `%1$s` by the first one.
+    /// A key without translation is its own format, as the client does
+    pub fn to_plain(&self, translation: &impl Fn(&str) -> Option<String>) -> String {
+        match &self.contents {
+            Some(ContentField::Text { text }) => text.clone(),
+            Some(ContentField::Translatable { translate, with }) => {
+                let format = translation(translate).unwrap_or_else(|| translate.clone());
+                let arguments: Vec<String> = with
+                    .iter()
+                    .flatten()
+                    .map(|argument| argument.to_plain(translation))
+                    .collect();
+                format_translation(&format, &arguments)
+            }
+            Some(ContentField::KeyBind { key_bind }) => key_bind.clone(),
+            Some(ContentField::Selector { selector, .. }) => selector.clone(),
+            Some(ContentField::Score { .. } | ContentField::Nbt { .. }) | None => String::new(),
+        }
+    }
+}
+
+/// Replace the `%s`, `%n$s` and `%%` of a translation
+fn format_translation(format: &str, arguments: &[String]) -> String {
+    let mut result = String::new();
+    let mut next = 0;
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        let mut position = String::new();
+        while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+            position.push(digit);
+        }
+        let index = match (position.is_empty(), chars.next_if_eq(&'$')) {
+            (true, _) => None,
+            (false, Some(_)) => position.parse::<usize>().ok().map(|index| index - 1),
+            // not a placeholder
+            (false, None) => {
+                result.push('%');
+                result.push_str(&position);
+                continue;
+            }
+        };
+        match chars.next() {
+            Some('s') => {
+                let index = index.unwrap_or_else(|| {
+                    next += 1;
+                    next - 1
+                });
+                result.push_str(arguments.get(index).map_or("", String::as_str));
+            }
+            Some('%') => result.push('%'),
+            Some(other) => {
+                result.push('%');
+                result.push(other);
+            }
+            None => result.push('%'),
+        }
+    }
+    result
+}
+
+impl Decoration {
+    /// `parameters` among `sender`, `target` and `content`
+    pub fn new(translation_key: &str, parameters: &[&str], style: Option<Style>) -> Self {
+        Self {
+            translation_key: translation_key.to_string(),
+            style: style.map(|style| TextComponent {
+                contents: None,
+                style: Some(style),
+            }),
+            parameters: parameters
+                .iter()
+                .map(|parameter| parameter.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn translation_key(&self) -> &str {
+        &self.translation_key
+    }
+
+    /// The message decorated with its parameters, a missing target is empty
+    pub fn decorate(&self, sender: &str, target: Option<&str>, content: &str) -> TextComponent {
+        let with = self
+            .parameters
+            .iter()
+            .map(|parameter| {
+                TextComponent::text(match parameter.as_str() {
+                    "sender" => sender,
+                    "target" => target.unwrap_or_default(),
+                    "content" => content,
+                    _ => "",
+                })
+            })
+            .collect();
+        TextComponent::translate(&self.translation_key, with)
+            .with_style(self.style.as_ref().and_then(|style| style.style.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_translation, Decoration, Style, TextComponent};
+
+    #[test]
+    fn test_format_translation() {
+        let arguments = ["a".to_string(), "b".to_string()];
+        assert_eq!(format_translation("<%s> %s", &arguments), "<a> b");
+        assert_eq!(format_translation("%2$s then %1$s", &arguments), "b then a");
+        assert_eq!(format_translation("100%% %s %d", &arguments), "100% a %d");
+    }
+
+    #[test]
+    fn test_decorate() {
+        let decoration = Decoration::new(
+            "commands.message.display.incoming",
+            &["sender", "content"],
+            Some(Style::color("gray").italic()),
+        );
+        let component = decoration.decorate("Alice", None, "hi");
+        assert_eq!(component.style(), Some(&Style::color("gray").italic()));
+        let plain = component.to_plain(&|key| {
+            (key == "commands.message.display.incoming").then(|| "%s whispers to you: %s".into())
+        });
+        assert_eq!(plain, "Alice whispers to you: hi");
+        // custom chat types of the data packs use their key as format
+        let custom = Decoration::new("[%s] %s", &["sender", "content"], None);
+        assert_eq!(
+            custom.decorate("Bob", None, "yo").to_plain(&|_| None),
+            "[Bob] yo"
+        );
+        assert_eq!(TextComponent::text("x").to_plain(&|_| None), "x");
+    }
+}
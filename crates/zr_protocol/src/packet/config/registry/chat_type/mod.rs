@@ -1,10 +1,256 @@
-use decoration::Decoration;
+use std::str::FromStr;
+
+use decoration::{Decoration, Style, TextComponent};
 use serde::{Deserialize, Serialize};
+use zr_data_type::{error::MalformedError, string::Identifier};
+
+use crate::packet::config::server::{RegistryData, RegistryEntry};
 
 pub mod decoration;
 
-#[derive(Clone, Serialize, Deserialize)]
+pub const CHAT: &str = "minecraft:chat";
+pub const SAY_COMMAND: &str = "minecraft:say_command";
+pub const MSG_COMMAND_INCOMING: &str = "minecraft:msg_command_incoming";
+pub const MSG_COMMAND_OUTGOING: &str = "minecraft:msg_command_outgoing";
+pub const TEAM_MSG_COMMAND_INCOMING: &str = "minecraft:team_msg_command_incoming";
+pub const TEAM_MSG_COMMAND_OUTGOING: &str = "minecraft:team_msg_command_outgoing";
+pub const EMOTE_COMMAND: &str = "minecraft:emote_command";
+pub const REGISTRY: &str = "minecraft:chat_type";
+
+/// English text of the vanilla chat translation keys
+const TRANSLATIONS: &[(&str, &str)] = &[
+    ("chat.type.text", "<%s> %s"),
+    ("chat.type.text.narrate", "%s says %s"),
+    ("chat.type.announcement", "[%s] %s"),
+    ("chat.type.emote", "* %s %s"),
+    ("chat.type.team.text", "%s <%s> %s"),
+    ("chat.type.team.sent", "-> %s <%s> %s"),
+    (
+        "commands.message.display.incoming",
+        "%s whispers to you: %s",
+    ),
+    ("commands.message.display.outgoing", "You whisper to %s: %s"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChatType {
     chat: Decoration,
     narration: Decoration,
 }
+
+impl ChatType {
+    pub fn new(chat: Decoration, narration: Decoration) -> Self {
+        Self { chat, narration }
+    }
+
+    pub fn chat(&self) -> &Decoration {
+        &self.chat
+    }
+
+    pub fn narration(&self) -> &Decoration {
+        &self.narration
+    }
+}
+
+/// Chat types by name, in the order of their registry ids, decorating the messages on the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatFormatter {
+    types: Vec<(String, ChatType)>,
+}
+
+impl Default for ChatFormatter {
+    /// The vanilla chat types
+    fn default() -> Self {
+        let narrate = Decoration::new("chat.type.text.narrate", &["sender", "content"], None);
+        let whisper = || Some(Style::color("gray").italic());
+        let types = [
+            (
+                CHAT,
+                Decoration::new("chat.type.text", &["sender", "content"], None),
+            ),
+            (
+                SAY_COMMAND,
+                Decoration::new("chat.type.announcement", &["sender", "content"], None),
+            ),
+            (
+                MSG_COMMAND_INCOMING,
+                Decoration::new(
+                    "commands.message.display.incoming",
+                    &["sender", "content"],
+                    whisper(),
+                ),
+            ),
+            (
+                MSG_COMMAND_OUTGOING,
+                Decoration::new(
+                    "commands.message.display.outgoing",
+                    &["target", "content"],
+                    whisper(),
+                ),
+            ),
+            (
+                TEAM_MSG_COMMAND_INCOMING,
+                Decoration::new(
+                    "chat.type.team.text",
+                    &["target", "sender", "content"],
+                    None,
+                ),
+            ),
+            (
+                TEAM_MSG_COMMAND_OUTGOING,
+                Decoration::new(
+                    "chat.type.team.sent",
+                    &["target", "sender", "content"],
+                    None,
+                ),
+            ),
+        ];
+        let mut formatter = Self {
+            types: types
+                .into_iter()
+                .map(|(name, chat)| (name.to_string(), ChatType::new(chat, narrate.clone())))
+                .collect(),
+        };
+        let emote = Decoration::new("chat.type.emote", &["sender", "content"], None);
+        formatter.types.push((
+            EMOTE_COMMAND.to_string(),
+            ChatType::new(emote.clone(), emote),
+        ));
+        formatter
+    }
+}
+
+impl ChatFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace a chat type, usually from a data pack, sent to the players joining after
+    pub fn register(&mut self, name: &str, chat_type: ChatType) -> Result<(), MalformedError> {
+        Identifier::from_str(name)?;
+        match self.types.iter_mut().find(|(known, _)| known == name) {
+            Some((_, known)) => *known = chat_type,
+            None => self.types.push((name.to_string(), chat_type)),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ChatType> {
+        self.types
+            .iter()
+            .find(|(known, _)| known == name)
+            .map(|(_, chat_type)| chat_type)
+    }
+
+    /// Registry id of the chat type
+    pub fn id(&self, name: &str) -> Option<i32> {
+        self.types
+            .iter()
+            .position(|(known, _)| known == name)
+            .map(|id| id as i32)
+    }
+
+    /// The chat types sent to the players, in the order of `id`
+    pub fn registry_data(&self) -> RegistryData {
+        RegistryData {
+            registry_id: Identifier::from_str(REGISTRY).unwrap(),
+            entries: self
+                .types
+                .iter()
+                // the names are checked by `register`
+                .filter_map(|(name, chat_type)| {
+                    let id = Identifier::from_str(name).ok()?;
+                    Some(RegistryEntry::new(id, chat_type.clone()))
+                })
+                .collect(),
+        }
+    }
+
+    /// The message as the client shows it with the chat type `name`
+    pub fn decorate(
+        &self,
+        name: &str,
+        sender: &str,
+        target: Option<&str>,
+        content: &str,
+    ) -> Option<TextComponent> {
+        self.get(name)
+            .map(|chat_type| chat_type.chat.decorate(sender, target, content))
+    }
+
+    /// The component in English, for the console
+    pub fn to_plain(&self, component: &TextComponent) -> String {
+        component.to_plain(&|key| {
+            TRANSLATIONS
+                .iter()
+                .find(|(known, _)| *known == key)
+                .map(|(_, translation)| translation.to_string())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_binary::binary::Binary;
+    use zr_nbt::Nbt;
+
+    use super::{
+        decoration::Decoration, ChatFormatter, ChatType, CHAT, EMOTE_COMMAND, MSG_COMMAND_OUTGOING,
+        REGISTRY,
+    };
+
+    #[test]
+    fn test_formatter() {
+        let mut formatter = ChatFormatter::new();
+        assert_eq!(formatter.id(CHAT), Some(0));
+        assert_eq!(formatter.id(EMOTE_COMMAND), Some(6));
+        let plain = |formatter: &ChatFormatter, name, target| {
+            formatter
+                .decorate(name, "Alice", target, "hello")
+                .map(|component| formatter.to_plain(&component))
+        };
+        assert_eq!(plain(&formatter, CHAT, None).unwrap(), "<Alice> hello");
+        assert_eq!(
+            plain(&formatter, MSG_COMMAND_OUTGOING, Some("Bob")).unwrap(),
+            "You whisper to Bob: hello"
+        );
+        assert_eq!(plain(&formatter, "example:shout", None), None);
+
+        let shout = Decoration::new("%s shouts %s!", &["sender", "content"], None);
+        formatter
+            .register("example:shout", ChatType::new(shout.clone(), shout.clone()))
+            .unwrap();
+        assert_eq!(formatter.id("example:shout"), Some(7));
+        assert_eq!(
+            plain(&formatter, "example:shout", None).unwrap(),
+            "Alice shouts hello!"
+        );
+        assert!(formatter
+            .register("Example:Shout!", ChatType::new(shout.clone(), shout))
+            .is_err());
+    }
+
+    #[test]
+    fn test_registry_data() {
+        let mut formatter = ChatFormatter::new();
+        let shout = Decoration::new("%s shouts %s!", &["sender", "content"], None);
+        let chat_type = ChatType::new(shout.clone(), shout);
+        formatter
+            .register("example:shout", chat_type.clone())
+            .unwrap();
+
+        let registry = formatter.registry_data();
+        assert_eq!(registry.registry_id.to_string(), REGISTRY);
+        // the position of an entry is the id of the chat type
+        assert_eq!(registry.entries.len(), 8);
+        for (id, entry) in registry.entries.iter().enumerate() {
+            assert_eq!(formatter.id(&entry.id.to_string()), Some(id as i32));
+        }
+        let shout = &registry.entries[7];
+        assert!(shout.has_data);
+        assert_eq!(
+            shout.data.clone().unwrap().0,
+            Nbt::new(chat_type).to_binary()
+        );
+    }
+}
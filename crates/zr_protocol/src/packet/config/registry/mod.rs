@@ -1,13 +1,15 @@
 use armor_trim::{material::ArmorTrimMaterial, pattern::ArmorTrimPattern};
 use banner_pattern::BannerPattern;
 use biome::Biome;
-use chat_type::ChatType;
 use damage_type::DamageType;
 use dimension_type::DimensionType;
 use painting_variant::PaintingVariant;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 use wolf_variant::WolfVariant;
+use zr_data_type::string::Identifier;
+
+use super::server::{RegistryData, RegistryEntry};
 
 pub mod armor_trim;
 pub mod banner_pattern;
@@ -18,6 +20,8 @@ pub mod dimension_type;
 pub mod painting_variant;
 pub mod wolf_variant;
 
+/// Registries sent to the players while configuring,
+/// the chat types are sent from the `ChatFormatter` of the server
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Registry {
     #[serde(rename = "minecraft:trim_material")]
@@ -28,8 +32,6 @@ pub struct Registry {
     banner_pattern: Option<HashMap<String, BannerPattern>>,
     #[serde(rename = "minecraft:worldgen/biome")]
     biome: Option<HashMap<String, Biome>>,
-    #[serde(rename = "minecraft:chat_type")]
-    chat_type: Option<HashMap<String, ChatType>>,
     #[serde(rename = "minecraft:damage_type")]
     damage_type: Option<HashMap<String, DamageType>>,
     #[serde(rename = "minecraft:dimension_type")]
@@ -40,6 +42,46 @@ pub struct Registry {
     painting_variant: Option<HashMap<String, PaintingVariant>>,
 }
 
+/// The entries sorted by name, the malformed names are left out
+fn registry_data<T>(registry_id: &str, entries: &Option<HashMap<String, T>>) -> Option<RegistryData>
+where
+    T: Serialize + for<'a> Deserialize<'a> + Clone,
+{
+    let mut entries: Vec<_> = entries.as_ref()?.iter().collect();
+    entries.sort_by_key(|(name, _)| *name);
+    Some(RegistryData {
+        registry_id: Identifier::from_str(registry_id).ok()?,
+        entries: entries
+            .into_iter()
+            .filter_map(|(name, entry)| {
+                Some(RegistryEntry::new(
+                    Identifier::from_str(name).ok()?,
+                    entry.clone(),
+                ))
+            })
+            .collect(),
+    })
+}
+
+impl Registry {
+    /// One packet per registry
+    pub fn registry_data(&self) -> Vec<RegistryData> {
+        [
+            registry_data("minecraft:trim_material", &self.armor_trim_material),
+            registry_data("minecraft:trim_pattern", &self.armor_trim_pattern),
+            registry_data("minecraft:banner_pattern", &self.banner_pattern),
+            registry_data("minecraft:worldgen/biome", &self.biome),
+            registry_data("minecraft:damage_type", &self.damage_type),
+            registry_data("minecraft:dimension_type", &self.dimension_type),
+            registry_data("minecraft:wolf_variant", &self.wolf_variant),
+            registry_data("minecraft:painting_variant", &self.painting_variant),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use zr_nbt::Nbt;
@@ -52,7 +94,6 @@ pub mod test {
             include_str!("/home/zirkonya/Desktop/workspace/rust/minecraft-server/1.20.6.json");
 
         let nbt: Nbt<Registry> = serde_json::from_str::<Nbt<Registry>>(json).unwrap();
-        let json_2 = serde_json::to_string(&nbt).unwrap();
-        println!("{json_2}");
+        serde_json::to_string(&nbt).unwrap();
     }
 }
@@ -1,14 +1,78 @@
-use zr_binary::varint::VarInt;
+use serde::{Deserialize, Serialize};
+use zr_binary::{binary::Binary, varint::VarInt};
 use zr_binary_macros::Binary;
-use zr_nbt::Nbt;
+use zr_data_type::string::Identifier;
+use zr_nbt::{raw::RawNbt, Nbt};
 use zr_network_macros::Packet;
 
-use super::registry::Registry;
+use super::registry::chat_type::decoration::TextComponent;
+
+#[derive(Binary, Packet)]
+#[id = 0x00]
+pub struct CookieRequest {
+    pub(crate) key: Identifier,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x02]
+pub struct Disconnect {
+    pub(crate) reason: Nbt<TextComponent>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x03]
+pub struct FinishConfiguration;
+
+#[derive(Binary, Packet)]
+#[id = 0x04]
+pub struct KeepAlive {
+    pub(crate) keep_alive_id: i64,
+}
+
+/// Entry of a registry, its id is its position in the packet
+#[derive(Debug, Binary, Clone, PartialEq)]
+pub struct RegistryEntry {
+    pub(crate) id: Identifier,
+    /// `false` to take the entry from a known pack
+    pub(crate) has_data: bool,
+    #[some_if = "self.has_data"]
+    pub(crate) data: Option<RawNbt>,
+}
+
+impl RegistryEntry {
+    pub fn new<T>(id: Identifier, data: T) -> Self
+    where
+        T: Serialize + for<'a> Deserialize<'a> + Clone,
+    {
+        Self {
+            id,
+            has_data: true,
+            data: Some(RawNbt(Nbt::new(data).to_binary())),
+        }
+    }
+}
 
 #[derive(Binary, Packet)]
 #[id = 0x07]
 pub struct RegistryData {
-    pub(crate) registry_codec: Nbt<Registry>,
+    pub(crate) registry_id: Identifier,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) entries: Vec<RegistryEntry>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x0A]
+pub struct StoreCookie {
+    pub(crate) key: Identifier,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) payload: Vec<u8>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x0B]
+pub struct Transfer {
+    pub(crate) host: String,
+    pub(crate) port: VarInt<i32>,
 }
 
 #[derive(Binary, Clone)]
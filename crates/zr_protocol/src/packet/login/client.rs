@@ -1,5 +1,6 @@
 use zr_binary::varint::VarInt;
 use zr_binary_macros::Binary;
+use zr_data_type::string::Identifier;
 use zr_network_macros::Packet;
 use uuid::Uuid;
 
@@ -23,7 +24,7 @@ pub struct EncryptionResponse {
 #[id = 0x02]
 pub struct LoginPluginResponse {
     pub(crate) message_id: VarInt<i32>,
-    successful: bool,
+    pub(crate) successful: bool,
     #[some_if = "self.successful"]
     pub(crate) data: Option<Vec<u8>>,
 }
@@ -32,3 +33,19 @@ pub struct LoginPluginResponse {
 #[id = 0x03]
 pub struct LoginAcknowledge;
 
+/// Data of a cookie stored on the client
+#[derive(Debug, Binary, Clone)]
+pub struct CookiePayload {
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) data: Vec<u8>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x04]
+pub struct CookieResponse {
+    pub(crate) key: Identifier,
+    pub(crate) has_payload: bool,
+    #[some_if = "self.has_payload"]
+    pub(crate) payload: Option<CookiePayload>,
+}
+
@@ -20,7 +20,7 @@ pub struct EncryptionRequest {
     pub(crate) verify_token: Vec<u8>,
 }
 
-#[derive(Debug, Binary, Clone, Default)]
+#[derive(Debug, Binary, Clone, Default, PartialEq)]
 pub struct Property {
     pub name: String,
     pub value: String,
@@ -52,3 +52,9 @@ pub struct LoginPluginRequest {
     pub(crate) channel: Identifier,
     pub(crate) data: Vec<u8>,
 }
+
+#[derive(Binary, Packet)]
+#[id = 0x05]
+pub struct CookieRequest {
+    pub(crate) key: Identifier,
+}
@@ -0,0 +1,118 @@
+use uuid::Uuid;
+use zr_binary::varint::VarInt;
+use zr_binary_macros::Binary;
+use zr_data_type::{string::Identifier, vector::Vector3};
+use zr_network_macros::Packet;
+
+use crate::packet::login::client::CookiePayload;
+
+pub const SIGNATURE_SIZE: usize = 256;
+pub type MessageSignature = [u8; SIGNATURE_SIZE];
+/// Fixed bit set of the 20 last seen messages acknowledged by the client
+pub type Acknowledged = [u8; 3];
+
+#[derive(Binary, Packet)]
+#[id = 0x03]
+pub struct AcknowledgeMessage {
+    pub(crate) message_count: VarInt<i32>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x04]
+pub struct ChatCommand {
+    pub(crate) command: String,
+}
+
+#[derive(Debug, Binary, Clone)]
+pub struct ArgumentSignature {
+    pub(crate) name: String,
+    pub(crate) signature: MessageSignature,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x05]
+pub struct SignedChatCommand {
+    pub(crate) command: String,
+    pub(crate) timestamp: i64,
+    pub(crate) salt: i64,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) argument_signatures: Vec<ArgumentSignature>,
+    pub(crate) message_count: VarInt<i32>,
+    pub(crate) acknowledged: Acknowledged,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x06]
+pub struct ChatMessage {
+    pub(crate) message: String,
+    pub(crate) timestamp: i64,
+    pub(crate) salt: i64,
+    pub(crate) has_signature: bool,
+    #[some_if = "self.has_signature"]
+    pub(crate) signature: Option<MessageSignature>,
+    pub(crate) message_count: VarInt<i32>,
+    pub(crate) acknowledged: Acknowledged,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x07]
+pub struct PlayerSession {
+    pub(crate) session_id: Uuid,
+    pub(crate) expires_at: i64,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) public_key: Vec<u8>,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) key_signature: Vec<u8>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x0B]
+pub struct CommandSuggestionsRequest {
+    pub(crate) transaction_id: VarInt<i32>,
+    pub(crate) text: String,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x11]
+pub struct CookieResponse {
+    pub(crate) key: Identifier,
+    pub(crate) has_payload: bool,
+    #[some_if = "self.has_payload"]
+    pub(crate) payload: Option<CookiePayload>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x18]
+pub struct KeepAlive {
+    pub(crate) keep_alive_id: i64,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x1A]
+pub struct SetPlayerPosition {
+    pub(crate) coordinate: Vector3<f64>,
+    pub(crate) on_ground: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x1B]
+pub struct SetPlayerPositionAndRotation {
+    pub(crate) coordinate: Vector3<f64>,
+    pub(crate) yaw: f32,
+    pub(crate) pitch: f32,
+    pub(crate) on_ground: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x1C]
+pub struct SetPlayerRotation {
+    pub(crate) yaw: f32,
+    pub(crate) pitch: f32,
+    pub(crate) on_ground: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x1D]
+pub struct SetPlayerOnGround {
+    pub(crate) on_ground: bool,
+}
@@ -1,3 +1,80 @@
 pub mod client;
-// pub mod server;
+pub mod server;
 
+#[cfg(test)]
+mod tests {
+    use zr_network::packet::PacketData;
+
+    use super::{client, server};
+
+    /// Ids of the protocol 767, the other versions are mapped to them
+    #[test]
+    fn test_ids() {
+        assert_eq!(server::BundleDelimiter::ID, 0x00);
+        assert_eq!(server::SpawnEntity::ID, 0x01);
+        assert_eq!(server::SpawnExperienceOrb::ID, 0x02);
+        assert_eq!(server::EntityAnimation::ID, 0x03);
+        assert_eq!(server::AwardStatistics::ID, 0x04);
+        assert_eq!(server::AcknowledgeBlockChange::ID, 0x05);
+        assert_eq!(server::SetBlockDestroyStage::ID, 0x06);
+        assert_eq!(server::BlockEntityData::ID, 0x07);
+        assert_eq!(server::BlockAction::ID, 0x08);
+        assert_eq!(server::BlockUpdate::ID, 0x09);
+        assert_eq!(server::BossBar::ID, 0x0A);
+        assert_eq!(server::ChangeDifficulty::ID, 0x0B);
+        assert_eq!(server::ChunkBatchFinished::ID, 0x0C);
+        assert_eq!(server::ChunkBatchStart::ID, 0x0D);
+        assert_eq!(server::ChunkBiomes::ID, 0x0E);
+        assert_eq!(server::ClearTitles::ID, 0x0F);
+        assert_eq!(server::CommandSuggestionsResponse::ID, 0x10);
+        assert_eq!(server::Commands::ID, 0x11);
+        assert_eq!(server::CloseContainer::ID, 0x12);
+        assert_eq!(server::ContainerContent::ID, 0x13);
+        assert_eq!(server::SetContainerProperty::ID, 0x14);
+        assert_eq!(server::SetContainerSlot::ID, 0x15);
+        assert_eq!(server::CookieRequest::ID, 0x16);
+        assert_eq!(server::SetCooldown::ID, 0x17);
+        assert_eq!(server::ChatSuggestions::ID, 0x18);
+        assert_eq!(server::ClientboundPluginMessage::ID, 0x19);
+        assert_eq!(server::DamageEvent::ID, 0x1A);
+        assert_eq!(server::DeleteMessage::ID, 0x1C);
+        assert_eq!(server::Disconnect::ID, 0x1D);
+        assert_eq!(server::DisguisedChatMessage::ID, 0x1E);
+        assert_eq!(server::EntityEvent::ID, 0x1F);
+        assert_eq!(server::Explosion::ID, 0x20);
+        assert_eq!(server::UnloadChunk::ID, 0x21);
+        assert_eq!(server::GameEvent::ID, 0x22);
+        assert_eq!(server::OpenHorseScreen::ID, 0x23);
+        assert_eq!(server::KeepAlive::ID, 0x26);
+        assert_eq!(server::Login::ID, 0x2B);
+        assert_eq!(server::UpdateEntityPosition::ID, 0x2E);
+        assert_eq!(server::UpdateEntityPositionAndRotation::ID, 0x2F);
+        assert_eq!(server::UpdateEntityRotation::ID, 0x30);
+        assert_eq!(server::PlayerChatMessage::ID, 0x39);
+        assert_eq!(server::PlayerInfoRemove::ID, 0x3D);
+        assert_eq!(server::PlayerInfoUpdate::ID, 0x3E);
+        assert_eq!(server::SynchronizePlayerPosition::ID, 0x40);
+        assert_eq!(server::RemoveEntities::ID, 0x42);
+        assert_eq!(server::SetHeadRotation::ID, 0x48);
+        assert_eq!(server::SetEntityMetadata::ID, 0x58);
+        assert_eq!(server::UpdateTime::ID, 0x64);
+        assert_eq!(server::StoreCookie::ID, 0x6B);
+        assert_eq!(server::SystemChatMessage::ID, 0x6C);
+        assert_eq!(server::SetTabListHeaderAndFooter::ID, 0x6D);
+        assert_eq!(server::TeleportEntity::ID, 0x70);
+        assert_eq!(server::Transfer::ID, 0x73);
+
+        assert_eq!(client::AcknowledgeMessage::ID, 0x03);
+        assert_eq!(client::ChatCommand::ID, 0x04);
+        assert_eq!(client::SignedChatCommand::ID, 0x05);
+        assert_eq!(client::ChatMessage::ID, 0x06);
+        assert_eq!(client::PlayerSession::ID, 0x07);
+        assert_eq!(client::CommandSuggestionsRequest::ID, 0x0B);
+        assert_eq!(client::CookieResponse::ID, 0x11);
+        assert_eq!(client::KeepAlive::ID, 0x18);
+        assert_eq!(client::SetPlayerPosition::ID, 0x1A);
+        assert_eq!(client::SetPlayerPositionAndRotation::ID, 0x1B);
+        assert_eq!(client::SetPlayerRotation::ID, 0x1C);
+        assert_eq!(client::SetPlayerOnGround::ID, 0x1D);
+    }
+}
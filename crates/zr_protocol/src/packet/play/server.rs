@@ -1,14 +1,22 @@
 use uuid::Uuid;
 use zr_binary::varint::VarInt;
 use zr_binary_macros::Binary;
+use zr_core::{command::node::NodeData, item::ItemStack, metadata::Metadata};
+use zr_data_type::{position::Position, vector::Vector3};
+use zr_nbt::{string::NbtString, Nbt};
 use zr_network_macros::Packet;
+use zr_registry::block::{Block, BlockState};
+
+use super::client::MessageSignature;
+use crate::packet::{
+    config::registry::chat_type::decoration::TextComponent, login::server::Property,
+};
 
 #[derive(Binary, Packet)]
 #[id = 0x00]
 pub struct BundleDelimiter;
 
-pub type Vector3d = f64;
-pub type Vector3<T> = T;
+pub type Vector3d = Vector3<f64>;
 
 #[derive(Binary, Packet)]
 #[id = 0x01]
@@ -71,7 +79,7 @@ pub struct SetBlockDestroyStage {
 pub struct BlockEntityData {
     pub(crate) location: Position,
     pub(crate) entity_block_type: VarInt<i32>,
-    pub(crate) nbt_tag: (),
+    pub(crate) nbt_tag: Vec<u8>, // TODO : network nbt
 }
 
 #[derive(Binary, Packet)]
@@ -80,14 +88,14 @@ pub struct BlockAction {
     pub(crate) location: Position,
     pub(crate) action_id: u8,
     pub(crate) action_parameter: u8,
-    pub(crate) block_type: VarInt<i32>,
+    pub(crate) block_type: Block,
 }
 
 #[derive(Binary, Packet)]
 #[id = 0x09]
 pub struct BlockUpdate {
     pub(crate) location: Position,
-    pub(crate) block_id: VarInt<i32>,
+    pub(crate) block_id: BlockState,
 }
 
 #[derive(Binary, Packet)]
@@ -132,11 +140,12 @@ pub struct ClearTitles {
     pub(crate) reset: bool,
 }
 
-// TODO : rename
 #[derive(Debug, Binary, Clone)]
 pub struct CommandSuggestion {
-    pub(crate) eligible: String,
-    pub(crate) jsp: Option<String>,
+    pub(crate) text: String,
+    pub(crate) has_tooltip: bool,
+    #[some_if = "self.has_tooltip"]
+    pub(crate) tooltip: Option<NbtString>,
 }
 
 #[derive(Binary, Packet)]
@@ -153,8 +162,7 @@ pub struct CommandSuggestionsResponse {
 #[id = 0x11]
 pub struct Commands {
     #[prefixed_length = "VarInt<i32>"]
-    pub(crate) node: Vec<String>, // TODO : Node (commands)
-    // #[prefixed_length = "VarInt<i32>"]
+    pub(crate) nodes: Vec<NodeData>,
     pub(crate) root_index: VarInt<i32>,
 }
 
@@ -164,16 +172,14 @@ pub struct CloseContainer {
     pub(crate) window_id: u8,
 }
 
-pub type Slot = String; // TODO : Slot
-
 #[derive(Binary, Packet)]
 #[id = 0x13]
 pub struct ContainerContent {
     pub(crate) window_id: u8,
     pub(crate) state_id: VarInt<i32>,
     #[prefixed_length = "VarInt<i32>"]
-    pub(crate) slot_data: Vec<Slot>,
-    pub(crate) carried_item: Slot,
+    pub(crate) slot_data: Vec<ItemStack>,
+    pub(crate) carried_item: ItemStack,
 }
 
 #[derive(Binary, Packet)]
@@ -190,18 +196,24 @@ pub struct SetContainerSlot {
     pub(crate) windows_id: u8,
     pub(crate) state_id: VarInt<i32>,
     pub(crate) slot: i16,
-    pub(crate) slot_data: Slot,
+    pub(crate) slot_data: ItemStack,
 }
 
 #[derive(Binary, Packet)]
 #[id = 0x16]
+pub struct CookieRequest {
+    pub(crate) key: Identifier,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x17]
 pub struct SetCooldown {
     pub(crate) item_id: VarInt<i32>,
     pub(crate) cooldown_ticks: VarInt<i32>,
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x17]
+#[id = 0x18]
 pub struct ChatSuggestions {
     pub(crate) action: VarInt<i32>, // TODO : VarInt<enum>
     #[prefixed_length = "VarInt<i32>"]
@@ -211,14 +223,14 @@ pub struct ChatSuggestions {
 pub type Identifier = String; // TODO : Identifier
 
 #[derive(Binary, Packet)]
-#[id = 0x18]
+#[id = 0x19]
 pub struct ClientboundPluginMessage {
     pub(crate) channel: Identifier,
     pub(crate) data: Vec<u8>,
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x19]
+#[id = 0x1A]
 pub struct DamageEvent {
     pub(crate) entity_id: VarInt<i32>,
     pub(crate) source_type_id: VarInt<i32>,
@@ -228,31 +240,34 @@ pub struct DamageEvent {
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x1A]
+#[id = 0x1C]
 pub struct DeleteMessage {
+    /// id of the signature + 1, 0 to give the full signature
     pub(crate) message_id: VarInt<i32>,
-    pub(crate) signature: Option<Vec<u8>>,
+    #[some_if = "self.message_id.0 == 0"]
+    pub(crate) signature: Option<MessageSignature>,
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x1B]
+#[id = 0x1D]
 pub struct Disconnect {
-    pub(crate) reason: String,
+    pub(crate) reason: Nbt<TextComponent>,
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x1C]
+#[id = 0x1E]
 pub struct DisguisedChatMessage {
-    pub(crate) message: String,
+    pub(crate) message: NbtString,
+    /// id of the chat type + 1
     pub(crate) chat_type: VarInt<i32>,
-    pub(crate) sender_name: String,
+    pub(crate) sender_name: NbtString,
     pub(crate) has_target_name: bool,
-    #[some_if = "has_target_name"]
-    pub(crate) target_name: Option<String>,
+    #[some_if = "self.has_target_name"]
+    pub(crate) target_name: Option<NbtString>,
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x1D]
+#[id = 0x1F]
 pub struct EntityEvent {
     pub(crate) entity_id: i32,
     pub(crate) entity_statue: u8, // TODO : enum
@@ -261,13 +276,13 @@ pub struct EntityEvent {
 pub type Particle = String; // TODO : particle
 
 #[derive(Binary, Packet)]
-#[id = 0x1E]
+#[id = 0x20]
 pub struct Explosion {
     pub(crate) coordinate: Vector3d,
     pub(crate) strength: f32,
     #[prefixed_length = "VarInt<i32>"]
     pub(crate) record: Vec<Vector3<i8>>,
-    pub(crate) player_motion: Vector2f,
+    pub(crate) player_motion: Vector3<f32>,
     pub(crate) block_interaction: VarInt<i32>,
     pub(crate) small_explosion_particle_id: VarInt<i32>,
     pub(crate) small_explosion_particle_data: Particle, // TODO : paticles
@@ -279,24 +294,283 @@ pub struct Explosion {
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x1F]
+#[id = 0x21]
 pub struct UnloadChunk {
     pub(crate) chunk_x: i32,
     pub(crate) chunk_z: i32,
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x20]
+#[id = 0x22]
 pub struct GameEvent {
     pub(crate) event: u8,
     pub(crate) value: f32,
 }
 
 #[derive(Binary, Packet)]
-#[id = 0x21]
+#[id = 0x23]
 pub struct OpenHorseScreen {
     pub(crate) window_id: u8,
     pub(crate) slot_count: VarInt<i32>,
     pub(crate) entity_id: i32,
 }
 
+#[derive(Binary, Packet)]
+#[id = 0x26]
+pub struct KeepAlive {
+    pub(crate) keep_alive_id: i64,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x2B]
+pub struct Login {
+    pub(crate) entity_id: i32,
+    pub(crate) is_hardcore: bool,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) dimension_names: Vec<Identifier>,
+    pub(crate) max_players: VarInt<i32>,
+    pub(crate) view_distance: VarInt<i32>,
+    pub(crate) simulation_distance: VarInt<i32>,
+    pub(crate) reduced_debug_info: bool,
+    pub(crate) enable_respawn_screen: bool,
+    pub(crate) do_limited_crafting: bool,
+    pub(crate) dimension_type: VarInt<i32>,
+    pub(crate) dimension_name: Identifier,
+    pub(crate) hashed_seed: i64,
+    pub(crate) game_mode: u8,
+    pub(crate) previous_game_mode: i8,
+    pub(crate) is_debug: bool,
+    pub(crate) is_flat: bool,
+    pub(crate) has_death_location: bool,
+    #[some_if = "self.has_death_location"]
+    pub(crate) death_dimension_name: Option<Identifier>,
+    #[some_if = "self.has_death_location"]
+    pub(crate) death_location: Option<Position>,
+    pub(crate) portal_cooldown: VarInt<i32>,
+    pub(crate) enforces_secure_chat: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x2E]
+pub struct UpdateEntityPosition {
+    pub(crate) entity_id: VarInt<i32>,
+    pub(crate) delta: Vector3<i16>,
+    pub(crate) on_ground: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x2F]
+pub struct UpdateEntityPositionAndRotation {
+    pub(crate) entity_id: VarInt<i32>,
+    pub(crate) delta: Vector3<i16>,
+    pub(crate) yaw: u8,
+    pub(crate) pitch: u8,
+    pub(crate) on_ground: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x30]
+pub struct UpdateEntityRotation {
+    pub(crate) entity_id: VarInt<i32>,
+    pub(crate) yaw: u8,
+    pub(crate) pitch: u8,
+    pub(crate) on_ground: bool,
+}
+
+/// Signature of a message the client has seen, sent in full or by its id in the client cache
+#[derive(Debug, Binary, Clone)]
+pub struct PreviousMessage {
+    /// id of the signature + 1, 0 to give the full signature
+    pub(crate) message_id: VarInt<i32>,
+    #[some_if = "self.message_id.0 == 0"]
+    pub(crate) signature: Option<MessageSignature>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x39]
+pub struct PlayerChatMessage {
+    pub(crate) sender: Uuid,
+    /// index of the message in the chain of the sender
+    pub(crate) index: VarInt<i32>,
+    pub(crate) has_signature: bool,
+    #[some_if = "self.has_signature"]
+    pub(crate) signature: Option<MessageSignature>,
+    pub(crate) message: String,
+    pub(crate) timestamp: i64,
+    pub(crate) salt: i64,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) previous_messages: Vec<PreviousMessage>,
+    pub(crate) has_unsigned_content: bool,
+    #[some_if = "self.has_unsigned_content"]
+    pub(crate) unsigned_content: Option<NbtString>,
+    /// 0 to pass the message through, the partial filter and its bit set are not supported
+    pub(crate) filter_type: VarInt<i32>,
+    /// id of the chat type + 1
+    pub(crate) chat_type: VarInt<i32>,
+    pub(crate) sender_name: NbtString,
+    pub(crate) has_target_name: bool,
+    #[some_if = "self.has_target_name"]
+    pub(crate) target_name: Option<NbtString>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x3D]
+pub struct PlayerInfoRemove {
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) players: Vec<Uuid>,
+}
+
+/// Game profile of a player added to the player list
+#[derive(Debug, Binary, Clone)]
+pub struct AddPlayer {
+    pub(crate) name: String,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) properties: Vec<Property>,
+}
+
+/// Chat session of a player, for the others to verify its signed messages
+#[derive(Debug, Binary, Clone, PartialEq)]
+pub struct ChatSessionData {
+    pub(crate) session_id: Uuid,
+    /// milliseconds since the epoch
+    pub(crate) expires_at: i64,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) public_key: Vec<u8>,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) key_signature: Vec<u8>,
+}
+
+/// Changes of a player in the player list, a field is only set when its action is in the packet
+#[derive(Debug, Binary, Clone)]
+pub struct PlayerActions {
+    pub(crate) uuid: Uuid,
+    pub(crate) add_player: Option<AddPlayer>,
+    pub(crate) has_chat_session: Option<bool>,
+    pub(crate) chat_session: Option<ChatSessionData>,
+    pub(crate) game_mode: Option<VarInt<i32>>,
+    pub(crate) listed: Option<bool>,
+    /// in milliseconds
+    pub(crate) latency: Option<VarInt<i32>>,
+    pub(crate) has_display_name: Option<bool>,
+    pub(crate) display_name: Option<Nbt<TextComponent>>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x3E]
+pub struct PlayerInfoUpdate {
+    /// bit set of the actions, the same for all the players
+    pub(crate) actions: u8,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) players: Vec<PlayerActions>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x40]
+pub struct SynchronizePlayerPosition {
+    pub(crate) coordinate: Vector3d,
+    pub(crate) yaw: f32,
+    pub(crate) pitch: f32,
+    pub(crate) flags: u8,
+    pub(crate) teleport_id: VarInt<i32>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x42]
+pub struct RemoveEntities {
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) entity_ids: Vec<VarInt<i32>>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x48]
+pub struct SetHeadRotation {
+    pub(crate) entity_id: VarInt<i32>,
+    pub(crate) head_yaw: u8,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x58]
+pub struct SetEntityMetadata {
+    pub(crate) entity_id: VarInt<i32>,
+    pub(crate) metadata: Metadata,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x64]
+pub struct UpdateTime {
+    pub(crate) world_age: i64,
+    pub(crate) time_of_day: i64,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x6B]
+pub struct StoreCookie {
+    pub(crate) key: Identifier,
+    #[prefixed_length = "VarInt<i32>"]
+    pub(crate) payload: Vec<u8>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x6C]
+pub struct SystemChatMessage {
+    pub(crate) content: Nbt<TextComponent>,
+    pub(crate) overlay: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x6D]
+pub struct SetTabListHeaderAndFooter {
+    pub(crate) header: Nbt<TextComponent>,
+    pub(crate) footer: Nbt<TextComponent>,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x70]
+pub struct TeleportEntity {
+    pub(crate) entity_id: VarInt<i32>,
+    pub(crate) coordinate: Vector3d,
+    pub(crate) yaw: u8,
+    pub(crate) pitch: u8,
+    pub(crate) on_ground: bool,
+}
+
+#[derive(Binary, Packet)]
+#[id = 0x73]
+pub struct Transfer {
+    pub(crate) host: String,
+    pub(crate) port: VarInt<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_binary::{binary::Binary, varint::VarInt};
+    use zr_nbt::Nbt;
+
+    use super::{Disconnect, RemoveEntities};
+    use crate::packet::config::registry::chat_type::decoration::TextComponent;
+
+    #[test]
+    fn test_prefixed_length() {
+        // 300 takes two bytes, the prefix still counts two entities
+        let packet = RemoveEntities {
+            entity_ids: vec![VarInt::new(1), VarInt::new(300)],
+        };
+        assert_eq!(packet.binary_len(), 4);
+        let bin = packet.to_binary();
+        assert_eq!(bin, [2, 1, 0xAC, 0x02]);
+        let entity_ids = RemoveEntities::from_binary(bin).unwrap().entity_ids;
+        assert_eq!(entity_ids, [VarInt::new(1), VarInt::new(300)]);
+    }
+
+    #[test]
+    fn test_disconnect_reason() {
+        // a nameless compound with the string `text`, not a prefixed string
+        let packet = Disconnect {
+            reason: Nbt::new(TextComponent::text("Bye")),
+        };
+        let nbt = [
+            0x0A, 0x08, 0, 4, b't', b'e', b'x', b't', 0, 3, b'B', b'y', b'e', 0x00,
+        ];
+        assert_eq!(packet.to_binary(), nbt);
+    }
+}
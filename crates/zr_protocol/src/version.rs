@@ -0,0 +1,165 @@
+use zr_network::packet::packet::Packet;
+
+use crate::handler::protocol_handler::State;
+
+/// Minecraft version the server runs
+pub const VERSION_NAME: &str = "1.21";
+/// Protocol version of `VERSION_NAME`
+pub const PROTOCOL_VERSION: i32 = 767;
+/// Versions of the clients that can join, told to the others
+pub const SUPPORTED_RANGE: &str = "1.20.5-1.21.1";
+
+/// Packet ids of a protocol version that differ from the ids of `PROTOCOL_VERSION`,
+/// by state: `(state, id of the server, id of the version)`
+pub type IdMapping = &'static [(State, i32, i32)];
+
+/// A protocol version the clients can join with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProtocolVersion {
+    pub protocol: i32,
+    /// latest release using this protocol
+    pub name: &'static str,
+    clientbound: IdMapping,
+    serverbound: IdMapping,
+}
+
+/// Clientbound ids of 1.20.5 and 1.20.6 that differ from 1.21. None: 1.21 only appended
+/// Custom Report Details and Server Links after the last play and configuration packets
+const CLIENTBOUND_766: IdMapping = &[];
+/// Serverbound ids of 1.20.5 and 1.20.6 that differ from 1.21, 1.21 added none
+const SERVERBOUND_766: IdMapping = &[];
+
+/// The protocol versions the server speaks, oldest first.
+/// An older version is only added with the mapping of the ids it does not share
+pub const SUPPORTED_VERSIONS: &[ProtocolVersion] = &[
+    ProtocolVersion::new(766, "1.20.6", CLIENTBOUND_766, SERVERBOUND_766),
+    ProtocolVersion::new(PROTOCOL_VERSION, "1.21.1", &[], &[]),
+];
+
+impl ProtocolVersion {
+    pub const fn new(
+        protocol: i32,
+        name: &'static str,
+        clientbound: IdMapping,
+        serverbound: IdMapping,
+    ) -> Self {
+        Self {
+            protocol,
+            name,
+            clientbound,
+            serverbound,
+        }
+    }
+
+    /// `None` if the server cannot speak `protocol`
+    pub fn get(protocol: i32) -> Option<&'static Self> {
+        SUPPORTED_VERSIONS
+            .iter()
+            .find(|version| version.protocol == protocol)
+    }
+
+    /// The packet written by the server with the id of this version
+    pub fn clientbound(&self, state: State, packet: Packet) -> Packet {
+        match self
+            .clientbound
+            .iter()
+            .find(|(known, id, _)| *known == state && *id == packet.id())
+        {
+            Some((_, _, mapped)) => packet.with_id(*mapped),
+            None => packet,
+        }
+    }
+
+    /// The packet sent by a client of this version with the id known by the server
+    pub fn serverbound(&self, state: State, packet: Packet) -> Packet {
+        match self
+            .serverbound
+            .iter()
+            .find(|(known, _, mapped)| *known == state && *mapped == packet.id())
+        {
+            Some((_, id, _)) => packet.with_id(*id),
+            None => packet,
+        }
+    }
+}
+
+/// Translation key of the message refusing a client speaking `protocol`
+pub fn outdated_key(protocol: i32) -> &'static str {
+    match SUPPORTED_VERSIONS
+        .first()
+        .is_some_and(|oldest| protocol < oldest.protocol)
+    {
+        true => "multiplayer.disconnect.outdated_client",
+        false => "multiplayer.disconnect.outdated_server",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_network::packet::{packet::Packet, PacketData};
+
+    use super::{outdated_key, ProtocolVersion, PROTOCOL_VERSION};
+    use crate::{
+        handler::protocol_handler::State,
+        packet::{config, play},
+    };
+
+    #[test]
+    fn test_supported() {
+        assert!(ProtocolVersion::get(PROTOCOL_VERSION).is_some());
+        assert!(ProtocolVersion::get(766).is_some());
+        assert!(ProtocolVersion::get(765).is_none());
+        assert_eq!(outdated_key(765), "multiplayer.disconnect.outdated_client");
+        assert_eq!(outdated_key(768), "multiplayer.disconnect.outdated_server");
+    }
+
+    #[test]
+    fn test_mapping() {
+        let version = ProtocolVersion::new(
+            0,
+            "test",
+            &[(State::Play, 0x26, 0x27)],
+            &[(State::Play, 0x18, 0x1A)],
+        );
+        let packet = |id| Packet::new(id, vec![1, 2]);
+        assert_eq!(version.clientbound(State::Play, packet(0x26)).id(), 0x27);
+        assert_eq!(version.clientbound(State::Config, packet(0x26)).id(), 0x26);
+        let mapped = version.serverbound(State::Play, packet(0x1A));
+        assert_eq!(mapped.id(), 0x18);
+        assert_eq!(mapped.raw_data(), &vec![1, 2]);
+        assert_eq!(version.serverbound(State::Play, packet(0x18)).id(), 0x18);
+    }
+    #[test]
+    fn test_766() {
+        // the packets of the server keep their 1.21 ids in 1.20.6
+        let version = ProtocolVersion::get(766).unwrap();
+        let ids = [
+            (State::Config, config::server::FinishConfiguration::ID),
+            (State::Config, config::server::RegistryData::ID),
+            (State::Config, config::server::KnownPacks::ID),
+            (State::Play, play::server::Login::ID),
+            (State::Play, play::server::SetCooldown::ID),
+            (State::Play, play::server::PlayerInfoUpdate::ID),
+            (State::Play, play::server::SetTabListHeaderAndFooter::ID),
+            (State::Play, play::server::Transfer::ID),
+        ];
+        for (state, id) in ids {
+            let packet = Packet::new(id, vec![]);
+            assert_eq!(version.clientbound(state, packet).id(), id);
+        }
+        let ids = [
+            (
+                State::Config,
+                config::client::AcknowledgeFinishConfiguration::ID,
+            ),
+            (State::Config, config::client::KnownPacks::ID),
+            (State::Play, play::client::ChatMessage::ID),
+            (State::Play, play::client::KeepAlive::ID),
+            (State::Play, play::client::SetPlayerPosition::ID),
+        ];
+        for (state, id) in ids {
+            let packet = Packet::new(id, vec![]);
+            assert_eq!(version.serverbound(state, packet).id(), id);
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::{collections::BTreeMap, env, fs, path::Path};
+
+use serde::Deserialize;
+
+// Vanilla reports, produced by `generate_reports.sh` with
+// `java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports`
+const BLOCKS_REPORT: &str = "data/blocks.json";
+const REGISTRIES_REPORT: &str = "data/registries.json";
+const ITEMS_REPORT: &str = "data/items.json";
+/// Stack size of the items without `minecraft:max_stack_size` in the report
+const DEFAULT_MAX_STACK_SIZE: u8 = 64;
+
+#[derive(Deserialize)]
+struct BlockReport {
+    #[serde(default)]
+    properties: BTreeMap<String, Vec<String>>,
+    states: Vec<StateReport>,
+}
+
+#[derive(Deserialize)]
+struct RegistryReport {
+    default: Option<String>,
+    entries: BTreeMap<String, EntryReport>,
+}
+
+#[derive(Deserialize)]
+struct EntryReport {
+    protocol_id: u16,
+}
+
+/// Default components of an item prototype
+#[derive(Deserialize)]
+struct ItemReport {
+    #[serde(default)]
+    components: BTreeMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct StateReport {
+    id: u16,
+    #[serde(default)]
+    default: bool,
+}
+
+fn variant_name(identifier: &str) -> String {
+    let name = identifier.split_once(':').map_or(identifier, |(_, value)| value);
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn generate_blocks(out_dir: &Path) {
+    let report = fs::read_to_string(BLOCKS_REPORT).expect("cannot read blocks report");
+    let report: BTreeMap<String, BlockReport> =
+        serde_json::from_str(&report).expect("malformed blocks report");
+    // the json map is sorted by name, registry order is given by state ids
+    let mut blocks: Vec<(String, BlockReport)> = report.into_iter().collect();
+    blocks.sort_by_key(|(_, block)| block.states.iter().map(|state| state.id).min());
+
+    let mut variants = String::new();
+    let mut infos = String::new();
+    let mut state_count = 0;
+    for (name, block) in &blocks {
+        let first_state = block.states.iter().map(|state| state.id).min().expect("block without state");
+        let default_state = block
+            .states
+            .iter()
+            .find(|state| state.default)
+            .map_or(first_state, |state| state.id);
+        let properties: Vec<String> = block
+            .properties
+            .iter()
+            .map(|(property, values)| {
+                format!("PropertyInfo {{ name: {property:?}, values: &{values:?} }}")
+            })
+            .collect();
+        variants.push_str(&format!("    {},\n", variant_name(name)));
+        infos.push_str(&format!(
+            "    BlockInfo {{ name: {name:?}, first_state: {first_state}, default_state: {default_state}, state_count: {}, properties: &[{}] }},\n",
+            block.states.len(),
+            properties.join(", ")
+        ));
+        state_count += block.states.len();
+    }
+    let all: Vec<String> = blocks
+        .iter()
+        .map(|(name, _)| format!("Block::{}", variant_name(name)))
+        .collect();
+
+    let code = format!(
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]\n\
+         #[repr(u16)]\n\
+         pub enum Block {{\n{variants}}}\n\n\
+         impl Block {{\n    pub const ALL: &'static [Block] = &[{}];\n}}\n\n\
+         pub(crate) const STATE_COUNT: u16 = {state_count};\n\n\
+         pub(crate) static BLOCKS: &[BlockInfo] = &[\n{infos}];\n",
+        all.join(", ")
+    );
+    fs::write(out_dir.join("blocks.rs"), code).expect("cannot write generated blocks");
+}
+
+/// Generate `enum_name` with one variant per entry of the `registry` registry,
+/// and the matching `names` table indexed by protocol id.
+fn generate_registry(
+    out_dir: &Path,
+    registries: &BTreeMap<String, RegistryReport>,
+    registry: &str,
+    enum_name: &str,
+    names: &str,
+) {
+    let report = registries
+        .get(registry)
+        .unwrap_or_else(|| panic!("missing registry {registry}"));
+    let mut entries: Vec<(&String, u16)> = report
+        .entries
+        .iter()
+        .map(|(name, entry)| (name, entry.protocol_id))
+        .collect();
+    entries.sort_by_key(|(_, id)| *id);
+    for (i, (name, id)) in entries.iter().enumerate() {
+        assert_eq!(i, *id as usize, "{name} protocol id is not contiguous");
+    }
+
+    let all: Vec<String> = entries
+        .iter()
+        .map(|(name, _)| format!("{enum_name}::{}", variant_name(name)))
+        .collect();
+    let variants: Vec<String> = entries
+        .iter()
+        .map(|(name, _)| match &report.default {
+            Some(default) if default == *name => format!("#[default]\n    {}", variant_name(name)),
+            _ => variant_name(name),
+        })
+        .collect();
+    let derive_default = if report.default.is_some() { ", Default" } else { "" };
+    let table: Vec<String> = entries.iter().map(|(name, _)| format!("{name:?}")).collect();
+
+    let code = format!(
+        "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord{derive_default})]\n\
+         #[repr(u16)]\n\
+         pub enum {enum_name} {{\n    {},\n}}\n\n\
+         impl {enum_name} {{\n    pub const ALL: &'static [{enum_name}] = &[{}];\n}}\n\n\
+         pub(crate) static {names}: &[&str] = &[{}];\n",
+        variants.join(",\n    "),
+        all.join(", "),
+        table.join(", ")
+    );
+    let file = format!("{}.rs", registry.split_once(':').map_or(registry, |(_, value)| value));
+    fs::write(out_dir.join(file), code).expect("cannot write generated registry");
+}
+
+/// Generate the stack size of every item, indexed by protocol id
+fn generate_max_stack_sizes(out_dir: &Path, registries: &BTreeMap<String, RegistryReport>) {
+    let report = fs::read_to_string(ITEMS_REPORT).expect("cannot read items report");
+    let report: BTreeMap<String, ItemReport> =
+        serde_json::from_str(&report).expect("malformed items report");
+    let mut items: Vec<(&String, u16)> = registries["minecraft:item"]
+        .entries
+        .iter()
+        .map(|(name, entry)| (name, entry.protocol_id))
+        .collect();
+    items.sort_by_key(|(_, id)| *id);
+    let sizes: Vec<String> = items
+        .iter()
+        .map(|(name, _)| {
+            report
+                .get(*name)
+                .and_then(|item| item.components.get("minecraft:max_stack_size"))
+                .and_then(|size| size.as_u64())
+                .map_or(DEFAULT_MAX_STACK_SIZE, |size| size as u8)
+                .to_string()
+        })
+        .collect();
+    let code = format!("pub(crate) static MAX_STACK_SIZES: &[u8] = &[{}];\n", sizes.join(", "));
+    fs::write(out_dir.join("max_stack_size.rs"), code).expect("cannot write generated stack sizes");
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={BLOCKS_REPORT}");
+    println!("cargo:rerun-if-changed={REGISTRIES_REPORT}");
+    println!("cargo:rerun-if-changed={ITEMS_REPORT}");
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    let out_dir = Path::new(&out_dir);
+    generate_blocks(out_dir);
+
+    let registries = fs::read_to_string(REGISTRIES_REPORT).expect("cannot read registries report");
+    let registries: BTreeMap<String, RegistryReport> =
+        serde_json::from_str(&registries).expect("malformed registries report");
+    generate_registry(out_dir, &registries, "minecraft:item", "Item", "ITEMS");
+    generate_max_stack_sizes(out_dir, &registries);
+    generate_registry(out_dir, &registries, "minecraft:entity_type", "EntityType", "ENTITY_TYPES");
+}
@@ -0,0 +1,36 @@
+#!/bin/sh
+# Regenerate the vanilla reports of `data/` from the server of the supported version.
+# Needs curl, jq and java 21: `./generate_reports.sh [version]`
+set -eu
+
+for tool in curl jq java; do
+    if ! command -v "$tool" >/dev/null; then
+        echo "missing $tool" >&2
+        exit 1
+    fi
+done
+# the servers since 1.20.5 need java 21
+java_version=$(java -version 2>&1 | sed -n 's/.*version "\([0-9]*\).*/\1/p' | head -n 1)
+if [ "${java_version:-0}" -lt 21 ]; then
+    echo "java 21 or newer is needed, found ${java_version:-none}" >&2
+    exit 1
+fi
+
+VERSION="${1:-1.21}"
+MANIFEST="https://piston-meta.mojang.com/mc/game/version_manifest_v2.json"
+DATA="$(cd "$(dirname "$0")" && pwd)/data"
+WORK="$(mktemp -d)"
+trap 'rm -rf "$WORK"' EXIT
+
+version_url=$(curl -fsSL "$MANIFEST" | jq -r --arg version "$VERSION" '.versions[] | select(.id == $version) | .url')
+if [ -z "$version_url" ]; then
+    echo "unknown version $VERSION" >&2
+    exit 1
+fi
+server_url=$(curl -fsSL "$version_url" | jq -r '.downloads.server.url')
+curl -fsSL -o "$WORK/server.jar" "$server_url"
+
+(cd "$WORK" && java -DbundlerMainClass=net.minecraft.data.Main -jar server.jar --reports --output generated)
+for report in blocks registries items; do
+    cp "$WORK/generated/reports/$report.json" "$DATA/$report.json"
+done
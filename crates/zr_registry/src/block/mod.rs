@@ -0,0 +1,294 @@
+//! Block registry generated at build time from the vanilla `blocks.json` report
+//! (see `data/blocks.json`, regenerated from the vanilla server by `generate_reports.sh`).
+
+pub mod property;
+
+use std::collections::BTreeMap;
+
+use property::Property;
+use serde::{de, Deserialize, Serialize};
+use zr_binary::{binary::Binary, varint::VarInt};
+
+use crate::error::RegistryError;
+
+pub(crate) struct PropertyInfo {
+    name: &'static str,
+    values: &'static [&'static str],
+}
+
+pub(crate) struct BlockInfo {
+    name: &'static str,
+    first_state: u16,
+    default_state: u16,
+    state_count: u16,
+    properties: &'static [PropertyInfo],
+}
+
+include!(concat!(env!("OUT_DIR"), "/blocks.rs"));
+
+registry!(Block, |block| block.info().name);
+
+impl Block {
+    fn info(&self) -> &'static BlockInfo {
+        &BLOCKS[*self as usize]
+    }
+
+    pub fn default_state(&self) -> BlockState {
+        BlockState(self.info().default_state)
+    }
+
+    pub fn states(&self) -> impl Iterator<Item = BlockState> {
+        let info = self.info();
+        (info.first_state..info.first_state + info.state_count).map(BlockState)
+    }
+
+    /// Property names with their possible values, in state id order
+    pub fn properties(&self) -> impl Iterator<Item = (&'static str, &'static [&'static str])> {
+        self.info()
+            .properties
+            .iter()
+            .map(|property| (property.name, property.values))
+    }
+}
+
+/// A block with a value for each of its properties, identified by its global state id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BlockState(u16);
+
+impl Default for BlockState {
+    fn default() -> Self {
+        Self::AIR
+    }
+}
+
+impl BlockState {
+    pub const AIR: BlockState = BlockState(0);
+
+    pub fn from_id(id: u16) -> Option<Self> {
+        if id < STATE_COUNT {
+            Some(Self(id))
+        } else {
+            None
+        }
+    }
+
+    pub fn id(&self) -> u16 {
+        self.0
+    }
+
+    pub fn block(&self) -> Block {
+        let index = BLOCKS.partition_point(|info| info.first_state <= self.0) - 1;
+        Block::ALL[index]
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.block().default_state() == *self
+    }
+
+    pub fn is_air(&self) -> bool {
+        self.block() == Block::Air
+    }
+
+    /// Index of the value taken by each property, in the block property order
+    fn value_indexes(&self) -> Vec<usize> {
+        let info = self.block().info();
+        let mut offset = (self.0 - info.first_state) as usize;
+        let mut indexes = vec![0; info.properties.len()];
+        for (i, property) in info.properties.iter().enumerate().rev() {
+            indexes[i] = offset % property.values.len();
+            offset /= property.values.len();
+        }
+        indexes
+    }
+
+    fn from_value_indexes(block: Block, indexes: &[usize]) -> Self {
+        let info = block.info();
+        let offset = info
+            .properties
+            .iter()
+            .zip(indexes)
+            .fold(0, |offset, (property, index)| offset * property.values.len() + index);
+        Self(info.first_state + offset as u16)
+    }
+
+    pub fn properties(&self) -> Vec<(&'static str, &'static str)> {
+        self.block()
+            .info()
+            .properties
+            .iter()
+            .zip(self.value_indexes())
+            .map(|(property, index)| (property.name, property.values[index]))
+            .collect()
+    }
+
+    pub fn property(&self, name: &str) -> Option<&'static str> {
+        self.properties()
+            .into_iter()
+            .find(|(property, _)| *property == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Same block with `name` set to `value`, `None` if the block has no such property or value
+    pub fn with_property(&self, name: &str, value: &str) -> Option<Self> {
+        let block = self.block();
+        let (i, property) = block
+            .info()
+            .properties
+            .iter()
+            .enumerate()
+            .find(|(_, property)| property.name == name)?;
+        let value = property.values.iter().position(|v| *v == value)?;
+        let mut indexes = self.value_indexes();
+        indexes[i] = value;
+        Some(Self::from_value_indexes(block, &indexes))
+    }
+
+    pub fn get<P: Property>(&self) -> Option<P> {
+        self.property(P::NAME).and_then(P::from_value)
+    }
+
+    pub fn set<P: Property>(&self, value: P) -> Option<Self> {
+        self.with_property(P::NAME, value.value())
+    }
+
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        self.property(name).and_then(|value| value.parse().ok())
+    }
+
+    pub fn get_int(&self, name: &str) -> Option<u8> {
+        self.property(name).and_then(|value| value.parse().ok())
+    }
+
+    pub fn waterlogged(&self) -> Option<bool> {
+        self.get_bool("waterlogged")
+    }
+}
+
+impl From<Block> for BlockState {
+    fn from(block: Block) -> Self {
+        block.default_state()
+    }
+}
+
+impl Binary for BlockState {
+    fn binary_len(&self) -> usize {
+        VarInt::<i32>::from(self.0).binary_len()
+    }
+
+    fn to_binary(self) -> Vec<u8> {
+        VarInt::<i32>::from(self.0).to_binary()
+    }
+
+    fn from_binary(bin: Vec<u8>) -> zr_binary::error::Result<Self>
+    where
+        Self: Sized,
+    {
+        let id = VarInt::<i32>::from_binary(bin)?;
+        u16::try_from(id.0)
+            .ok()
+            .and_then(Self::from_id)
+            .ok_or(zr_binary::error::BinaryError::FormatError)
+    }
+}
+
+/// Vanilla representation of a block state (`{Name: "...", Properties: {...}}`)
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct BlockStateCodec {
+    name: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    properties: BTreeMap<String, String>,
+}
+
+impl TryFrom<BlockStateCodec> for BlockState {
+    type Error = RegistryError;
+
+    fn try_from(codec: BlockStateCodec) -> Result<Self, Self::Error> {
+        let block: Block = codec.name.parse()?;
+        codec
+            .properties
+            .into_iter()
+            .try_fold(block.default_state(), |state, (name, value)| {
+                state
+                    .with_property(&name, &value)
+                    .ok_or(RegistryError::UnknownProperty { name, value })
+            })
+    }
+}
+
+impl Serialize for BlockState {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        BlockStateCodec {
+            name: self.block().name().to_string(),
+            properties: self
+                .properties()
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        BlockStateCodec::deserialize(deserializer)
+            .and_then(|codec| BlockState::try_from(codec).map_err(de::Error::custom))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{property::Axis, Block, BlockState};
+
+    #[test]
+    fn test_state_ids() {
+        assert_eq!(Block::Air.default_state(), BlockState::AIR);
+        assert_eq!(Block::Stone.default_state().id(), 1);
+        assert_eq!(Block::GrassBlock.default_state().id(), 9);
+        assert_eq!(Block::Water.default_state().id(), 80);
+        assert_eq!(Block::OakLog.default_state().id(), 131);
+        for block in Block::ALL {
+            for state in block.states() {
+                assert_eq!(state.block(), *block);
+                assert_eq!(BlockState::from_id(state.id()), Some(state));
+            }
+        }
+    }
+
+    #[test]
+    fn test_properties() {
+        let propagule = Block::MangrovePropagule.default_state();
+        assert_eq!(propagule.id(), 44);
+        assert_eq!(propagule.waterlogged(), Some(false));
+        let waterlogged = propagule.with_property("waterlogged", "true").unwrap();
+        assert_eq!(waterlogged.waterlogged(), Some(true));
+        assert_eq!(waterlogged.get_int("age"), Some(0));
+        assert_eq!(waterlogged.with_property("waterlogged", "false"), Some(propagule));
+        assert_eq!(propagule.with_property("facing", "north"), None);
+        assert_eq!(Block::OakLog.default_state().set(Axis::Z).unwrap().id(), 132);
+    }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!("minecraft:oak_log".parse::<Block>().ok(), Some(Block::OakLog));
+        assert_eq!("bedrock".parse::<Block>().ok(), Some(Block::Bedrock));
+        assert!("minecraft:unknown".parse::<Block>().is_err());
+        assert_eq!(Block::Lava.identifier().to_string(), "minecraft:lava");
+    }
+
+    #[test]
+    fn test_serde() {
+        let state = Block::GrassBlock.default_state().with_property("snowy", "true").unwrap();
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, r#"{"Name":"minecraft:grass_block","Properties":{"snowy":"true"}}"#);
+        assert_eq!(serde_json::from_str::<BlockState>(&json).unwrap(), state);
+        assert!(serde_json::from_str::<BlockState>(r#"{"Name":"minecraft:stone","Properties":{"snowy":"true"}}"#).is_err());
+    }
+}
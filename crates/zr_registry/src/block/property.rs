@@ -0,0 +1,79 @@
+/// Typed view over a block state property.
+/// ```
+/// use zr_registry::block::{property::Axis, Block};
+///
+/// let log = Block::OakLog.default_state();
+/// assert_eq!(log.get::<Axis>(), Some(Axis::Y));
+/// assert_eq!(log.set(Axis::X).and_then(|state| state.get::<Axis>()), Some(Axis::X));
+/// ```
+pub trait Property: Sized + Copy {
+    const NAME: &'static str;
+
+    fn from_value(value: &str) -> Option<Self>;
+    fn value(&self) -> &'static str;
+}
+
+macro_rules! property {
+    ($ty: ident $name: literal { $($variant: ident => $value: literal),* $(,)? }) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $ty {
+            $($variant),*
+        }
+
+        impl Property for $ty {
+            const NAME: &'static str = $name;
+
+            fn from_value(value: &str) -> Option<Self> {
+                match value {
+                    $($value => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            fn value(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $value),*
+                }
+            }
+        }
+    };
+}
+
+property!(Axis "axis" {
+    X => "x",
+    Y => "y",
+    Z => "z",
+});
+
+property!(Facing "facing" {
+    Down => "down",
+    Up => "up",
+    North => "north",
+    South => "south",
+    West => "west",
+    East => "east",
+});
+
+property!(Half "half" {
+    Top => "top",
+    Bottom => "bottom",
+});
+
+property!(DoubleBlockHalf "half" {
+    Upper => "upper",
+    Lower => "lower",
+});
+
+property!(StairsShape "shape" {
+    Straight => "straight",
+    InnerLeft => "inner_left",
+    InnerRight => "inner_right",
+    OuterLeft => "outer_left",
+    OuterRight => "outer_right",
+});
+
+property!(SlabType "type" {
+    Top => "top",
+    Bottom => "bottom",
+    Double => "double",
+});
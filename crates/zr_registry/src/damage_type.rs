@@ -0,0 +1,254 @@
+//! Damage types of the `minecraft:damage_type` registry. It is a data driven registry,
+//! the ids are the order in which its entries are sent during configuration.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u16)]
+pub enum DamageType {
+    Arrow,
+    BadRespawnPoint,
+    Cactus,
+    Cramming,
+    DragonBreath,
+    Drown,
+    DryOut,
+    Explosion,
+    Fall,
+    FallingAnvil,
+    FallingBlock,
+    FallingStalactite,
+    Fireball,
+    Fireworks,
+    FlyIntoWall,
+    Freeze,
+    Generic,
+    GenericKill,
+    HotFloor,
+    InFire,
+    InWall,
+    IndirectMagic,
+    Lava,
+    LightningBolt,
+    Magic,
+    MobAttack,
+    MobAttackNoAggro,
+    MobProjectile,
+    OnFire,
+    OutOfWorld,
+    OutsideBorder,
+    PlayerAttack,
+    PlayerExplosion,
+    SonicBoom,
+    Spit,
+    Stalagmite,
+    Starve,
+    Sting,
+    SweetBerryBush,
+    Thorns,
+    Thrown,
+    Trident,
+    UnattributedFireball,
+    WindCharge,
+    Wither,
+    WitherSkull,
+}
+
+impl DamageType {
+    pub const ALL: &'static [DamageType] = &[
+        Self::Arrow,
+        Self::BadRespawnPoint,
+        Self::Cactus,
+        Self::Cramming,
+        Self::DragonBreath,
+        Self::Drown,
+        Self::DryOut,
+        Self::Explosion,
+        Self::Fall,
+        Self::FallingAnvil,
+        Self::FallingBlock,
+        Self::FallingStalactite,
+        Self::Fireball,
+        Self::Fireworks,
+        Self::FlyIntoWall,
+        Self::Freeze,
+        Self::Generic,
+        Self::GenericKill,
+        Self::HotFloor,
+        Self::InFire,
+        Self::InWall,
+        Self::IndirectMagic,
+        Self::Lava,
+        Self::LightningBolt,
+        Self::Magic,
+        Self::MobAttack,
+        Self::MobAttackNoAggro,
+        Self::MobProjectile,
+        Self::OnFire,
+        Self::OutOfWorld,
+        Self::OutsideBorder,
+        Self::PlayerAttack,
+        Self::PlayerExplosion,
+        Self::SonicBoom,
+        Self::Spit,
+        Self::Stalagmite,
+        Self::Starve,
+        Self::Sting,
+        Self::SweetBerryBush,
+        Self::Thorns,
+        Self::Thrown,
+        Self::Trident,
+        Self::UnattributedFireball,
+        Self::WindCharge,
+        Self::Wither,
+        Self::WitherSkull,
+    ];
+}
+
+static DAMAGE_TYPES: &[&str] = &[
+    "minecraft:arrow",
+    "minecraft:bad_respawn_point",
+    "minecraft:cactus",
+    "minecraft:cramming",
+    "minecraft:dragon_breath",
+    "minecraft:drown",
+    "minecraft:dry_out",
+    "minecraft:explosion",
+    "minecraft:fall",
+    "minecraft:falling_anvil",
+    "minecraft:falling_block",
+    "minecraft:falling_stalactite",
+    "minecraft:fireball",
+    "minecraft:fireworks",
+    "minecraft:fly_into_wall",
+    "minecraft:freeze",
+    "minecraft:generic",
+    "minecraft:generic_kill",
+    "minecraft:hot_floor",
+    "minecraft:in_fire",
+    "minecraft:in_wall",
+    "minecraft:indirect_magic",
+    "minecraft:lava",
+    "minecraft:lightning_bolt",
+    "minecraft:magic",
+    "minecraft:mob_attack",
+    "minecraft:mob_attack_no_aggro",
+    "minecraft:mob_projectile",
+    "minecraft:on_fire",
+    "minecraft:out_of_world",
+    "minecraft:outside_border",
+    "minecraft:player_attack",
+    "minecraft:player_explosion",
+    "minecraft:sonic_boom",
+    "minecraft:spit",
+    "minecraft:stalagmite",
+    "minecraft:starve",
+    "minecraft:sting",
+    "minecraft:sweet_berry_bush",
+    "minecraft:thorns",
+    "minecraft:thrown",
+    "minecraft:trident",
+    "minecraft:unattributed_fireball",
+    "minecraft:wind_charge",
+    "minecraft:wither",
+    "minecraft:wither_skull",
+];
+
+registry!(DamageType, |damage_type| DAMAGE_TYPES
+    [*damage_type as usize]);
+
+impl DamageType {
+    /// Food exhaustion caused to a player
+    pub fn exhaustion(&self) -> f32 {
+        match self {
+            Self::Arrow
+            | Self::Fireball
+            | Self::Fireworks
+            | Self::MobAttack
+            | Self::MobAttackNoAggro
+            | Self::MobProjectile
+            | Self::PlayerAttack
+            | Self::Sting
+            | Self::Thrown
+            | Self::Trident
+            | Self::UnattributedFireball
+            | Self::WitherSkull
+            | Self::Spit
+            | Self::WindCharge
+            | Self::Thorns
+            | Self::Explosion
+            | Self::PlayerExplosion
+            | Self::BadRespawnPoint
+            | Self::Cactus
+            | Self::SweetBerryBush
+            | Self::FallingAnvil
+            | Self::FallingBlock
+            | Self::FallingStalactite
+            | Self::Stalagmite
+            | Self::InFire
+            | Self::HotFloor
+            | Self::Lava
+            | Self::OnFire
+            | Self::LightningBolt
+            | Self::Generic => 0.1,
+            _ => 0.0,
+        }
+    }
+
+    /// `minecraft:bypasses_invulnerability`, also hurts creative players
+    pub fn bypasses_invulnerability(&self) -> bool {
+        matches!(self, Self::OutOfWorld | Self::GenericKill)
+    }
+
+    /// `minecraft:is_fire`
+    pub fn is_fire(&self) -> bool {
+        matches!(
+            self,
+            Self::InFire
+                | Self::OnFire
+                | Self::Lava
+                | Self::HotFloor
+                | Self::Fireball
+                | Self::UnattributedFireball
+        )
+    }
+
+    /// `minecraft:is_fall`
+    pub fn is_fall(&self) -> bool {
+        matches!(self, Self::Fall | Self::Stalagmite)
+    }
+
+    /// `minecraft:is_projectile`
+    pub fn is_projectile(&self) -> bool {
+        matches!(
+            self,
+            Self::Arrow
+                | Self::Trident
+                | Self::MobProjectile
+                | Self::UnattributedFireball
+                | Self::Fireball
+                | Self::WitherSkull
+                | Self::Thrown
+                | Self::WindCharge
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DamageType;
+
+    #[test]
+    fn test_damage_types() {
+        assert_eq!(DamageType::Arrow.id(), 0);
+        assert_eq!(DamageType::ALL.len(), 46);
+        assert_eq!(
+            DamageType::from_id(DamageType::Lava.id()),
+            Some(DamageType::Lava)
+        );
+        assert_eq!(
+            "minecraft:out_of_world".parse::<DamageType>().ok(),
+            Some(DamageType::OutOfWorld)
+        );
+        assert!(DamageType::OutOfWorld.bypasses_invulnerability());
+        assert!(DamageType::Lava.is_fire());
+    }
+}
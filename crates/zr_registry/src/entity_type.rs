@@ -0,0 +1,64 @@
+//! Entity type registry generated at build time from the `minecraft:entity_type` entry
+//! of the vanilla `registries.json` report (see `data/registries.json`).
+
+include!(concat!(env!("OUT_DIR"), "/entity_type.rs"));
+
+registry!(EntityType, |entity_type| ENTITY_TYPES
+    [*entity_type as usize]);
+
+impl EntityType {
+    /// Distance in chunks under which the entity is sent to players,
+    /// still capped by the view distance
+    pub fn tracking_range(&self) -> u8 {
+        match self {
+            Self::Player => 32,
+            Self::EnderDragon | Self::Wither | Self::Ghast => 10,
+            Self::Item
+            | Self::ExperienceOrb
+            | Self::ItemFrame
+            | Self::GlowItemFrame
+            | Self::Painting
+            | Self::LeashKnot => 6,
+            Self::Arrow
+            | Self::SpectralArrow
+            | Self::Trident
+            | Self::Snowball
+            | Self::Egg
+            | Self::EnderPearl
+            | Self::Potion
+            | Self::ExperienceBottle
+            | Self::FishingBobber
+            | Self::SmallFireball
+            | Self::Fireball
+            | Self::WindCharge
+            | Self::BreezeWindCharge
+            | Self::LlamaSpit
+            | Self::ShulkerBullet => 4,
+            _ => 8,
+        }
+    }
+
+    /// Whether the entity is spawned with the `SpawnEntity` packet,
+    /// only experience orbs still have their own packet
+    pub fn uses_spawn_entity(&self) -> bool {
+        *self != Self::ExperienceOrb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntityType;
+
+    #[test]
+    fn test_entity_type_ids() {
+        assert_eq!(EntityType::Allay.id(), 0);
+        assert_eq!(EntityType::Pig.id(), 77);
+        assert_eq!(EntityType::Player.id(), 128);
+        assert_eq!(EntityType::default(), EntityType::Pig);
+        assert_eq!(
+            "minecraft:zombie".parse::<EntityType>().ok(),
+            Some(EntityType::Zombie)
+        );
+        assert_eq!(EntityType::Player.tracking_range(), 32);
+    }
+}
@@ -0,0 +1,18 @@
+use std::{error::Error, fmt::Display};
+
+use zr_data_type::error::MalformedError;
+
+#[derive(Debug)]
+pub enum RegistryError {
+    Malformed(MalformedError),
+    UnknownEntry(String),
+    UnknownProperty { name: String, value: String },
+}
+
+impl Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl Error for RegistryError {}
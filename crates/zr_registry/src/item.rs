@@ -0,0 +1,37 @@
+//! Item registry generated at build time from the `minecraft:item` entry of the
+//! vanilla `registries.json` report (see `data/registries.json`), the stack sizes
+//! from the `items.json` report.
+
+include!(concat!(env!("OUT_DIR"), "/item.rs"));
+include!(concat!(env!("OUT_DIR"), "/max_stack_size.rs"));
+
+registry!(Item, |item| ITEMS[*item as usize]);
+
+impl Item {
+    pub fn is_air(&self) -> bool {
+        *self == Self::Air
+    }
+
+    /// Stack size of the item prototype, can be overridden by the `max_stack_size` component
+    pub fn max_stack_size(&self) -> u8 {
+        MAX_STACK_SIZES[*self as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zr_binary::binary::Binary;
+
+    use super::Item;
+
+    #[test]
+    fn test_item_ids() {
+        assert_eq!(Item::Air.id(), 0);
+        assert_eq!(Item::GrassBlock.id(), 27);
+        assert_eq!(Item::from_id(36), Some(Item::OakPlanks));
+        assert_eq!("minecraft:dirt".parse::<Item>().ok(), Some(Item::Dirt));
+        assert_eq!(Item::from_binary(Item::Cobblestone.to_binary()).ok(), Some(Item::Cobblestone));
+        assert_eq!(Item::ALL.len(), super::MAX_STACK_SIZES.len());
+        assert_eq!(Item::Dirt.max_stack_size(), 64);
+    }
+}
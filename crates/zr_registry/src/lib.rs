@@ -0,0 +1,75 @@
+/// Common accessors of a generated registry enum, `$name` gives the full name of an entry.
+macro_rules! registry {
+    ($ty: ident, $name: expr) => {
+        impl $ty {
+            /// Protocol id
+            pub fn id(&self) -> u16 {
+                *self as u16
+            }
+
+            pub fn from_id(id: u16) -> Option<Self> {
+                Self::ALL.get(id as usize).copied()
+            }
+
+            /// Full name, e.g. `minecraft:stone`
+            pub fn name(&self) -> &'static str {
+                let name: fn(&Self) -> &'static str = $name;
+                name(self)
+            }
+
+            pub fn identifier(&self) -> zr_data_type::string::Identifier {
+                self.name()
+                    .parse()
+                    .expect("generated name is a valid identifier")
+            }
+
+            pub fn from_identifier(identifier: &zr_data_type::string::Identifier) -> Option<Self> {
+                static BY_NAME: std::sync::OnceLock<std::collections::HashMap<&'static str, $ty>> =
+                    std::sync::OnceLock::new();
+                BY_NAME
+                    .get_or_init(|| Self::ALL.iter().map(|entry| (entry.name(), *entry)).collect())
+                    .get(identifier.to_string().as_str())
+                    .copied()
+            }
+        }
+
+        impl std::str::FromStr for $ty {
+            type Err = $crate::error::RegistryError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let identifier = s
+                    .parse::<zr_data_type::string::Identifier>()
+                    .map_err($crate::error::RegistryError::Malformed)?;
+                Self::from_identifier(&identifier)
+                    .ok_or_else(|| $crate::error::RegistryError::UnknownEntry(s.to_string()))
+            }
+        }
+
+        impl zr_binary::binary::Binary for $ty {
+            fn binary_len(&self) -> usize {
+                zr_binary::varint::VarInt::<i32>::from(self.id()).binary_len()
+            }
+
+            fn to_binary(self) -> Vec<u8> {
+                zr_binary::varint::VarInt::<i32>::from(self.id()).to_binary()
+            }
+
+            fn from_binary(bin: Vec<u8>) -> zr_binary::error::Result<Self>
+            where
+                Self: Sized,
+            {
+                let id = zr_binary::varint::VarInt::<i32>::from_binary(bin)?;
+                u16::try_from(id.0)
+                    .ok()
+                    .and_then(Self::from_id)
+                    .ok_or(zr_binary::error::BinaryError::FormatError)
+            }
+        }
+    };
+}
+
+pub mod block;
+pub mod damage_type;
+pub mod entity_type;
+pub mod error;
+pub mod item;
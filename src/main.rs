@@ -1,39 +1,228 @@
 #![doc = include_str!("../README.md")]
 use std::{
-    net::TcpListener,
+    io::ErrorKind,
+    net::{SocketAddr, TcpListener, TcpStream},
+    path::Path,
+    process::ExitCode,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use zr_core::handler::Handler;
+use zr_core::{
+    access::AccessLists,
+    command::vanilla,
+    console::{self, Console},
+    handler::Handler,
+    permission::Permissions,
+    save::{WorldData, WorldSave, WORLD_DIRECTORY},
+    settings::{ForwardingMode, ServerSettings},
+    supervisor::{Supervisor, SupervisorHandle},
+    tick::TickLoop,
+    user_cache::UserCache,
+};
 use zr_network::client::{
     client::{Client, COMPRESSION_ACTIVE},
     manager::ClientManager,
+    proxy::ProxyProtocol,
+};
+use zr_protocol::{
+    handler::{forwarding::Forwarding, protocol_handler::ProtocolHandler, status},
+    packet::config::registry::chat_type::decoration::TextComponent,
 };
-use zr_protocol::handler::protocol_handler::ProtocolHandler;
 
 const SERVER_ADDRESS: &str = "127.0.0.1:25565";
+/// Commands typed in the console are kept there
+const CONSOLE_HISTORY: &str = ".console_history";
+/// `server.json`, `ops.json`, `permissions.json`, the ban lists, the whitelist, `usercache.json`,
+/// `server-icon.png` and the world are read from there
+const SERVER_DIRECTORY: &str = ".";
+/// Pause between two checks of the listener while no client connects
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(50);
 
-pub fn main() {
+/// Read the PROXY protocol header of a trusted proxy then hand the client to the server
+fn connect(
+    stream: TcpStream,
+    peer: SocketAddr,
+    proxy: &ProxyProtocol,
+    client_manager: Arc<Mutex<ClientManager>>,
+    protocol_handler: Arc<Mutex<ProtocolHandler>>,
+    shutdown: SupervisorHandle,
+) {
+    let client = match Client::new_with_proxy(stream, COMPRESSION_ACTIVE, proxy) {
+        Ok(client) => client,
+        Err(err) => {
+            console::error(format!("client refused from {peer}: {err}"));
+            return;
+        }
+    };
+    if shutdown.is_shutting_down() {
+        return;
+    }
+    let mut protocol_handler = protocol_handler.lock().unwrap();
+    if let Some(id) = ClientManager::add_client(client_manager, client.try_clone().unwrap()) {
+        let address = client.address().unwrap_or(peer);
+        console::log(format!("client accepted [{id:08x}] from {address}"));
+        protocol_handler.add_client(id, client);
+    } else {
+        console::error("server is full");
+    }
+}
+
+/// Accept the clients until the server shuts down
+fn accept(
+    listener: TcpListener,
+    proxy: ProxyProtocol,
+    client_manager: Arc<Mutex<ClientManager>>,
+    protocol_handler: Arc<Mutex<ProtocolHandler>>,
+    shutdown: SupervisorHandle,
+) {
+    console::log(format!("Listen on {SERVER_ADDRESS}"));
+    let proxy = Arc::new(proxy);
+    while !shutdown.is_shutting_down() {
+        match listener.accept() {
+            Ok((stream, peer)) => {
+                // a proxy may be slow to send its header, the others keep connecting meanwhile
+                let proxy = proxy.clone();
+                let client_manager = client_manager.clone();
+                let protocol_handler = protocol_handler.clone();
+                let shutdown = shutdown.clone();
+                std::thread::spawn(move || {
+                    connect(
+                        stream,
+                        peer,
+                        &proxy,
+                        client_manager,
+                        protocol_handler,
+                        shutdown,
+                    )
+                });
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_INTERVAL),
+            Err(err) => console::error(format!("{err:?}")),
+        }
+    }
+}
+
+pub fn main() -> ExitCode {
     let listener = TcpListener::bind(SERVER_ADDRESS).expect("Cannot listen");
+    listener
+        .set_nonblocking(true)
+        .expect("Cannot set listener non blocking");
     let (client_manager, receiver) = ClientManager::new(20, 1);
     let client_manager = Arc::new(Mutex::new(client_manager));
-    let protocol_handler = Arc::new(Mutex::new(ProtocolHandler::new(receiver)));
-    Handler::handle(protocol_handler.clone());
-    println!("Listen on {SERVER_ADDRESS}");
-    loop {
-        for stream in listener.incoming() {
-            if let Ok(stream) = stream {
-                let mut protocol_handler = protocol_handler.lock().unwrap();
-                let client = Client::new_with_opt(stream, COMPRESSION_ACTIVE).unwrap();
-                if let Some(id) =
-                    ClientManager::add_client(client_manager.clone(), client.try_clone().unwrap())
-                {
-                    println!("client accepted [{id:08x}]");
-                    protocol_handler.add_client(id, client);
-                } else {
-                    eprint!("server is full");
-                }
+    let settings = ServerSettings::load(SERVER_DIRECTORY).unwrap_or_else(|err| {
+        console::error(format!("Cannot load the settings: {err}"));
+        ServerSettings::default()
+    });
+    let proxy = ProxyProtocol::new(settings.trusted_proxies.clone());
+    if settings.forwarding.mode == ForwardingMode::Velocity && settings.forwarding.secret.is_empty()
+    {
+        console::error("The Velocity forwarding has no secret, anyone can forge the players");
+    }
+    let permissions = Permissions::load(SERVER_DIRECTORY).unwrap_or_else(|err| {
+        console::error(format!("Cannot load the permissions: {err}"));
+        Permissions::new()
+    });
+    let access = AccessLists::load(SERVER_DIRECTORY).unwrap_or_else(|err| {
+        console::error(format!(
+            "Cannot load the ban lists and the whitelist: {err}"
+        ));
+        AccessLists::new()
+    });
+    let users = UserCache::load(SERVER_DIRECTORY).unwrap_or_else(|err| {
+        console::error(format!("Cannot load the user cache: {err}"));
+        UserCache::new()
+    });
+    let favicon = status::load_icon(SERVER_DIRECTORY).unwrap_or_else(|err| {
+        console::error(format!("Cannot load the server icon: {err}"));
+        None
+    });
+    let save = WorldSave::new(Path::new(SERVER_DIRECTORY).join(WORLD_DIRECTORY));
+    let world = save.load_world().unwrap_or_else(|err| {
+        console::error(format!("Cannot load the world: {err}"));
+        WorldData::default()
+    });
+    // a new world keeps its seed even if the server does not stop cleanly
+    if let Err(err) = save.save_world(&world) {
+        console::error(format!("Cannot save the world: {err}"));
+    }
+    let protocol_handler = Arc::new(Mutex::new(
+        ProtocolHandler::new(receiver)
+            .with_permissions(permissions)
+            .with_access(access)
+            .with_user_cache(users)
+            .with_favicon(favicon)
+            .with_world(world)
+            .with_save(save)
+            .with_forwarding(Forwarding::from(&settings.forwarding))
+            .with_transfers(settings.accept_transfers)
+            .with_secure_chat(settings.enforce_secure_chat)
+            .with_tab_list(
+                TextComponent::text(&settings.tab_list_header),
+                TextComponent::text(&settings.tab_list_footer),
+            )
+            .with_sample_size(settings.status_sample_size),
+    ));
+    let handler_thread = Handler::handle(protocol_handler.clone());
+    let tick_loop = Arc::new(TickLoop::new());
+    let tick_thread = tick_loop.run(protocol_handler.clone());
+
+    let mut supervisor = Supervisor::new();
+    let network = client_manager.clone();
+    let protocol = protocol_handler.clone();
+    let ticks = tick_loop.clone();
+    supervisor
+        .on_shutdown("network", move || {
+            if let Ok(mut network) = network.lock() {
+                network.shutdown();
+            }
+        })
+        .on_shutdown("protocol", move || {
+            if let Ok(mut protocol) = protocol.lock() {
+                protocol.shutdown(&settings.shutdown_message);
             }
+        })
+        .on_shutdown("tick loop", move || ticks.stop());
+    let shutdown = supervisor.handle();
+
+    let signal = shutdown.clone();
+    ctrlc::set_handler(move || signal.shutdown()).expect("Cannot handle SIGINT and SIGTERM");
+    let commands = protocol_handler.lock().unwrap().commands();
+    let server = protocol_handler.lock().unwrap().server();
+    vanilla::register(
+        &mut commands.lock().unwrap(),
+        Arc::new(
+            server
+                .with_shutdown(shutdown.clone())
+                .with_tick_stats(tick_loop.stats()),
+        ),
+    );
+    Console::new().with_history(CONSOLE_HISTORY).run(commands);
+
+    let network_thread = {
+        let shutdown = shutdown.clone();
+        let protocol_handler = protocol_handler.clone();
+        std::thread::spawn(move || {
+            accept(listener, proxy, client_manager, protocol_handler, shutdown)
+        })
+    };
+    supervisor
+        .watch("network", network_thread)
+        .watch("protocol", handler_thread)
+        .watch("tick loop", tick_thread);
+
+    match supervisor.run().join() {
+        Ok(Ok(())) => {
+            console::log("Server stopped");
+            ExitCode::SUCCESS
+        }
+        Ok(Err(critical)) => {
+            console::error(format!("Server stopped: {critical}"));
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            console::error(format!("Server stopped: supervisor crashed {err:?}"));
+            ExitCode::FAILURE
         }
     }
 }